@@ -1,9 +1,34 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
 use chromiumoxide::browser::{Browser, BrowserConfig};
+use chromiumoxide::cdp::browser_protocol::page::{
+    EventJavascriptDialogOpening, HandleJavaScriptDialogParams,
+};
 use chromiumoxide::Page;
 use futures::StreamExt;
 
+/// A pending `alert()`/`confirm()`/`prompt()` dialog, cached from CDP's
+/// `Page.javascriptDialogOpening` event until resolved via
+/// `Page.handleJavaScriptDialog`.
+#[derive(Debug, Clone)]
+pub struct PendingDialog {
+    pub message: String,
+    pub kind: String,
+}
+
 pub struct BrowserSession {
+    // Kept alongside `page` so the browser process stays alive for as long
+    // as the session does — if this were dropped while the page is still in
+    // use elsewhere (e.g. a daemon-held session outliving this function),
+    // the underlying connection would go away with it.
+    pub browser: Browser,
     pub page: Page,
+    /// The `--session` name this session was created under, so teardown
+    /// knows which `~/.mini-claw/cookies/<name>.json` to save to.
+    pub session_name: String,
+    pending_dialog: Arc<Mutex<Option<PendingDialog>>>,
+    auto_dismiss_dialogs: Arc<AtomicBool>,
 }
 
 impl BrowserSession {
@@ -19,9 +44,46 @@ impl BrowserSession {
             .and_then(|v| v.into_value::<String>().ok())
             .unwrap_or_default()
     }
+
+    pub fn pending_dialog(&self) -> Option<PendingDialog> {
+        self.pending_dialog.lock().unwrap().clone()
+    }
+
+    pub fn set_auto_dismiss_dialogs(&self, enabled: bool) {
+        self.auto_dismiss_dialogs.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Resolves the pending dialog, accepting (optionally with `text` for a
+    /// `prompt()`) or dismissing it.
+    pub async fn resolve_dialog(
+        &self,
+        accept: bool,
+        text: Option<String>,
+    ) -> anyhow::Result<PendingDialog> {
+        let dialog = self
+            .pending_dialog
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("No dialog is currently open"))?;
+
+        let mut builder = HandleJavaScriptDialogParams::builder().accept(accept);
+        if let Some(text) = text {
+            builder = builder.prompt_text(text);
+        }
+        self.page
+            .execute(
+                builder
+                    .build()
+                    .map_err(|e| anyhow::anyhow!("Invalid dialog params: {e}"))?,
+            )
+            .await?;
+
+        Ok(dialog)
+    }
 }
 
-pub async fn get_browser() -> anyhow::Result<BrowserSession> {
+pub async fn get_browser(session_name: &str) -> anyhow::Result<BrowserSession> {
     // For CLI usage, create a fresh browser each time
     let config = BrowserConfig::builder()
         .no_sandbox()
@@ -43,15 +105,54 @@ pub async fn get_browser() -> anyhow::Result<BrowserSession> {
 
     let page = browser.new_page("about:blank").await?;
 
-    // Store for later cleanup
-    // Note: for the CLI, each invocation is a new process, so we don't need
-    // to worry about browser reuse across commands
-    Ok(BrowserSession { page })
+    let pending_dialog: Arc<Mutex<Option<PendingDialog>>> = Arc::new(Mutex::new(None));
+    let auto_dismiss_dialogs = Arc::new(AtomicBool::new(false));
+
+    let mut dialog_events = page.event_listener::<EventJavascriptDialogOpening>().await?;
+    let dialog_state = pending_dialog.clone();
+    let dialog_page = page.clone();
+    let dialog_auto_dismiss = auto_dismiss_dialogs.clone();
+    tokio::spawn(async move {
+        while let Some(event) = dialog_events.next().await {
+            if dialog_auto_dismiss.load(Ordering::SeqCst) {
+                let _ = dialog_page
+                    .execute(HandleJavaScriptDialogParams::builder().accept(false).build().unwrap())
+                    .await;
+                continue;
+            }
+            *dialog_state.lock().unwrap() = Some(PendingDialog {
+                message: event.message.clone(),
+                kind: format!("{:?}", event.r#type),
+            });
+        }
+    });
+
+    let session = BrowserSession {
+        browser,
+        page,
+        session_name: session_name.to_string(),
+        pending_dialog,
+        auto_dismiss_dialogs,
+    };
+
+    // Restore any cookies saved by a previous session of this name before
+    // the caller's first real navigation sees the destination domain.
+    if let Err(e) = crate::commands::cookie_store::load_cookies(&session, session_name).await {
+        eprintln!("Failed to restore cookies for session '{session_name}': {e}");
+    }
+
+    Ok(session)
 }
 
-pub async fn close_browser() {
-    // Browser will be dropped when the process exits
-    // For explicit cleanup, we could store the browser handle
+pub async fn close_browser(session: &BrowserSession) {
+    if let Err(e) =
+        crate::commands::cookie_store::save_cookies(session, &session.session_name).await
+    {
+        eprintln!(
+            "Failed to save cookies for session '{}': {e}",
+            session.session_name
+        );
+    }
 }
 
 pub async fn get_status() -> serde_json::Value {
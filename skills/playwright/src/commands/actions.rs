@@ -0,0 +1,235 @@
+use chromiumoxide::cdp::browser_protocol::input::{
+    DispatchKeyEventParams, DispatchKeyEventType, DispatchMouseEventParams, DispatchMouseEventType,
+    MouseButton as CdpMouseButton,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::browser::BrowserSession;
+
+const MODIFIER_ALT: i64 = 1;
+const MODIFIER_CTRL: i64 = 2;
+const MODIFIER_META: i64 = 4;
+const MODIFIER_SHIFT: i64 = 8;
+
+fn modifier_bit(key: &str) -> i64 {
+    match key {
+        "Alt" | "AltLeft" | "AltRight" => MODIFIER_ALT,
+        "Control" | "ControlLeft" | "ControlRight" => MODIFIER_CTRL,
+        "Meta" | "MetaLeft" | "MetaRight" | "Command" => MODIFIER_META,
+        "Shift" | "ShiftLeft" | "ShiftRight" => MODIFIER_SHIFT,
+        _ => 0,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PointerButton {
+    #[default]
+    Left,
+    Middle,
+    Right,
+}
+
+impl PointerButton {
+    fn cdp(self) -> CdpMouseButton {
+        match self {
+            Self::Left => CdpMouseButton::Left,
+            Self::Middle => CdpMouseButton::Middle,
+            Self::Right => CdpMouseButton::Right,
+        }
+    }
+}
+
+/// One tick action for a single input source, modeled on WebDriver's
+/// action primitives.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InputAction {
+    PointerMove {
+        x: f64,
+        y: f64,
+        #[serde(default)]
+        duration: u64,
+    },
+    PointerDown {
+        #[serde(default)]
+        button: PointerButton,
+    },
+    PointerUp {
+        #[serde(default)]
+        button: PointerButton,
+    },
+    KeyDown {
+        key: String,
+    },
+    KeyUp {
+        key: String,
+    },
+    Pause {
+        #[serde(default)]
+        duration: u64,
+    },
+}
+
+/// An input device and the ordered actions it performs, one per tick.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ActionSource {
+    Key { actions: Vec<InputAction> },
+    Pointer { actions: Vec<InputAction> },
+}
+
+impl ActionSource {
+    fn actions(&self) -> &[InputAction] {
+        match self {
+            Self::Key { actions } | Self::Pointer { actions } => actions,
+        }
+    }
+}
+
+#[derive(Default)]
+struct InputState {
+    modifiers: i64,
+    pointer_x: f64,
+    pointer_y: f64,
+}
+
+/// Replays `sources` as WebDriver-style synchronized ticks: at tick *i*,
+/// every source's *i*-th action fires before any source moves on to tick
+/// *i+1*. Everything goes through CDP `Input.dispatchMouseEvent` /
+/// `Input.dispatchKeyEvent` rather than JS-side event dispatch, so held
+/// modifiers, drags, and hover state are all genuine rather than simulated.
+pub async fn perform(
+    session: &mut BrowserSession,
+    sources: Vec<ActionSource>,
+) -> anyhow::Result<serde_json::Value> {
+    let ticks = sources.iter().map(|s| s.actions().len()).max().unwrap_or(0);
+    let mut state = InputState::default();
+
+    for tick in 0..ticks {
+        for source in &sources {
+            if let Some(action) = source.actions().get(tick) {
+                dispatch_one(session, &mut state, action).await?;
+            }
+        }
+    }
+
+    Ok(serde_json::json!({
+        "url": session.url().await,
+    }))
+}
+
+async fn dispatch_one(
+    session: &mut BrowserSession,
+    state: &mut InputState,
+    action: &InputAction,
+) -> anyhow::Result<()> {
+    match action {
+        InputAction::PointerMove { x, y, duration } => {
+            if *duration == 0 {
+                move_to(session, state, *x, *y).await?;
+            } else {
+                let steps = (*duration / 16).max(1);
+                let (start_x, start_y) = (state.pointer_x, state.pointer_y);
+                for step in 1..=steps {
+                    let t = step as f64 / steps as f64;
+                    move_to(
+                        session,
+                        state,
+                        start_x + (x - start_x) * t,
+                        start_y + (y - start_y) * t,
+                    )
+                    .await?;
+                    tokio::time::sleep(tokio::time::Duration::from_millis(duration / steps)).await;
+                }
+            }
+        }
+        InputAction::PointerDown { button } => {
+            session
+                .page
+                .execute(
+                    DispatchMouseEventParams::builder()
+                        .r#type(DispatchMouseEventType::MousePressed)
+                        .x(state.pointer_x)
+                        .y(state.pointer_y)
+                        .button(button.cdp())
+                        .click_count(1)
+                        .modifiers(state.modifiers)
+                        .build()
+                        .map_err(|e| anyhow::anyhow!("Invalid mouse event: {e}"))?,
+                )
+                .await?;
+        }
+        InputAction::PointerUp { button } => {
+            session
+                .page
+                .execute(
+                    DispatchMouseEventParams::builder()
+                        .r#type(DispatchMouseEventType::MouseReleased)
+                        .x(state.pointer_x)
+                        .y(state.pointer_y)
+                        .button(button.cdp())
+                        .click_count(1)
+                        .modifiers(state.modifiers)
+                        .build()
+                        .map_err(|e| anyhow::anyhow!("Invalid mouse event: {e}"))?,
+                )
+                .await?;
+        }
+        InputAction::KeyDown { key } => {
+            state.modifiers |= modifier_bit(key);
+            session
+                .page
+                .execute(
+                    DispatchKeyEventParams::builder()
+                        .r#type(DispatchKeyEventType::KeyDown)
+                        .key(key.clone())
+                        .modifiers(state.modifiers)
+                        .build()
+                        .map_err(|e| anyhow::anyhow!("Invalid key event: {e}"))?,
+                )
+                .await?;
+        }
+        InputAction::KeyUp { key } => {
+            session
+                .page
+                .execute(
+                    DispatchKeyEventParams::builder()
+                        .r#type(DispatchKeyEventType::KeyUp)
+                        .key(key.clone())
+                        .modifiers(state.modifiers)
+                        .build()
+                        .map_err(|e| anyhow::anyhow!("Invalid key event: {e}"))?,
+                )
+                .await?;
+            state.modifiers &= !modifier_bit(key);
+        }
+        InputAction::Pause { duration } => {
+            tokio::time::sleep(tokio::time::Duration::from_millis(*duration)).await;
+        }
+    }
+    Ok(())
+}
+
+async fn move_to(
+    session: &mut BrowserSession,
+    state: &mut InputState,
+    x: f64,
+    y: f64,
+) -> anyhow::Result<()> {
+    session
+        .page
+        .execute(
+            DispatchMouseEventParams::builder()
+                .r#type(DispatchMouseEventType::MouseMoved)
+                .x(x)
+                .y(y)
+                .modifiers(state.modifiers)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Invalid mouse event: {e}"))?,
+        )
+        .await?;
+    state.pointer_x = x;
+    state.pointer_y = y;
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::browser::BrowserSession;
+use crate::error::PwError;
 
 pub async fn content(
     session: &mut BrowserSession,
@@ -10,7 +11,8 @@ pub async fn content(
         session
             .page
             .evaluate("document.body.innerText")
-            .await?
+            .await
+            .map_err(|e| PwError::JavascriptError(e.to_string()))?
             .into_value::<String>()
             .unwrap_or_default()
     };
@@ -29,7 +31,7 @@ pub async fn text(
         .page
         .find_element(selector)
         .await
-        .map_err(|e| anyhow::anyhow!("Element not found: {e}"))?;
+        .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
 
     let text = element
         .inner_text()
@@ -75,7 +77,8 @@ pub async fn snapshot(
             })()
             "#,
         )
-        .await?
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?
         .into_value::<String>()
         .unwrap_or_default();
 
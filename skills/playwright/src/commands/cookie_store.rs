@@ -0,0 +1,129 @@
+//! Automatic cookie persistence across session lifecycles, as opposed to
+//! `commands::cookies`' user-facing `pw cookies` subcommand. Mirrors the
+//! `~/.mini-claw/<state>.json` per-chat persistence convention the bot side
+//! uses for workspaces: cookies are dumped to
+//! `~/.mini-claw/cookies/<session_name>.json` when a session tears down and
+//! restored from there the next time a session of that name is created, so
+//! a logged-in site survives a daemon restart instead of forcing
+//! re-authentication.
+
+use std::path::PathBuf;
+
+use chromiumoxide::cdp::browser_protocol::network::{
+    CookieParam, CookieSameSite, GetAllCookiesParams, SetCookiesParams,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::browser::BrowserSession;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedCookie {
+    name: String,
+    value: String,
+    domain: String,
+    path: String,
+    expires: f64,
+    http_only: bool,
+    secure: bool,
+    same_site: Option<String>,
+}
+
+fn same_site_to_str(s: &CookieSameSite) -> String {
+    format!("{s:?}").to_lowercase()
+}
+
+fn parse_same_site(s: &str) -> Option<CookieSameSite> {
+    match s.to_lowercase().as_str() {
+        "strict" => Some(CookieSameSite::Strict),
+        "lax" => Some(CookieSameSite::Lax),
+        "none" => Some(CookieSameSite::None),
+        _ => None,
+    }
+}
+
+fn cookies_path(session_name: &str) -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".mini-claw")
+        .join("cookies")
+        .join(format!("{session_name}.json"))
+}
+
+/// Dumps every cookie the browser currently holds (via CDP
+/// `Network.getAllCookies`, not just the active page's) to
+/// `~/.mini-claw/cookies/<session_name>.json`. Called on session teardown
+/// (`pw close`, daemon eviction, or the idle reaper).
+pub async fn save_cookies(session: &BrowserSession, session_name: &str) -> anyhow::Result<()> {
+    let cookies = session
+        .page
+        .execute(GetAllCookiesParams::default())
+        .await?
+        .result
+        .cookies
+        .iter()
+        .map(|c| SavedCookie {
+            name: c.name.clone(),
+            value: c.value.clone(),
+            domain: c.domain.clone(),
+            path: c.path.clone(),
+            expires: c.expires,
+            http_only: c.http_only,
+            secure: c.secure,
+            same_site: c.same_site.as_ref().map(same_site_to_str),
+        })
+        .collect::<Vec<_>>();
+
+    let path = cookies_path(session_name);
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(&path, serde_json::to_string_pretty(&cookies)?).await?;
+    Ok(())
+}
+
+/// Restores cookies previously saved by [`save_cookies`] for `session_name`
+/// via CDP `Network.setCookies`, if any were saved. A no-op when nothing
+/// has been saved yet. Call on session creation, before the first
+/// navigation, so the destination domain sees them on its very first
+/// request.
+pub async fn load_cookies(session: &BrowserSession, session_name: &str) -> anyhow::Result<()> {
+    let path = cookies_path(session_name);
+    let Ok(data) = tokio::fs::read_to_string(&path).await else {
+        return Ok(());
+    };
+    let saved: Vec<SavedCookie> = serde_json::from_str(&data)?;
+    if saved.is_empty() {
+        return Ok(());
+    }
+
+    let mut params = Vec::with_capacity(saved.len());
+    for cookie in saved {
+        let mut builder = CookieParam::builder()
+            .name(cookie.name)
+            .value(cookie.value)
+            .domain(cookie.domain)
+            .path(cookie.path)
+            .expires(cookie.expires)
+            .http_only(cookie.http_only)
+            .secure(cookie.secure);
+        if let Some(same_site) = cookie.same_site.as_deref().and_then(parse_same_site) {
+            builder = builder.same_site(same_site);
+        }
+        params.push(
+            builder
+                .build()
+                .map_err(|e| anyhow::anyhow!("Invalid saved cookie: {e}"))?,
+        );
+    }
+
+    session
+        .page
+        .execute(
+            SetCookiesParams::builder()
+                .cookies(params)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Invalid cookie params: {e}"))?,
+        )
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use chromiumoxide::cdp::browser_protocol::network::{
+    ClearBrowserCookiesParams, Cookie, CookieSameSite, DeleteCookiesParams, GetCookiesParams,
+    SetCookieParams,
+};
+
+use crate::browser::BrowserSession;
+
+fn cookie_to_json(cookie: &Cookie) -> serde_json::Value {
+    serde_json::json!({
+        "name": cookie.name,
+        "value": cookie.value,
+        "domain": cookie.domain,
+        "path": cookie.path,
+        "expires": cookie.expires,
+        "secure": cookie.secure,
+        "httpOnly": cookie.http_only,
+        "sameSite": cookie.same_site.as_ref().map(|s| format!("{s:?}")),
+    })
+}
+
+fn parse_same_site(s: &str) -> anyhow::Result<CookieSameSite> {
+    match s.to_lowercase().as_str() {
+        "strict" => Ok(CookieSameSite::Strict),
+        "lax" => Ok(CookieSameSite::Lax),
+        "none" => Ok(CookieSameSite::None),
+        other => Err(anyhow::anyhow!("Unsupported sameSite value: {other}")),
+    }
+}
+
+pub async fn list(session: &mut BrowserSession) -> anyhow::Result<serde_json::Value> {
+    let cookies = session
+        .page
+        .execute(GetCookiesParams::default())
+        .await?
+        .result
+        .cookies;
+
+    Ok(serde_json::json!({
+        "cookies": cookies.iter().map(cookie_to_json).collect::<Vec<_>>(),
+        "url": session.url().await,
+    }))
+}
+
+pub async fn get(session: &mut BrowserSession, name: &str) -> anyhow::Result<serde_json::Value> {
+    let cookies = session
+        .page
+        .execute(GetCookiesParams::default())
+        .await?
+        .result
+        .cookies;
+
+    let cookie = cookies
+        .iter()
+        .find(|c| c.name == name)
+        .ok_or_else(|| anyhow::anyhow!("No cookie named '{name}'"))?;
+
+    Ok(serde_json::json!({
+        "cookie": cookie_to_json(cookie),
+        "url": session.url().await,
+    }))
+}
+
+/// Options for adding a cookie, mirroring WebDriver's `AddCookie`. When
+/// `domain` is unset, the cookie is scoped to the current page's URL.
+#[derive(Debug, Clone, Default)]
+pub struct SetCookieOptions {
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    pub expires: Option<f64>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<String>,
+}
+
+pub async fn set(
+    session: &mut BrowserSession,
+    name: &str,
+    value: &str,
+    options: SetCookieOptions,
+) -> anyhow::Result<serde_json::Value> {
+    let mut builder = SetCookieParams::builder()
+        .name(name)
+        .value(value)
+        .secure(options.secure)
+        .http_only(options.http_only);
+
+    builder = match &options.domain {
+        Some(domain) => builder.domain(domain.clone()),
+        None => builder.url(session.url().await),
+    };
+    if let Some(path) = &options.path {
+        builder = builder.path(path.clone());
+    }
+    if let Some(expires) = options.expires {
+        builder = builder.expires(expires);
+    }
+    if let Some(same_site) = &options.same_site {
+        builder = builder.same_site(parse_same_site(same_site)?);
+    }
+
+    let params = builder
+        .build()
+        .map_err(|e| anyhow::anyhow!("Invalid cookie params: {e}"))?;
+    session.page.execute(params).await?;
+
+    Ok(serde_json::json!({
+        "name": name,
+        "url": session.url().await,
+    }))
+}
+
+pub async fn delete(session: &mut BrowserSession, name: &str) -> anyhow::Result<serde_json::Value> {
+    let url = session.url().await;
+    let params = DeleteCookiesParams::builder()
+        .name(name)
+        .url(url)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Invalid cookie params: {e}"))?;
+    session.page.execute(params).await?;
+
+    Ok(serde_json::json!({
+        "name": name,
+        "url": session.url().await,
+    }))
+}
+
+pub async fn clear(session: &mut BrowserSession) -> anyhow::Result<serde_json::Value> {
+    session
+        .page
+        .execute(ClearBrowserCookiesParams::default())
+        .await?;
+
+    Ok(serde_json::json!({
+        "url": session.url().await,
+    }))
+}
@@ -0,0 +1,42 @@
+use crate::browser::BrowserSession;
+
+pub async fn accept(
+    session: &mut BrowserSession,
+    text: Option<String>,
+) -> anyhow::Result<serde_json::Value> {
+    let dialog = session.resolve_dialog(true, text).await?;
+    Ok(serde_json::json!({
+        "message": dialog.message,
+        "kind": dialog.kind,
+        "url": session.url().await,
+    }))
+}
+
+pub async fn dismiss(session: &mut BrowserSession) -> anyhow::Result<serde_json::Value> {
+    let dialog = session.resolve_dialog(false, None).await?;
+    Ok(serde_json::json!({
+        "message": dialog.message,
+        "kind": dialog.kind,
+        "url": session.url().await,
+    }))
+}
+
+pub async fn text(session: &mut BrowserSession) -> anyhow::Result<serde_json::Value> {
+    let dialog = session
+        .pending_dialog()
+        .ok_or_else(|| anyhow::anyhow!("No dialog is currently open"))?;
+    Ok(serde_json::json!({
+        "message": dialog.message,
+        "kind": dialog.kind,
+    }))
+}
+
+/// Toggles auto-dismissing dialogs the moment they open, so a long-running
+/// `fetch` against an untrusted page doesn't hang on an unexpected alert.
+pub async fn set_auto_dismiss(
+    session: &mut BrowserSession,
+    enabled: bool,
+) -> anyhow::Result<serde_json::Value> {
+    session.set_auto_dismiss_dialogs(enabled);
+    Ok(serde_json::json!({ "auto_dismiss": enabled }))
+}
@@ -0,0 +1,93 @@
+//! Structured scraping: compiles a JSON field schema (or a `<table>`
+//! selector) into a single injected script, rather than leaving callers to
+//! hand-roll `Eval`/`Query` calls per field.
+
+use crate::browser::BrowserSession;
+use crate::error::PwError;
+
+/// Runs `schema` (a JSON object mapping output key to `{"selector": ...,
+/// "attr": ..., "all": bool}`) against the page in one round trip and
+/// returns a JSON object with the same keys.
+///
+/// `attr` is `"text"` for `innerText`, `"html"` for `innerHTML`, or any
+/// other string to read that DOM attribute. `all` (default `false`) returns
+/// every match as an array instead of just the first.
+pub async fn extract_fields(
+    session: &mut BrowserSession,
+    schema: &serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    if !schema.is_object() {
+        anyhow::bail!("extract schema must be a JSON object");
+    }
+
+    let schema_json = serde_json::to_string(schema)?;
+    let script = format!(
+        r#"(function(schema) {{
+            const readField = (el, attr) => {{
+                if (attr === 'text') return el.innerText;
+                if (attr === 'html') return el.innerHTML;
+                return el.getAttribute(attr);
+            }};
+            const result = {{}};
+            for (const key of Object.keys(schema)) {{
+                const spec = schema[key];
+                const nodes = Array.from(document.querySelectorAll(spec.selector));
+                if (spec.all) {{
+                    result[key] = nodes.map((el) => readField(el, spec.attr));
+                }} else {{
+                    result[key] = nodes.length > 0 ? readField(nodes[0], spec.attr) : null;
+                }}
+            }}
+            return result;
+        }})({schema_json})"#
+    );
+
+    session
+        .page
+        .evaluate(script)
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?
+        .into_value::<serde_json::Value>()
+        .map_err(|e| PwError::JavascriptError(e.to_string()).into())
+}
+
+/// Converts the HTML `<table>` matching `selector` into an array of row
+/// objects keyed by the first row's header cells, so agents don't have to
+/// parse raw `<table>` HTML themselves.
+pub async fn extract_table(
+    session: &mut BrowserSession,
+    selector: &str,
+) -> anyhow::Result<serde_json::Value> {
+    let selector_json = serde_json::to_string(selector)?;
+    let script = format!(
+        r#"(function(tableSelector) {{
+            const table = document.querySelector(tableSelector);
+            if (!table) return null;
+            const rows = Array.from(table.querySelectorAll('tr'));
+            if (rows.length === 0) return [];
+            const headers = Array.from(rows[0].querySelectorAll('th, td'))
+                .map((cell, i) => cell.innerText.trim() || ('column_' + i));
+            return rows.slice(1).map((row) => {{
+                const cells = Array.from(row.querySelectorAll('td, th'));
+                const obj = {{}};
+                headers.forEach((header, i) => {{
+                    obj[header] = cells[i] ? cells[i].innerText.trim() : null;
+                }});
+                return obj;
+            }});
+        }})({selector_json})"#
+    );
+
+    let value = session
+        .page
+        .evaluate(script)
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?
+        .into_value::<serde_json::Value>()
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?;
+
+    if value.is_null() {
+        return Err(PwError::NoSuchElement(selector.to_string()).into());
+    }
+    Ok(value)
+}
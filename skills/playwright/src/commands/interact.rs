@@ -1,4 +1,6 @@
 use crate::browser::BrowserSession;
+use crate::commands::actions::{self, ActionSource, InputAction, PointerButton};
+use crate::error::PwError;
 
 pub async fn click(
     session: &mut BrowserSession,
@@ -8,12 +10,29 @@ pub async fn click(
         .page
         .find_element(selector)
         .await
-        .map_err(|e| anyhow::anyhow!("Element not found: {e}"))?;
-    element.click().await?;
+        .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
+    element.scroll_into_view().await?;
+    let rect = element
+        .bounding_box()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read element box model: {e}"))?;
+    let (x, y) = (rect.x + rect.width / 2.0, rect.y + rect.height / 2.0);
 
-    Ok(serde_json::json!({
-        "url": session.url().await,
-    }))
+    actions::perform(
+        session,
+        vec![ActionSource::Pointer {
+            actions: vec![
+                InputAction::PointerMove { x, y, duration: 0 },
+                InputAction::PointerDown {
+                    button: PointerButton::Left,
+                },
+                InputAction::PointerUp {
+                    button: PointerButton::Left,
+                },
+            ],
+        }],
+    )
+    .await
 }
 
 pub async fn type_text(
@@ -25,7 +44,7 @@ pub async fn type_text(
         .page
         .find_element(selector)
         .await
-        .map_err(|e| anyhow::anyhow!("Element not found: {e}"))?;
+        .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
     element.type_str(text).await?;
 
     Ok(serde_json::json!({
@@ -48,9 +67,17 @@ pub async fn fill(
         "#,
         selector.replace('\'', "\\'")
     );
-    session.page.evaluate(js).await?;
+    session
+        .page
+        .evaluate(js)
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?;
 
-    let element = session.page.find_element(selector).await?;
+    let element = session
+        .page
+        .find_element(selector)
+        .await
+        .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
     element.type_str(value).await?;
 
     Ok(serde_json::json!({
@@ -73,7 +100,11 @@ pub async fn select(
         selector.replace('\'', "\\'"),
         value.replace('\'', "\\'")
     );
-    session.page.evaluate(js).await?;
+    session
+        .page
+        .evaluate(js)
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?;
 
     Ok(serde_json::json!({
         "url": session.url().await,
@@ -88,12 +119,24 @@ pub async fn hover(
         .page
         .find_element(selector)
         .await
-        .map_err(|e| anyhow::anyhow!("Element not found: {e}"))?;
+        .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
     element.scroll_into_view().await?;
+    let rect = element
+        .bounding_box()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read element box model: {e}"))?;
 
-    Ok(serde_json::json!({
-        "url": session.url().await,
-    }))
+    actions::perform(
+        session,
+        vec![ActionSource::Pointer {
+            actions: vec![InputAction::PointerMove {
+                x: rect.x + rect.width / 2.0,
+                y: rect.y + rect.height / 2.0,
+                duration: 0,
+            }],
+        }],
+    )
+    .await
 }
 
 pub async fn focus(
@@ -104,7 +147,7 @@ pub async fn focus(
         .page
         .find_element(selector)
         .await
-        .map_err(|e| anyhow::anyhow!("Element not found: {e}"))?;
+        .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
     element.focus().await?;
 
     Ok(serde_json::json!({
@@ -116,15 +159,18 @@ pub async fn press(
     session: &mut BrowserSession,
     key: &str,
 ) -> anyhow::Result<serde_json::Value> {
-    session
-        .page
-        .evaluate(format!(
-            "document.dispatchEvent(new KeyboardEvent('keydown', {{ key: '{}' }}))",
-            key.replace('\'', "\\'")
-        ))
-        .await?;
-
-    Ok(serde_json::json!({
-        "url": session.url().await,
-    }))
+    actions::perform(
+        session,
+        vec![ActionSource::Key {
+            actions: vec![
+                InputAction::KeyDown {
+                    key: key.to_string(),
+                },
+                InputAction::KeyUp {
+                    key: key.to_string(),
+                },
+            ],
+        }],
+    )
+    .await
 }
@@ -0,0 +1,12 @@
+pub mod actions;
+pub mod content;
+pub mod cookie_store;
+pub mod cookies;
+pub mod dialog;
+pub mod extract;
+pub mod interact;
+pub mod navigate;
+pub mod query;
+pub mod screenshot;
+pub mod viewport;
+pub mod wait;
@@ -1,4 +1,5 @@
 use crate::browser::BrowserSession;
+use crate::error::PwError;
 
 fn normalize_url(url: &str) -> String {
     if url.starts_with("http://") || url.starts_with("https://") {
@@ -10,7 +11,11 @@ fn normalize_url(url: &str) -> String {
 
 pub async fn goto(session: &mut BrowserSession, url: &str) -> anyhow::Result<serde_json::Value> {
     let url = normalize_url(url);
-    session.page.goto(&url).await?;
+    session
+        .page
+        .goto(&url)
+        .await
+        .map_err(|e| PwError::NavigationFailed(format!("{url}: {e}")))?;
 
     Ok(serde_json::json!({
         "url": session.url().await,
@@ -0,0 +1,106 @@
+use chromiumoxide::Element;
+
+use crate::browser::BrowserSession;
+use crate::error::PwError;
+
+/// Resolves `name` against `element`, trying (in order) a DOM attribute, a
+/// live JS property, then a computed CSS value — mirroring WebDriver's own
+/// `GetElementAttribute`/`GetElementProperty`/`GetCSSValue` precedence so one
+/// field name can address whichever of the three actually holds the value.
+async fn resolve_field(element: &Element, name: &str) -> anyhow::Result<serde_json::Value> {
+    let escaped_name = name.replace('\'', "\\'");
+    let js = format!(
+        r#"function() {{
+            const name = '{escaped_name}';
+            if (this.hasAttribute(name)) return this.getAttribute(name);
+            if (name in this) return this[name];
+            const value = getComputedStyle(this).getPropertyValue(name);
+            return value === '' ? null : value;
+        }}"#
+    );
+    element
+        .call_js_fn(js, false)
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?
+        .into_value::<serde_json::Value>()
+        .map_err(|e| PwError::JavascriptError(e.to_string()).into())
+}
+
+async fn tag_name(element: &Element) -> anyhow::Result<String> {
+    Ok(element
+        .call_js_fn("function() { return this.tagName.toLowerCase(); }", false)
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?
+        .into_value::<String>()
+        .unwrap_or_default())
+}
+
+async fn describe(element: &Element, fields: &[String]) -> anyhow::Result<serde_json::Value> {
+    let tag_name = tag_name(element).await?;
+    let text = element.inner_text().await?.unwrap_or_default();
+    let rect = element.bounding_box().await.ok();
+
+    let mut attributes = serde_json::Map::new();
+    for field in fields {
+        attributes.insert(field.clone(), resolve_field(element, field).await?);
+    }
+
+    Ok(serde_json::json!({
+        "tag_name": tag_name,
+        "text": text.trim(),
+        "rect": rect.map(|r| serde_json::json!({
+            "x": r.x,
+            "y": r.y,
+            "width": r.width,
+            "height": r.height,
+        })),
+        "attributes": attributes,
+    }))
+}
+
+/// Finds every element matching `selector` (WebDriver's `FindElements`) and
+/// returns each one's text, tag name, bounding rect, and the requested
+/// `fields`, for scraping tables/lists without resorting to ad-hoc `Eval`.
+pub async fn query(
+    session: &mut BrowserSession,
+    selector: &str,
+    fields: &[String],
+) -> anyhow::Result<serde_json::Value> {
+    let elements = session
+        .page
+        .find_elements(selector)
+        .await
+        .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
+
+    let mut results = Vec::with_capacity(elements.len());
+    for element in &elements {
+        results.push(describe(element, fields).await?);
+    }
+
+    Ok(serde_json::json!({
+        "elements": results,
+        "count": results.len(),
+        "url": session.url().await,
+    }))
+}
+
+/// Looks up a single attribute/property/CSS value on the first element
+/// matching `selector`, following the same precedence as [`query`].
+pub async fn attr(
+    session: &mut BrowserSession,
+    selector: &str,
+    name: &str,
+) -> anyhow::Result<serde_json::Value> {
+    let element = session
+        .page
+        .find_element(selector)
+        .await
+        .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
+    let value = resolve_field(&element, name).await?;
+
+    Ok(serde_json::json!({
+        "name": name,
+        "value": value,
+        "url": session.url().await,
+    }))
+}
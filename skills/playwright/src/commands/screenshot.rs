@@ -1,11 +1,184 @@
+use chromiumoxide::cdp::browser_protocol::page::{
+    CaptureScreenshotFormat, PrintToPdfParams, Viewport,
+};
 use chromiumoxide::page::ScreenshotParams;
 
 use crate::browser::BrowserSession;
+use crate::error::PwError;
+
+/// Device emulation applied before capture: viewport size, pixel density,
+/// and whether to present as a mobile device.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeviceEmulation {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub device_scale_factor: Option<f64>,
+    pub mobile: bool,
+}
+
+/// Options layered onto a capture, mirroring how `fetch` already layers
+/// navigation onto content/screenshot: an optional element to scope the
+/// capture to, an arbitrary clip region, output format/quality, and device
+/// emulation applied beforehand.
+#[derive(Debug, Clone, Default)]
+pub struct ScreenshotOptions {
+    pub selector: Option<String>,
+    pub clip: Option<(f64, f64, f64, f64)>,
+    pub format: ScreenshotFormat,
+    pub quality: Option<i64>,
+    pub emulation: Option<DeviceEmulation>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScreenshotFormat {
+    #[default]
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl ScreenshotFormat {
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "png" => Ok(Self::Png),
+            "jpeg" | "jpg" => Ok(Self::Jpeg),
+            "webp" => Ok(Self::Webp),
+            other => Err(anyhow::anyhow!("Unsupported screenshot format: {other}")),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+
+    fn cdp_format(self) -> CaptureScreenshotFormat {
+        match self {
+            Self::Png => CaptureScreenshotFormat::Png,
+            Self::Jpeg => CaptureScreenshotFormat::Jpeg,
+            Self::Webp => CaptureScreenshotFormat::Webp,
+        }
+    }
+}
+
+async fn apply_emulation(
+    session: &BrowserSession,
+    emulation: &DeviceEmulation,
+) -> anyhow::Result<()> {
+    let width = emulation.width.unwrap_or(1280);
+    let height = emulation.height.unwrap_or(720);
+    session
+        .page
+        .execute(
+            chromiumoxide::cdp::browser_protocol::emulation::SetDeviceMetricsOverrideParams::builder()
+                .width(width as i64)
+                .height(height as i64)
+                .device_scale_factor(emulation.device_scale_factor.unwrap_or(1.0))
+                .mobile(emulation.mobile)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Invalid device emulation params: {e}"))?,
+        )
+        .await?;
+    Ok(())
+}
 
 pub async fn screenshot(
     session: &mut BrowserSession,
     output: Option<&str>,
     full_page: bool,
+    options: ScreenshotOptions,
+) -> anyhow::Result<serde_json::Value> {
+    if let Some(emulation) = &options.emulation {
+        apply_emulation(session, emulation).await?;
+    }
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+
+    let path = output.map(|s| s.to_string()).unwrap_or_else(|| {
+        format!(
+            "/tmp/pw-screenshot-{timestamp}.{}",
+            options.format.extension()
+        )
+    });
+
+    if let Some(parent) = std::path::Path::new(&path).parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let mut builder = ScreenshotParams::builder()
+        .format(options.format.cdp_format())
+        .full_page(full_page && options.selector.is_none());
+
+    if let Some(quality) = options.quality {
+        if options.format != ScreenshotFormat::Png {
+            builder = builder.quality(quality);
+        }
+    }
+
+    let clip = if let Some(selector) = &options.selector {
+        let element = session
+            .page
+            .find_element(selector)
+            .await
+            .map_err(|e| PwError::NoSuchElement(format!("{selector}: {e}")))?;
+        let rect = element
+            .bounding_box()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read element box model: {e}"))?;
+        Some((rect.x, rect.y, rect.width, rect.height))
+    } else {
+        options.clip
+    };
+
+    if let Some((x, y, width, height)) = clip {
+        builder = builder.clip(
+            Viewport::builder()
+                .x(x)
+                .y(y)
+                .width(width)
+                .height(height)
+                .scale(1.0)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Invalid clip region: {e}"))?,
+        );
+    }
+
+    let params = builder
+        .build()
+        .map_err(|e| anyhow::anyhow!("Invalid screenshot params: {e}"))?;
+
+    let bytes = session.page.screenshot(params).await?;
+    tokio::fs::write(&path, &bytes).await?;
+
+    Ok(serde_json::json!({
+        "path": path,
+        "url": session.url().await,
+    }))
+}
+
+/// Options for [`pdf`], layered the same way `ScreenshotOptions` is.
+#[derive(Debug, Clone, Default)]
+pub struct PdfOptions {
+    pub landscape: bool,
+    pub print_background: bool,
+    pub paper_width: Option<f64>,
+    pub paper_height: Option<f64>,
+    pub margin_top: Option<f64>,
+    pub margin_bottom: Option<f64>,
+    pub margin_left: Option<f64>,
+    pub margin_right: Option<f64>,
+}
+
+pub async fn pdf(
+    session: &mut BrowserSession,
+    output: Option<&str>,
+    options: PdfOptions,
 ) -> anyhow::Result<serde_json::Value> {
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::SystemTime::UNIX_EPOCH)
@@ -14,18 +187,24 @@ pub async fn screenshot(
 
     let path = output
         .map(|s| s.to_string())
-        .unwrap_or_else(|| format!("/tmp/pw-screenshot-{timestamp}.png"));
+        .unwrap_or_else(|| format!("/tmp/pw-page-{timestamp}.pdf"));
 
-    // Ensure parent directory exists
     if let Some(parent) = std::path::Path::new(&path).parent() {
         tokio::fs::create_dir_all(parent).await?;
     }
 
-    let params = ScreenshotParams::builder()
-        .full_page(full_page)
+    let params = PrintToPdfParams::builder()
+        .landscape(options.landscape)
+        .print_background(options.print_background)
+        .paper_width(options.paper_width)
+        .paper_height(options.paper_height)
+        .margin_top(options.margin_top)
+        .margin_bottom(options.margin_bottom)
+        .margin_left(options.margin_left)
+        .margin_right(options.margin_right)
         .build();
 
-    let bytes = session.page.screenshot(params).await?;
+    let bytes = session.page.pdf(params).await?;
     tokio::fs::write(&path, &bytes).await?;
 
     Ok(serde_json::json!({
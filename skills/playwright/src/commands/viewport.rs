@@ -0,0 +1,111 @@
+use chromiumoxide::cdp::browser_protocol::emulation::SetDeviceMetricsOverrideParams;
+use chromiumoxide::cdp::browser_protocol::network::SetUserAgentOverrideParams;
+
+use crate::browser::BrowserSession;
+
+/// A named device preset: the viewport metrics and user-agent string a
+/// DevTools-style device toolbar would expand the name to.
+struct DevicePreset {
+    width: u32,
+    height: u32,
+    device_scale_factor: f64,
+    mobile: bool,
+    user_agent: &'static str,
+}
+
+fn lookup_preset(name: &str) -> Option<DevicePreset> {
+    match name.to_lowercase().as_str() {
+        "iphone 13" | "iphone13" => Some(DevicePreset {
+            width: 390,
+            height: 844,
+            device_scale_factor: 3.0,
+            mobile: true,
+            user_agent: "Mozilla/5.0 (iPhone; CPU iPhone OS 15_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/15.0 Mobile/15E148 Safari/604.1",
+        }),
+        "pixel 7" | "pixel7" => Some(DevicePreset {
+            width: 412,
+            height: 915,
+            device_scale_factor: 2.625,
+            mobile: true,
+            user_agent: "Mozilla/5.0 (Linux; Android 13; Pixel 7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36",
+        }),
+        _ => None,
+    }
+}
+
+/// Options for [`set`]. Explicit fields always win over a `device` preset's
+/// defaults, so `--device "iPhone 13" --width 430` overrides just the width.
+#[derive(Debug, Clone, Default)]
+pub struct ViewportOptions {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub device_scale_factor: Option<f64>,
+    pub mobile: bool,
+    pub user_agent: Option<String>,
+    pub device: Option<String>,
+}
+
+pub async fn set(
+    session: &mut BrowserSession,
+    options: ViewportOptions,
+) -> anyhow::Result<serde_json::Value> {
+    let preset = options
+        .device
+        .as_deref()
+        .map(|name| {
+            lookup_preset(name).ok_or_else(|| anyhow::anyhow!("Unknown device preset: {name}"))
+        })
+        .transpose()?;
+
+    let width = options
+        .width
+        .or(preset.as_ref().map(|p| p.width))
+        .unwrap_or(1280);
+    let height = options
+        .height
+        .or(preset.as_ref().map(|p| p.height))
+        .unwrap_or(720);
+    let device_scale_factor = options
+        .device_scale_factor
+        .or(preset.as_ref().map(|p| p.device_scale_factor))
+        .unwrap_or(1.0);
+    let mobile = options.mobile || preset.as_ref().is_some_and(|p| p.mobile);
+    let user_agent = options
+        .user_agent
+        .clone()
+        .or_else(|| preset.as_ref().map(|p| p.user_agent.to_string()));
+
+    session
+        .page
+        .execute(
+            SetDeviceMetricsOverrideParams::builder()
+                .width(width as i64)
+                .height(height as i64)
+                .device_scale_factor(device_scale_factor)
+                .mobile(mobile)
+                .build()
+                .map_err(|e| anyhow::anyhow!("Invalid viewport params: {e}"))?,
+        )
+        .await?;
+
+    if let Some(user_agent) = &user_agent {
+        session
+            .page
+            .execute(
+                SetUserAgentOverrideParams::builder()
+                    .user_agent(user_agent.clone())
+                    .build()
+                    .map_err(|e| anyhow::anyhow!("Invalid user agent: {e}"))?,
+            )
+            .await?;
+    }
+
+    Ok(serde_json::json!({
+        "width": width,
+        "height": height,
+        "device_scale_factor": device_scale_factor,
+        "mobile": mobile,
+        "user_agent": user_agent,
+        "url": session.url().await,
+    }))
+}
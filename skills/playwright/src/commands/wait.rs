@@ -1,117 +1,246 @@
-use tokio::time::{Duration, timeout};
+use chromiumoxide::cdp::browser_protocol::page::{
+    EventLifecycleEvent, SetLifecycleEventsEnabledParams,
+};
+use chromiumoxide::cdp::js_protocol::runtime::{
+    AddBindingParams, EventBindingCalled, RemoveBindingParams,
+};
+use futures::StreamExt;
+use tokio::sync::oneshot;
+use tokio::time::{timeout, Duration};
 
 use crate::browser::BrowserSession;
+use crate::error::PwError;
 
-pub async fn wait_selector(
-    session: &mut BrowserSession,
-    selector: &str,
+/// JSON-encodes each argument so it can be spliced into a generated call
+/// expression as a literal — proper JSON escaping instead of the naive
+/// `replace('\'', "\\'")` this module used to rely on.
+fn encode_args(args: &[serde_json::Value]) -> String {
+    args.iter()
+        .map(|a| serde_json::to_string(a).unwrap_or_else(|_| "null".to_string()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Installs a one-shot `Runtime.addBinding` callback and a `MutationObserver`
+/// that calls it as soon as `predicate_js` (a JS function source, e.g.
+/// `"function(selector) { return !!document.querySelector(selector); }"`)
+/// returns truthy, then awaits that callback over a channel instead of
+/// re-evaluating the predicate on a 100 ms timer. `args` are passed to the
+/// predicate positionally via [`encode_args`] rather than being
+/// string-interpolated into the predicate source itself.
+async fn wait_for_mutation(
+    session: &BrowserSession,
+    binding: &str,
+    predicate_js: &str,
+    args: &[serde_json::Value],
     timeout_ms: u64,
-) -> anyhow::Result<serde_json::Value> {
-    let sel = selector.to_string();
+    description: &str,
+) -> anyhow::Result<()> {
     let page = &session.page;
 
-    let result = timeout(Duration::from_millis(timeout_ms), async {
-        loop {
-            let found = page
-                .evaluate(format!(
-                    "!!document.querySelector('{}')",
-                    sel.replace('\'', "\\'")
-                ))
-                .await
-                .ok()
-                .and_then(|v| v.into_value::<bool>().ok())
-                .unwrap_or(false);
-
-            if found {
-                return Ok::<(), anyhow::Error>(());
+    page.execute(
+        AddBindingParams::builder()
+            .name(binding)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Invalid binding params: {e}"))?,
+    )
+    .await
+    .map_err(|e| PwError::JavascriptError(e.to_string()))?;
+
+    let mut binding_events = page
+        .event_listener::<EventBindingCalled>()
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?;
+    let binding_name = binding.to_string();
+    let (tx, rx) = oneshot::channel();
+    tokio::spawn(async move {
+        while let Some(event) = binding_events.next().await {
+            if event.name == binding_name {
+                let _ = tx.send(());
+                return;
             }
-            tokio::time::sleep(Duration::from_millis(100)).await;
         }
-    })
-    .await;
+    });
+
+    let observer_js = format!(
+        r#"(function(predicate, args) {{
+            const check = () => {{
+                try {{
+                    if (predicate.apply(null, args)) {{
+                        window['{binding}'](String(true));
+                        return true;
+                    }}
+                }} catch (e) {{}}
+                return false;
+            }};
+            if (check()) return;
+            const observer = new MutationObserver(check);
+            observer.observe(document, {{
+                childList: true,
+                subtree: true,
+                characterData: true,
+                attributes: true,
+            }});
+        }})({predicate_js}, [{args_json}])"#,
+        binding = binding,
+        predicate_js = predicate_js,
+        args_json = encode_args(args),
+    );
+    page.evaluate(observer_js)
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?;
+
+    let result = timeout(Duration::from_millis(timeout_ms), rx).await;
+
+    if let Ok(remove) = RemoveBindingParams::builder().name(binding).build() {
+        let _ = page.execute(remove).await;
+    }
 
     match result {
-        Ok(Ok(())) => Ok(serde_json::json!({
-            "url": session.url().await,
-        })),
-        _ => Err(anyhow::anyhow!(
-            "Timeout waiting for selector: {selector}"
-        )),
+        Ok(Ok(())) => Ok(()),
+        _ => Err(PwError::Timeout(description.to_string()).into()),
     }
 }
 
-pub async fn wait_text(
-    session: &mut BrowserSession,
-    text: &str,
+/// Waits for one of `names` (e.g. `"load"`, `"networkIdle"`) on CDP's
+/// `Page.lifecycleEvent` stream instead of polling `document.readyState`.
+async fn wait_for_lifecycle_event(
+    session: &BrowserSession,
+    names: &[&str],
     timeout_ms: u64,
-) -> anyhow::Result<serde_json::Value> {
-    let search_text = text.to_string();
+    description: &str,
+) -> anyhow::Result<()> {
     let page = &session.page;
 
-    let result = timeout(Duration::from_millis(timeout_ms), async {
-        loop {
-            let found = page
-                .evaluate(format!(
-                    "document.body.innerText.includes('{}')",
-                    search_text.replace('\'', "\\'")
-                ))
-                .await
-                .ok()
-                .and_then(|v| v.into_value::<bool>().ok())
-                .unwrap_or(false);
-
-            if found {
-                return Ok::<(), anyhow::Error>(());
+    page.execute(
+        SetLifecycleEventsEnabledParams::builder()
+            .enabled(true)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Invalid lifecycle params: {e}"))?,
+    )
+    .await
+    .map_err(|e| PwError::JavascriptError(e.to_string()))?;
+
+    let mut events = page
+        .event_listener::<EventLifecycleEvent>()
+        .await
+        .map_err(|e| PwError::JavascriptError(e.to_string()))?;
+    let wanted: Vec<String> = names.iter().map(|s| s.to_string()).collect();
+    let (tx, rx) = oneshot::channel();
+    tokio::spawn(async move {
+        while let Some(event) = events.next().await {
+            if wanted.iter().any(|n| n == &event.name) {
+                let _ = tx.send(());
+                return;
             }
-            tokio::time::sleep(Duration::from_millis(100)).await;
         }
-    })
-    .await;
+    });
 
-    match result {
-        Ok(Ok(())) => Ok(serde_json::json!({
-            "url": session.url().await,
-        })),
-        _ => Err(anyhow::anyhow!("Timeout waiting for text: {text}")),
+    match timeout(Duration::from_millis(timeout_ms), rx).await {
+        Ok(Ok(())) => Ok(()),
+        _ => Err(PwError::Timeout(description.to_string()).into()),
     }
 }
 
-pub async fn wait_navigation(
+pub async fn wait_selector(
     session: &mut BrowserSession,
+    selector: &str,
     timeout_ms: u64,
 ) -> anyhow::Result<serde_json::Value> {
-    // Wait for the page to reach a loaded state
-    let page = &session.page;
+    wait_for_mutation(
+        session,
+        "__pw_wait_selector",
+        "function(selector) { return !!document.querySelector(selector); }",
+        &[serde_json::json!(selector)],
+        timeout_ms,
+        &format!("waiting for selector: {selector}"),
+    )
+    .await?;
 
-    let result = timeout(Duration::from_millis(timeout_ms), async {
-        loop {
-            let state = page
-                .evaluate("document.readyState")
-                .await
-                .ok()
-                .and_then(|v| v.into_value::<String>().ok())
-                .unwrap_or_default();
-
-            if state == "complete" || state == "interactive" {
-                return Ok::<(), anyhow::Error>(());
-            }
-            tokio::time::sleep(Duration::from_millis(100)).await;
-        }
-    })
-    .await;
+    Ok(serde_json::json!({
+        "url": session.url().await,
+    }))
+}
 
-    match result {
-        Ok(Ok(())) => Ok(serde_json::json!({
-            "url": session.url().await,
-        })),
-        _ => Err(anyhow::anyhow!("Timeout waiting for navigation")),
-    }
+/// The inverse of [`wait_selector`]: resolves once `selector` no longer
+/// matches any element, for waiting out a spinner or a transient banner.
+pub async fn wait_selector_gone(
+    session: &mut BrowserSession,
+    selector: &str,
+    timeout_ms: u64,
+) -> anyhow::Result<serde_json::Value> {
+    wait_for_mutation(
+        session,
+        "__pw_wait_selector_gone",
+        "function(selector) { return !document.querySelector(selector); }",
+        &[serde_json::json!(selector)],
+        timeout_ms,
+        &format!("waiting for selector to disappear: {selector}"),
+    )
+    .await?;
+
+    Ok(serde_json::json!({
+        "url": session.url().await,
+    }))
+}
+
+pub async fn wait_text(
+    session: &mut BrowserSession,
+    text: &str,
+    timeout_ms: u64,
+) -> anyhow::Result<serde_json::Value> {
+    wait_for_mutation(
+        session,
+        "__pw_wait_text",
+        "function(text) { return document.body && document.body.innerText.includes(text); }",
+        &[serde_json::json!(text)],
+        timeout_ms,
+        &format!("waiting for text: {text}"),
+    )
+    .await?;
+
+    Ok(serde_json::json!({
+        "url": session.url().await,
+    }))
+}
+
+pub async fn wait_navigation(
+    session: &mut BrowserSession,
+    timeout_ms: u64,
+) -> anyhow::Result<serde_json::Value> {
+    wait_for_lifecycle_event(
+        session,
+        &["load", "networkIdle"],
+        timeout_ms,
+        "waiting for navigation",
+    )
+    .await?;
+
+    Ok(serde_json::json!({
+        "url": session.url().await,
+    }))
 }
 
-pub async fn wait(
+/// Waits specifically for CDP's `networkIdle` lifecycle event (no network
+/// connections for ~500 ms), stricter than [`wait_navigation`]'s `load`.
+pub async fn wait_network_idle(
     session: &mut BrowserSession,
-    ms: u64,
+    timeout_ms: u64,
 ) -> anyhow::Result<serde_json::Value> {
+    wait_for_lifecycle_event(
+        session,
+        &["networkIdle"],
+        timeout_ms,
+        "waiting for network idle",
+    )
+    .await?;
+
+    Ok(serde_json::json!({
+        "url": session.url().await,
+    }))
+}
+
+pub async fn wait(session: &mut BrowserSession, ms: u64) -> anyhow::Result<serde_json::Value> {
     tokio::time::sleep(Duration::from_millis(ms)).await;
 
     Ok(serde_json::json!({
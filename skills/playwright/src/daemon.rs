@@ -0,0 +1,242 @@
+//! `pw serve`: a background process that owns long-lived, named browser
+//! sessions behind a Unix socket, so a scripted sequence of `pw` commands
+//! keeps its cookies, navigation, and focus across invocations instead of
+//! launching and killing a browser per command. Every other subcommand
+//! auto-detects a running daemon via [`try_dispatch`] and routes over the
+//! socket when one is reachable, falling back to a throwaway session
+//! (`main::run_command`) when it isn't.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::Mutex;
+
+use crate::browser::{self, BrowserSession};
+use crate::PwCommand;
+
+#[derive(Serialize, Deserialize)]
+struct DaemonRequest {
+    session: String,
+    command: PwCommand,
+}
+
+struct SessionEntry {
+    session: BrowserSession,
+    last_used: Instant,
+}
+
+/// One slot per session name. The browser inside is created lazily, on
+/// first use of that name, under the slot's own lock — so commands against
+/// different sessions never block on each other.
+struct SessionSlot {
+    entry: Mutex<Option<SessionEntry>>,
+}
+
+struct Daemon {
+    sessions: Mutex<HashMap<String, Arc<SessionSlot>>>,
+    idle_timeout: Duration,
+}
+
+impl Daemon {
+    fn new(idle_timeout: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            sessions: Mutex::new(HashMap::new()),
+            idle_timeout,
+        })
+    }
+
+    async fn slot(&self, name: &str) -> Arc<SessionSlot> {
+        self.sessions
+            .lock()
+            .await
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                Arc::new(SessionSlot {
+                    entry: Mutex::new(None),
+                })
+            })
+            .clone()
+    }
+
+    async fn handle(&self, session_name: &str, cmd: PwCommand) -> anyhow::Result<serde_json::Value> {
+        match cmd {
+            PwCommand::Status => {
+                let sessions = self.sessions.lock().await;
+                Ok(serde_json::json!({
+                    "connected": sessions.contains_key(session_name),
+                    "session": session_name,
+                    "active_sessions": sessions.keys().cloned().collect::<Vec<_>>(),
+                }))
+            }
+            PwCommand::Close => {
+                let slot = self.sessions.lock().await.remove(session_name);
+                if let Some(slot) = slot {
+                    if let Some(entry) = slot.entry.lock().await.as_ref() {
+                        let _ =
+                            crate::commands::cookie_store::save_cookies(&entry.session, session_name)
+                                .await;
+                    }
+                }
+                Ok(serde_json::json!({ "session": session_name }))
+            }
+            PwCommand::Serve { .. } => {
+                anyhow::bail!("`pw serve` cannot run against an already-running daemon")
+            }
+            other => {
+                let slot = self.slot(session_name).await;
+                let mut guard = slot.entry.lock().await;
+                if guard.is_none() {
+                    *guard = Some(SessionEntry {
+                        session: browser::get_browser(session_name).await?,
+                        last_used: Instant::now(),
+                    });
+                }
+                let entry = guard.as_mut().expect("just initialized above");
+                entry.last_used = Instant::now();
+                crate::dispatch_on_session(&mut entry.session, other).await
+            }
+        }
+    }
+
+    /// Drops any session whose last command was more than `idle_timeout`
+    /// ago, so a forgotten `pw serve` doesn't keep Chrome instances running
+    /// forever.
+    ///
+    /// Snapshots the `Arc<SessionSlot>`s and drops the map lock before
+    /// probing each one, rather than holding it across an `.await` on every
+    /// slot's own lock — otherwise a command in flight against any slot
+    /// (holding that slot's lock while it awaits the browser) blocks the
+    /// reaper's map lock, and the reaper in turn blocks `slot()`/`Close` for
+    /// every other session until that command finishes.
+    async fn reap_idle(&self) {
+        let snapshot: Vec<(String, Arc<SessionSlot>)> = self
+            .sessions
+            .lock()
+            .await
+            .iter()
+            .map(|(name, slot)| (name.clone(), slot.clone()))
+            .collect();
+
+        let mut idle = Vec::new();
+        for (name, slot) in &snapshot {
+            let Ok(guard) = slot.entry.try_lock() else {
+                continue;
+            };
+            if let Some(entry) = guard.as_ref() {
+                if entry.last_used.elapsed() > self.idle_timeout {
+                    idle.push(name.clone());
+                }
+            }
+        }
+
+        for name in idle {
+            let mut sessions = self.sessions.lock().await;
+            let Some(slot) = sessions.remove(&name) else {
+                continue;
+            };
+            drop(sessions);
+            if let Ok(guard) = slot.entry.try_lock() {
+                if let Some(entry) = guard.as_ref() {
+                    let _ = crate::commands::cookie_store::save_cookies(&entry.session, &name).await;
+                }
+            }
+        }
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/pw.sock`, falling back to `/tmp/pw-<uid>.sock` when
+/// unset — the per-user socket convention daemons without a system-wide
+/// install location typically use, so it doesn't collide across users on a
+/// shared host.
+fn socket_path() -> std::path::PathBuf {
+    if let Ok(dir) = std::env::var("XDG_RUNTIME_DIR") {
+        return std::path::PathBuf::from(dir).join("pw.sock");
+    }
+    let uid = unsafe { libc::getuid() };
+    std::path::PathBuf::from(format!("/tmp/pw-{uid}.sock"))
+}
+
+/// Starts the daemon in the foreground, listening on [`socket_path`] until
+/// killed. Background it yourself (`pw serve &`) to keep a shell free.
+pub async fn serve(idle_timeout: Duration) -> anyhow::Result<()> {
+    let path = socket_path();
+    // A stale socket left behind by a daemon that didn't shut down cleanly
+    // would otherwise make binding fail with "address in use".
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to bind {}: {e}", path.display()))?;
+
+    let daemon = Daemon::new(idle_timeout);
+
+    let reaper = daemon.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            reaper.reap_idle().await;
+        }
+    });
+
+    eprintln!("pw daemon listening on {}", path.display());
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let daemon = daemon.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(daemon, stream).await {
+                eprintln!("pw daemon connection error: {e}");
+            }
+        });
+    }
+}
+
+async fn handle_connection(daemon: Arc<Daemon>, stream: UnixStream) -> anyhow::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let Some(line) = lines.next_line().await? else {
+        return Ok(());
+    };
+
+    let request: DaemonRequest = serde_json::from_str(&line)
+        .map_err(|e| anyhow::anyhow!("Invalid daemon request: {e}"))?;
+
+    let reply = match daemon.handle(&request.session, request.command).await {
+        Ok(value) => crate::json_success(value),
+        Err(e) => crate::json_error(&e),
+    };
+
+    writer.write_all(reply.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    Ok(())
+}
+
+/// Tries to reach a running daemon and run `cmd` against `session` through
+/// it. `Ok(None)` means no daemon is listening at [`socket_path`], so the
+/// caller should fall back to a one-shot throwaway browser session.
+pub async fn try_dispatch(session: &str, cmd: &PwCommand) -> anyhow::Result<Option<String>> {
+    let stream = match UnixStream::connect(socket_path()).await {
+        Ok(stream) => stream,
+        Err(_) => return Ok(None),
+    };
+
+    let request = DaemonRequest {
+        session: session.to_string(),
+        command: cmd.clone(),
+    };
+    let mut line = serde_json::to_string(&request)?;
+    line.push('\n');
+
+    let (reader, mut writer) = stream.into_split();
+    writer.write_all(line.as_bytes()).await?;
+
+    let mut lines = BufReader::new(reader).lines();
+    let reply = lines
+        .next_line()
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("pw daemon closed the connection without replying"))?;
+    Ok(Some(reply))
+}
@@ -0,0 +1,46 @@
+use thiserror::Error;
+
+/// Stable failure categories for automation callers to branch on — retry on
+/// [`Timeout`](PwError::Timeout), abort on
+/// [`InvalidSelector`](PwError::InvalidSelector) — instead of string-matching
+/// the human-readable `error` field. Mirrors the taxonomy WebDriver's own
+/// `error.rs` uses for the same purpose.
+#[derive(Error, Debug)]
+pub enum PwError {
+    #[error("Element not found: {0}")]
+    NoSuchElement(String),
+
+    #[error("Element is no longer attached to the page: {0}")]
+    StaleElementReference(String),
+
+    #[error("Element is not interactable: {0}")]
+    ElementNotInteractable(String),
+
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    #[error("JavaScript evaluation failed: {0}")]
+    JavascriptError(String),
+
+    #[error("Invalid selector: {0}")]
+    InvalidSelector(String),
+
+    #[error("Navigation failed: {0}")]
+    NavigationFailed(String),
+}
+
+impl PwError {
+    /// Stable snake_case identifier for this failure kind, stored alongside
+    /// the human message in the JSON error envelope.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::NoSuchElement(_) => "no_such_element",
+            Self::StaleElementReference(_) => "stale_element_reference",
+            Self::ElementNotInteractable(_) => "element_not_interactable",
+            Self::Timeout(_) => "timeout",
+            Self::JavascriptError(_) => "javascript_error",
+            Self::InvalidSelector(_) => "invalid_selector",
+            Self::NavigationFailed(_) => "navigation_failed",
+        }
+    }
+}
@@ -1,16 +1,28 @@
 mod browser;
 mod commands;
+mod daemon;
+mod error;
 
 use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+
+use error::PwError;
 
 #[derive(Parser)]
 #[command(name = "pw", about = "Browser automation CLI using Chrome DevTools Protocol")]
 struct Cli {
+    /// Named session to run against when a `pw serve` daemon is reachable.
+    /// Outside daemon mode every invocation gets its own throwaway browser,
+    /// and the name picks which saved cookie file
+    /// (`~/.mini-claw/cookies/<session>.json`) is restored and saved.
+    #[arg(long, global = true, default_value = "default")]
+    session: String,
+
     #[command(subcommand)]
     command: PwCommand,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Serialize, Deserialize, Debug, Clone)]
 enum PwCommand {
     /// Navigate to a URL
     #[command(alias = "goto")]
@@ -26,12 +38,57 @@ enum PwCommand {
     Reload,
     /// Take a screenshot
     Screenshot {
-        /// Output path (default: /tmp/pw-screenshot-<timestamp>.png)
+        /// Output path (default: /tmp/pw-screenshot-<timestamp>.<ext>)
         #[arg(short, long)]
         output: Option<String>,
         /// Capture full page
         #[arg(short, long)]
         full_page: bool,
+        /// Capture only the bounding box of this CSS selector
+        #[arg(short, long)]
+        selector: Option<String>,
+        /// Clip to an arbitrary region: x,y,width,height
+        #[arg(long, value_parser = parse_clip)]
+        clip: Option<(f64, f64, f64, f64)>,
+        /// Output format: png, jpeg, or webp
+        #[arg(long, default_value = "png")]
+        format: String,
+        /// Quality (0-100) for lossy formats (jpeg/webp)
+        #[arg(long)]
+        quality: Option<i64>,
+        /// Emulated viewport width
+        #[arg(long)]
+        device_width: Option<u32>,
+        /// Emulated viewport height
+        #[arg(long)]
+        device_height: Option<u32>,
+        /// Emulated device scale factor
+        #[arg(long)]
+        device_scale_factor: Option<f64>,
+        /// Emulate a mobile device
+        #[arg(long)]
+        mobile: bool,
+    },
+    /// Export the current page as a PDF
+    Pdf {
+        /// Output path (default: /tmp/pw-page-<timestamp>.pdf)
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Render in landscape orientation
+        #[arg(long)]
+        landscape: bool,
+        /// Include background graphics
+        #[arg(long)]
+        print_background: bool,
+        /// Paper width in inches
+        #[arg(long)]
+        paper_width: Option<f64>,
+        /// Paper height in inches
+        #[arg(long)]
+        paper_height: Option<f64>,
+        /// Page margin (all sides) in inches
+        #[arg(long)]
+        margin: Option<f64>,
     },
     /// Click an element by CSS selector
     Click {
@@ -74,6 +131,16 @@ enum PwCommand {
         /// Key to press (e.g., "Enter", "Tab", "Escape")
         key: String,
     },
+    /// Replay a WebDriver-style synchronized-tick input sequence: a JSON
+    /// array of input sources (one `key` source, one or more `pointer`
+    /// sources), each with an ordered list of actions. At tick *i* every
+    /// source performs its *i*-th action together, e.g.
+    /// `[{"type":"key","actions":[{"type":"keyDown","key":"Control"}]},
+    ///   {"type":"pointer","actions":[{"type":"pointerMove","x":10,"y":10}]}]`
+    Actions {
+        /// JSON array of input sources
+        sources: String,
+    },
     /// Get page content (text or HTML)
     Content {
         /// Output format: text or html
@@ -87,6 +154,40 @@ enum PwCommand {
     },
     /// Get accessibility tree snapshot
     Snapshot,
+    /// Find every element matching a CSS selector (WebDriver's
+    /// `FindElements`) and return each one's text, tag name, bounding rect,
+    /// and a requested set of attributes/properties/CSS values. Enables
+    /// scraping tables and lists without ad-hoc `Eval`.
+    Query {
+        /// CSS selector
+        selector: String,
+        /// Attribute/property/CSS name to resolve per element (repeatable)
+        #[arg(long = "field")]
+        fields: Vec<String>,
+    },
+    /// Look up a single attribute/property/CSS value on the first element
+    /// matching a CSS selector, resolving DOM attribute vs. live JS property
+    /// vs. computed CSS value in that order (WebDriver's
+    /// `GetElementAttribute`/`GetElementProperty`/`GetCSSValue` precedence).
+    Attr {
+        /// CSS selector
+        selector: String,
+        /// Attribute, property, or CSS property name
+        name: String,
+    },
+    /// Scrape the page into a JSON object in one round trip, from a schema
+    /// like `{"title": {"selector": "h1", "attr": "text"},
+    /// "links": {"selector": "a.item", "attr": "href", "all": true}}`
+    Extract {
+        /// JSON object mapping output key to {selector, attr, all}
+        schema: String,
+    },
+    /// Convert the `<table>` matching a CSS selector into an array of row
+    /// objects keyed by the first row's header cells
+    ExtractTable {
+        /// CSS selector for the table
+        selector: String,
+    },
     /// Wait for a selector to appear
     WaitSelector {
         /// CSS selector to wait for
@@ -109,6 +210,21 @@ enum PwCommand {
         #[arg(short, long, default_value = "30000")]
         timeout: u64,
     },
+    /// Wait for the CDP `networkIdle` lifecycle event (no network
+    /// connections for ~500ms), stricter than `wait-navigation`'s `load`
+    WaitNetworkIdle {
+        /// Timeout in milliseconds
+        #[arg(short, long, default_value = "30000")]
+        timeout: u64,
+    },
+    /// Wait for a selector to stop matching any element
+    WaitSelectorGone {
+        /// CSS selector to wait to disappear
+        selector: String,
+        /// Timeout in milliseconds
+        #[arg(short, long, default_value = "30000")]
+        timeout: u64,
+    },
     /// Wait for a specified time
     Wait {
         /// Milliseconds to wait
@@ -130,11 +246,127 @@ enum PwCommand {
         /// Take screenshot instead of getting content
         #[arg(long)]
         screenshot: bool,
+        /// Get the text content of this CSS selector instead of the whole page
+        #[arg(long)]
+        selector: Option<String>,
+        /// Get an accessibility-tree snapshot instead of page content
+        #[arg(long)]
+        snapshot: bool,
+    },
+    /// Set the rendering viewport and (optionally) the user-agent, via CDP
+    /// `Emulation.setDeviceMetricsOverride` / `Network.setUserAgentOverride`.
+    /// Accepts a named device preset (e.g. "iPhone 13", "Pixel 7") as a
+    /// starting point; explicit flags override individual preset fields.
+    Viewport {
+        /// Viewport width in CSS pixels
+        #[arg(long)]
+        width: Option<u32>,
+        /// Viewport height in CSS pixels
+        #[arg(long)]
+        height: Option<u32>,
+        /// Device pixel ratio
+        #[arg(long)]
+        device_scale_factor: Option<f64>,
+        /// Emulate a mobile device (touch, mobile viewport meta handling)
+        #[arg(long)]
+        mobile: bool,
+        /// Override the navigator.userAgent string
+        #[arg(long)]
+        user_agent: Option<String>,
+        /// Named device preset, e.g. "iPhone 13" or "Pixel 7"
+        #[arg(long)]
+        device: Option<String>,
+    },
+    /// Handle a pending JavaScript `alert()`/`confirm()`/`prompt()` dialog,
+    /// so automation doesn't hang when a page opens one unexpectedly.
+    Dialog {
+        #[command(subcommand)]
+        action: DialogAction,
+    },
+    /// Manage cookies for the current page (get/list/set/delete/clear),
+    /// following WebDriver's cookie commands. Combined with `pw serve`,
+    /// this lets an authenticated session's cookies be dumped and restored
+    /// later instead of re-authenticating every run.
+    Cookies {
+        #[command(subcommand)]
+        action: CookieAction,
     },
     /// Check browser connection status
     Status,
     /// Close the browser
     Close,
+    /// Run as a background daemon: owns long-lived, named browser sessions
+    /// behind a Unix socket so a scripted sequence of commands keeps its
+    /// cookies, navigation, and focus across invocations instead of
+    /// launching and killing a browser per command. Run it backgrounded
+    /// yourself (`pw serve &`); once it's listening, every other subcommand
+    /// auto-detects it and routes over the socket using `--session`.
+    Serve {
+        /// Close a session's browser after it sits idle this many seconds
+        #[arg(long, default_value = "300")]
+        idle_timeout: u64,
+    },
+}
+
+#[derive(Subcommand, Serialize, Deserialize, Debug, Clone)]
+enum DialogAction {
+    /// Accept the pending dialog, optionally supplying prompt text
+    Accept {
+        #[arg(long)]
+        text: Option<String>,
+    },
+    /// Dismiss (cancel) the pending dialog
+    Dismiss,
+    /// Get the pending dialog's message and type without resolving it
+    Text,
+    /// Toggle auto-dismissing dialogs as soon as they open
+    AutoDismiss {
+        #[arg(long, default_value = "true")]
+        enabled: bool,
+    },
+}
+
+#[derive(Subcommand, Serialize, Deserialize, Debug, Clone)]
+enum CookieAction {
+    /// List all cookies visible to the current page
+    List,
+    /// Get a single cookie by name
+    Get {
+        /// Cookie name
+        name: String,
+    },
+    /// Add (or overwrite) a cookie
+    Set {
+        /// Cookie name
+        name: String,
+        /// Cookie value
+        value: String,
+        /// Cookie domain (defaults to the current page's URL)
+        #[arg(long)]
+        domain: Option<String>,
+        /// Cookie path
+        #[arg(long)]
+        path: Option<String>,
+        /// Expiry as seconds since the Unix epoch
+        #[arg(long)]
+        expires: Option<f64>,
+        /// Mark the cookie Secure
+        #[arg(long)]
+        secure: bool,
+        /// Mark the cookie HttpOnly
+        #[arg(long)]
+        http_only: bool,
+        /// SameSite policy: strict, lax, or none
+        #[arg(long)]
+        same_site: Option<String>,
+    },
+    /// Delete a single cookie by name
+    Delete {
+        /// Cookie name
+        name: String,
+    },
+    /// Delete all cookies
+    Clear,
 }
 
 fn json_success(fields: serde_json::Value) -> String {
@@ -147,15 +379,35 @@ fn json_success(fields: serde_json::Value) -> String {
     serde_json::to_string(&obj).unwrap()
 }
 
-fn json_error(error: &str) -> String {
+fn json_error(error: &anyhow::Error) -> String {
+    let error_code = error
+        .downcast_ref::<PwError>()
+        .map(PwError::error_code)
+        .unwrap_or("internal_error");
     serde_json::json!({
         "success": false,
-        "error": error,
+        "error": error.to_string(),
+        "error_code": error_code,
         "timestamp": timestamp(),
     })
     .to_string()
 }
 
+fn parse_clip(s: &str) -> Result<(f64, f64, f64, f64), String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 4 {
+        return Err("Expected x,y,width,height".to_string());
+    }
+    let mut nums = [0.0; 4];
+    for (i, part) in parts.iter().enumerate() {
+        nums[i] = part
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid number in clip region: {part}"))?;
+    }
+    Ok((nums[0], nums[1], nums[2], nums[3]))
+}
+
 fn timestamp() -> String {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::SystemTime::UNIX_EPOCH)
@@ -168,170 +420,264 @@ fn timestamp() -> String {
 async fn main() {
     let cli = Cli::parse();
 
-    let output = match run_command(cli.command).await {
-        Ok(json) => json,
-        Err(e) => json_error(&e.to_string()),
+    if let PwCommand::Serve { idle_timeout } = cli.command {
+        let output = match daemon::serve(std::time::Duration::from_secs(idle_timeout)).await {
+            Ok(()) => json_success(serde_json::json!({})),
+            Err(e) => json_error(&e),
+        };
+        println!("{output}");
+        return;
+    }
+
+    // If a `pw serve` daemon is already listening, route through it so the
+    // command reuses that session's browser instead of launching a fresh
+    // one. Falls through to the one-shot path below when nothing is
+    // listening, so `pw` keeps working exactly as before without a daemon.
+    let output = match daemon::try_dispatch(&cli.session, &cli.command).await {
+        Ok(Some(reply)) => reply,
+        Ok(None) => match run_command(&cli.session, cli.command).await {
+            Ok(json) => json,
+            Err(e) => json_error(&e),
+        },
+        Err(e) => json_error(&e),
     };
 
     println!("{output}");
 }
 
-async fn run_command(cmd: PwCommand) -> anyhow::Result<String> {
+async fn run_command(session_name: &str, cmd: PwCommand) -> anyhow::Result<String> {
     match cmd {
-        PwCommand::Navigate { url } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::navigate::goto(&mut session, &url).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
-        }
-        PwCommand::Back => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::navigate::back(&mut session).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
-        }
-        PwCommand::Forward => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::navigate::forward(&mut session).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::Status => {
+            let status = browser::get_status().await;
+            return Ok(json_success(status));
         }
-        PwCommand::Reload => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::navigate::reload(&mut session).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::Close => {
+            // No persistent session exists in one-shot mode, so there's
+            // nothing to tear down or save cookies for.
+            return Ok(json_success(serde_json::json!({})));
         }
-        PwCommand::Screenshot { output, full_page } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::screenshot::screenshot(&mut session, output.as_deref(), full_page).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::Serve { .. } => unreachable!("handled in main() before run_command is called"),
+        _ => {}
+    }
+
+    let mut session = browser::get_browser(session_name).await?;
+    // Run `close_browser` (which saves this session's cookies) whether `cmd`
+    // succeeds or fails — an early `?` here would skip the save on every
+    // command error in one-shot mode.
+    let result = dispatch_on_session(&mut session, cmd).await;
+    browser::close_browser(&session).await;
+    Ok(json_success(result?))
+}
+
+/// Runs `cmd` against an already-open `session`, with no browser lifecycle
+/// management of its own. Shared by the one-shot path above, which wraps a
+/// single throwaway session around one call, and `daemon::Daemon`, which
+/// keeps named sessions open across calls. `Status`/`Close`/`Serve` operate
+/// on the session table rather than a page, so callers handle those before
+/// reaching here.
+async fn dispatch_on_session(
+    session: &mut browser::BrowserSession,
+    cmd: PwCommand,
+) -> anyhow::Result<serde_json::Value> {
+    match cmd {
+        PwCommand::Navigate { url } => commands::navigate::goto(session, &url).await,
+        PwCommand::Back => commands::navigate::back(session).await,
+        PwCommand::Forward => commands::navigate::forward(session).await,
+        PwCommand::Reload => commands::navigate::reload(session).await,
+        PwCommand::Screenshot {
+            output,
+            full_page,
+            selector,
+            clip,
+            format,
+            quality,
+            device_width,
+            device_height,
+            device_scale_factor,
+            mobile,
+        } => {
+            let options = commands::screenshot::ScreenshotOptions {
+                selector,
+                clip,
+                format: commands::screenshot::ScreenshotFormat::parse(&format)?,
+                quality,
+                emulation: if device_width.is_some()
+                    || device_height.is_some()
+                    || device_scale_factor.is_some()
+                    || mobile
+                {
+                    Some(commands::screenshot::DeviceEmulation {
+                        width: device_width,
+                        height: device_height,
+                        device_scale_factor,
+                        mobile,
+                    })
+                } else {
+                    None
+                },
+            };
+            commands::screenshot::screenshot(session, output.as_deref(), full_page, options).await
         }
-        PwCommand::Click { selector } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::interact::click(&mut session, &selector).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::Pdf {
+            output,
+            landscape,
+            print_background,
+            paper_width,
+            paper_height,
+            margin,
+        } => {
+            let options = commands::screenshot::PdfOptions {
+                landscape,
+                print_background,
+                paper_width,
+                paper_height,
+                margin_top: margin,
+                margin_bottom: margin,
+                margin_left: margin,
+                margin_right: margin,
+            };
+            commands::screenshot::pdf(session, output.as_deref(), options).await
         }
+        PwCommand::Click { selector } => commands::interact::click(session, &selector).await,
         PwCommand::Type { selector, text } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::interact::type_text(&mut session, &selector, &text).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+            commands::interact::type_text(session, &selector, &text).await
         }
         PwCommand::Fill { selector, value } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::interact::fill(&mut session, &selector, &value).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+            commands::interact::fill(session, &selector, &value).await
         }
         PwCommand::Select { selector, value } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::interact::select(&mut session, &selector, &value).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
-        }
-        PwCommand::Hover { selector } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::interact::hover(&mut session, &selector).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+            commands::interact::select(session, &selector, &value).await
         }
-        PwCommand::Focus { selector } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::interact::focus(&mut session, &selector).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::Hover { selector } => commands::interact::hover(session, &selector).await,
+        PwCommand::Focus { selector } => commands::interact::focus(session, &selector).await,
+        PwCommand::Press { key } => commands::interact::press(session, &key).await,
+        PwCommand::Actions { sources } => {
+            let sources: Vec<commands::actions::ActionSource> = serde_json::from_str(&sources)
+                .map_err(|e| anyhow::anyhow!("Invalid actions JSON: {e}"))?;
+            commands::actions::perform(session, sources).await
         }
-        PwCommand::Press { key } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::interact::press(&mut session, &key).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::Content { format } => commands::content::content(session, &format).await,
+        PwCommand::Text { selector } => commands::content::text(session, &selector).await,
+        PwCommand::Snapshot => commands::content::snapshot(session).await,
+        PwCommand::Query { selector, fields } => {
+            commands::query::query(session, &selector, &fields).await
         }
-        PwCommand::Content { format } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::content::content(&mut session, &format).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::Attr { selector, name } => {
+            commands::query::attr(session, &selector, &name).await
         }
-        PwCommand::Text { selector } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::content::text(&mut session, &selector).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::Extract { schema } => {
+            let schema: serde_json::Value = serde_json::from_str(&schema)
+                .map_err(|e| anyhow::anyhow!("Invalid extract schema JSON: {e}"))?;
+            commands::extract::extract_fields(session, &schema).await
         }
-        PwCommand::Snapshot => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::content::snapshot(&mut session).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::ExtractTable { selector } => {
+            commands::extract::extract_table(session, &selector).await
         }
         PwCommand::WaitSelector { selector, timeout } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::wait::wait_selector(&mut session, &selector, timeout).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+            commands::wait::wait_selector(session, &selector, timeout).await
         }
         PwCommand::WaitText { text, timeout } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::wait::wait_text(&mut session, &text, timeout).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+            commands::wait::wait_text(session, &text, timeout).await
         }
         PwCommand::WaitNavigation { timeout } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::wait::wait_navigation(&mut session, timeout).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+            commands::wait::wait_navigation(session, timeout).await
         }
-        PwCommand::Wait { ms } => {
-            let mut session = browser::get_browser().await?;
-            let result = commands::wait::wait(&mut session, ms).await?;
-            browser::close_browser().await;
-            Ok(json_success(result))
+        PwCommand::WaitNetworkIdle { timeout } => {
+            commands::wait::wait_network_idle(session, timeout).await
         }
+        PwCommand::WaitSelectorGone { selector, timeout } => {
+            commands::wait::wait_selector_gone(session, &selector, timeout).await
+        }
+        PwCommand::Wait { ms } => commands::wait::wait(session, ms).await,
         PwCommand::Fetch {
             url,
             output,
             full_page,
             format,
             screenshot,
+            selector,
+            snapshot,
         } => {
-            let mut session = browser::get_browser().await?;
-            let nav_result = commands::navigate::goto(&mut session, &url).await?;
+            let nav_result = commands::navigate::goto(session, &url).await?;
 
-            let result = if screenshot || output.is_some() {
-                let ss = commands::screenshot::screenshot(&mut session, output.as_deref(), full_page).await?;
-                let mut merged = nav_result.as_object().cloned().unwrap_or_default();
-                if let Some(obj) = ss.as_object() {
-                    for (k, v) in obj {
-                        merged.insert(k.clone(), v.clone());
-                    }
-                }
-                serde_json::Value::Object(merged)
+            let extra = if screenshot || output.is_some() {
+                commands::screenshot::screenshot(
+                    session,
+                    output.as_deref(),
+                    full_page,
+                    commands::screenshot::ScreenshotOptions::default(),
+                )
+                .await?
+            } else if let Some(selector) = selector {
+                commands::content::text(session, &selector).await?
+            } else if snapshot {
+                commands::content::snapshot(session).await?
             } else {
-                let ct = commands::content::content(&mut session, &format).await?;
-                let mut merged = nav_result.as_object().cloned().unwrap_or_default();
-                if let Some(obj) = ct.as_object() {
-                    for (k, v) in obj {
-                        merged.insert(k.clone(), v.clone());
-                    }
-                }
-                serde_json::Value::Object(merged)
+                commands::content::content(session, &format).await?
             };
 
-            browser::close_browser().await;
-            Ok(json_success(result))
+            let mut merged = nav_result.as_object().cloned().unwrap_or_default();
+            if let Some(obj) = extra.as_object() {
+                for (k, v) in obj {
+                    merged.insert(k.clone(), v.clone());
+                }
+            }
+            Ok(serde_json::Value::Object(merged))
         }
-        PwCommand::Status => {
-            let status = browser::get_status().await;
-            Ok(json_success(status))
+        PwCommand::Viewport {
+            width,
+            height,
+            device_scale_factor,
+            mobile,
+            user_agent,
+            device,
+        } => {
+            let options = commands::viewport::ViewportOptions {
+                width,
+                height,
+                device_scale_factor,
+                mobile,
+                user_agent,
+                device,
+            };
+            commands::viewport::set(session, options).await
         }
-        PwCommand::Close => {
-            browser::close_browser().await;
-            Ok(json_success(serde_json::json!({})))
+        PwCommand::Dialog { action } => match action {
+            DialogAction::Accept { text } => commands::dialog::accept(session, text).await,
+            DialogAction::Dismiss => commands::dialog::dismiss(session).await,
+            DialogAction::Text => commands::dialog::text(session).await,
+            DialogAction::AutoDismiss { enabled } => {
+                commands::dialog::set_auto_dismiss(session, enabled).await
+            }
+        },
+        PwCommand::Cookies { action } => match action {
+            CookieAction::List => commands::cookies::list(session).await,
+            CookieAction::Get { name } => commands::cookies::get(session, &name).await,
+            CookieAction::Set {
+                name,
+                value,
+                domain,
+                path,
+                expires,
+                secure,
+                http_only,
+                same_site,
+            } => {
+                let options = commands::cookies::SetCookieOptions {
+                    domain,
+                    path,
+                    expires,
+                    secure,
+                    http_only,
+                    same_site,
+                };
+                commands::cookies::set(session, &name, &value, options).await
+            }
+            CookieAction::Delete { name } => commands::cookies::delete(session, &name).await,
+            CookieAction::Clear => commands::cookies::clear(session).await,
+        },
+        PwCommand::Status | PwCommand::Close | PwCommand::Serve { .. } => {
+            unreachable!("{cmd:?} is handled by the caller before dispatching to a session")
         }
     }
 }
@@ -0,0 +1,179 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::MiniClawError;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AccessData {
+    allowed_users: HashSet<i64>,
+    rate_limit_overrides: HashMap<i64, u64>,
+    known_chats: HashSet<i64>,
+}
+
+/// Runtime-mutable allow-list and per-user rate-limit overrides, replacing
+/// the frozen `allowed_users` vector `Config` used to load once from
+/// `ALLOWED_USERS` and never update again. Persists to disk (mirroring
+/// `WorkspaceManager`/`ChatSettingsManager`) so `/adduser`, `/removeuser`,
+/// and `/setlimit` survive a restart, and tracks every chat ID `check_access`
+/// has seen so `/broadcast` has somewhere to send to.
+pub struct AccessManager {
+    data: AccessData,
+    state_file: PathBuf,
+    loaded: bool,
+    seed_allowed_users: Vec<i64>,
+}
+
+impl AccessManager {
+    pub fn new(seed_allowed_users: Vec<i64>) -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+        Self {
+            data: AccessData::default(),
+            state_file: home.join(".mini-claw").join("access.json"),
+            loaded: false,
+            seed_allowed_users,
+        }
+    }
+
+    async fn load(&mut self) {
+        if self.loaded {
+            return;
+        }
+        match tokio::fs::read_to_string(&self.state_file).await {
+            Ok(raw) => {
+                if let Ok(parsed) = serde_json::from_str(&raw) {
+                    self.data = parsed;
+                }
+            }
+            Err(_) => {
+                // No persisted file yet: seed from the `ALLOWED_USERS` env
+                // var so the first run behaves like it always did.
+                self.data.allowed_users = self.seed_allowed_users.iter().copied().collect();
+            }
+        }
+        self.loaded = true;
+    }
+
+    async fn save(&self) -> Result<(), MiniClawError> {
+        if let Some(dir) = self.state_file.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let json = serde_json::to_string_pretty(&self.data)?;
+        tokio::fs::write(&self.state_file, json).await?;
+        Ok(())
+    }
+
+    /// Returns whether `user_id` may use the bot: an empty allow-list means
+    /// anyone may (matching the old `Config::allowed_users` behavior).
+    pub async fn is_allowed(&mut self, user_id: i64) -> bool {
+        self.load().await;
+        self.data.allowed_users.is_empty() || self.data.allowed_users.contains(&user_id)
+    }
+
+    pub async fn add_user(&mut self, user_id: i64) -> Result<(), MiniClawError> {
+        self.load().await;
+        self.data.allowed_users.insert(user_id);
+        self.save().await
+    }
+
+    pub async fn remove_user(&mut self, user_id: i64) -> Result<(), MiniClawError> {
+        self.load().await;
+        self.data.allowed_users.remove(&user_id);
+        self.save().await
+    }
+
+    pub async fn list_users(&mut self) -> Vec<i64> {
+        self.load().await;
+        let mut users: Vec<i64> = self.data.allowed_users.iter().copied().collect();
+        users.sort_unstable();
+        users
+    }
+
+    pub async fn set_rate_limit(&mut self, user_id: i64, cooldown_ms: u64) -> Result<(), MiniClawError> {
+        self.load().await;
+        self.data.rate_limit_overrides.insert(user_id, cooldown_ms);
+        self.save().await
+    }
+
+    pub async fn rate_limit_override(&mut self, chat_id: i64) -> Option<u64> {
+        self.load().await;
+        self.data.rate_limit_overrides.get(&chat_id).copied()
+    }
+
+    /// Records `chat_id` as having been seen, so `/broadcast` can reach it.
+    /// Only writes to disk when the chat is actually new.
+    pub async fn record_known_chat(&mut self, chat_id: i64) {
+        self.load().await;
+        if self.data.known_chats.insert(chat_id) {
+            let _ = self.save().await;
+        }
+    }
+
+    pub async fn known_chats(&mut self) -> Vec<i64> {
+        self.load().await;
+        self.data.known_chats.iter().copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_empty_allow_list_allows_everyone() {
+        let mut mgr = AccessManager::new(vec![]);
+        mgr.loaded = true; // Skip file loading
+        assert!(mgr.is_allowed(123).await);
+    }
+
+    #[tokio::test]
+    async fn test_seeded_allow_list_rejects_unknown_user() {
+        let mut mgr = AccessManager::new(vec![]);
+        mgr.loaded = true;
+        mgr.data.allowed_users.insert(1);
+        assert!(mgr.is_allowed(1).await);
+        assert!(!mgr.is_allowed(2).await);
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_user() {
+        let mut mgr = AccessManager::new(vec![]);
+        mgr.state_file = std::env::temp_dir().join(format!("mini-claw-access-test-{}.json", std::process::id()));
+        mgr.loaded = true;
+        mgr.data.allowed_users.insert(1);
+
+        mgr.add_user(2).await.unwrap();
+        assert!(mgr.is_allowed(2).await);
+
+        mgr.remove_user(1).await.unwrap();
+        assert!(!mgr.is_allowed(1).await);
+
+        let _ = tokio::fs::remove_file(&mgr.state_file).await;
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_override_roundtrip() {
+        let mut mgr = AccessManager::new(vec![]);
+        mgr.state_file = std::env::temp_dir().join(format!("mini-claw-access-test-{}.json", std::process::id()));
+        mgr.loaded = true;
+
+        assert_eq!(mgr.rate_limit_override(5).await, None);
+        mgr.set_rate_limit(5, 1000).await.unwrap();
+        assert_eq!(mgr.rate_limit_override(5).await, Some(1000));
+
+        let _ = tokio::fs::remove_file(&mgr.state_file).await;
+    }
+
+    #[tokio::test]
+    async fn test_record_known_chat() {
+        let mut mgr = AccessManager::new(vec![]);
+        mgr.state_file = std::env::temp_dir().join(format!("mini-claw-access-test-{}.json", std::process::id()));
+        mgr.loaded = true;
+
+        mgr.record_known_chat(42).await;
+        assert_eq!(mgr.known_chats().await, vec![42]);
+
+        let _ = tokio::fs::remove_file(&mgr.state_file).await;
+    }
+}
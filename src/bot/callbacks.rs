@@ -76,7 +76,7 @@ async fn handle_session_cleanup(
         .text("Cleaning up...")
         .await?;
 
-    let deleted = cleanup_old_sessions(&state.config, 5).await;
+    let deleted = cleanup_old_sessions(&state.config).await;
 
     if let Some(msg) = q.message {
         let _ = bot
@@ -84,7 +84,7 @@ async fn handle_session_cleanup(
                 msg.chat().id,
                 msg.id(),
                 format!(
-                    "\u{1f5d1} Cleanup complete!\nDeleted {deleted} old session(s).\nKept the 5 most recent sessions per chat."
+                    "\u{1f5d1} Cleanup complete!\nDeleted {deleted} old session(s).\nKept one per recent hour/day/week/month slot per chat."
                 ),
             )
             .await;
@@ -1,13 +1,19 @@
 use teloxide::prelude::*;
+use teloxide::types::{ChatId, InputFile};
 use teloxide::utils::command::BotCommands;
 
-use super::util::{run_shell, split_message};
+use super::util::{run_shell, run_shell_via, split_message};
 use super::AppState;
+use crate::browser_runner::{extract_selector, read_page, snapshot_page};
+use crate::hosts::ActiveTarget;
 use crate::pi_runner::check_pi_auth;
+use crate::pty_shell::is_interactive_command;
 use crate::sessions::{
     archive_session, format_file_size, format_session_age,
     generate_session_title, list_sessions,
 };
+use crate::storage::{ChatState, Storage};
+use crate::transcript::parse_transcript;
 use crate::workspace::WorkspaceManager;
 
 #[derive(BotCommands, Clone)]
@@ -33,6 +39,184 @@ pub enum BotCommand {
     Status,
     #[command(description = "Toggle live interactive mode")]
     Live(String),
+    #[command(description = "Toggle workspace file-watch mode")]
+    Watch(String),
+    #[command(description = "View or change the bot's reply language")]
+    Lang(String),
+    #[command(description = "View or change per-chat preferences")]
+    Settings(String),
+    #[command(description = "Kill the active interactive shell session")]
+    Kill,
+    #[command(description = "Resize the active interactive shell's terminal")]
+    Resize(String),
+    #[command(description = "Read a web page's text content")]
+    Read(String),
+    #[command(description = "Extract an element's text from a web page")]
+    Extract(String),
+    #[command(description = "Get an accessibility-tree snapshot of a web page")]
+    Snapshot(String),
+    #[command(description = "View or change this chat's thinking level")]
+    Think(String),
+    #[command(description = "Manage which machine /shell runs commands on")]
+    Host(String),
+    #[command(description = "Export the current session as a downloadable transcript")]
+    Export(String),
+    #[command(description = "Cancel the current live-mode generation without ending the session")]
+    Stop,
+}
+
+/// Commands gated on `config.admins` rather than `AppState::check_access`,
+/// for managing the runtime-mutable allow-list in `crate::access` and
+/// broadcasting to every chat that has ever talked to the bot. Parsed as a
+/// separate `BotCommands` enum (rather than folded into `BotCommand`) so an
+/// unprivileged user's `/adduser` falls straight to the admin check instead
+/// of needing every ordinary handler to re-check admin status.
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase")]
+pub enum AdminBotCommand {
+    #[command(description = "Add a Telegram user ID to the allow-list")]
+    Adduser(String),
+    #[command(description = "Remove a Telegram user ID from the allow-list")]
+    Removeuser(String),
+    #[command(description = "List allowed user IDs")]
+    Listusers,
+    #[command(description = "Send a message to every chat the bot has seen")]
+    Broadcast(String),
+    #[command(description = "Override a user's rate-limit cooldown, in ms")]
+    Setlimit(String),
+}
+
+pub async fn handle_admin_command(
+    bot: Bot,
+    msg: Message,
+    cmd: AdminBotCommand,
+    state: AppState,
+) -> anyhow::Result<()> {
+    let is_admin = msg
+        .from
+        .as_ref()
+        .map(|user| state.config.admins.contains(&(user.id.0 as i64)))
+        .unwrap_or(false);
+
+    if !is_admin {
+        let text = state.t(msg.chat.id.0, "not-authorized", None).await;
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    match cmd {
+        AdminBotCommand::Adduser(arg) => handle_adduser(bot, msg, state, &arg).await,
+        AdminBotCommand::Removeuser(arg) => handle_removeuser(bot, msg, state, &arg).await,
+        AdminBotCommand::Listusers => handle_listusers(bot, msg, state).await,
+        AdminBotCommand::Broadcast(arg) => handle_broadcast(bot, msg, state, &arg).await,
+        AdminBotCommand::Setlimit(arg) => handle_setlimit(bot, msg, state, &arg).await,
+    }
+}
+
+async fn handle_adduser(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let Ok(user_id) = arg.trim().parse::<i64>() else {
+        bot.send_message(msg.chat.id, "Usage: /adduser <telegram_user_id>")
+            .await?;
+        return Ok(());
+    };
+
+    let mut mgr = state.access_mgr.lock().await;
+    match mgr.add_user(user_id).await {
+        Ok(()) => bot.send_message(msg.chat.id, format!("Added user {user_id}.")).await?,
+        Err(e) => bot.send_message(msg.chat.id, format!("Failed to add user: {e}")).await?,
+    };
+    Ok(())
+}
+
+async fn handle_removeuser(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let Ok(user_id) = arg.trim().parse::<i64>() else {
+        bot.send_message(msg.chat.id, "Usage: /removeuser <telegram_user_id>")
+            .await?;
+        return Ok(());
+    };
+
+    let mut mgr = state.access_mgr.lock().await;
+    match mgr.remove_user(user_id).await {
+        Ok(()) => bot.send_message(msg.chat.id, format!("Removed user {user_id}.")).await?,
+        Err(e) => bot.send_message(msg.chat.id, format!("Failed to remove user: {e}")).await?,
+    };
+    Ok(())
+}
+
+async fn handle_listusers(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
+    let mut mgr = state.access_mgr.lock().await;
+    let users = mgr.list_users().await;
+    drop(mgr);
+
+    let reply = if users.is_empty() {
+        "Allow-list is empty: every user is currently allowed.".to_string()
+    } else {
+        let list = users
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("Allowed users:\n{list}")
+    };
+    bot.send_message(msg.chat.id, reply).await?;
+    Ok(())
+}
+
+async fn handle_broadcast(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let text = arg.trim();
+    if text.is_empty() {
+        bot.send_message(msg.chat.id, "Usage: /broadcast <message>")
+            .await?;
+        return Ok(());
+    }
+
+    let mut mgr = state.access_mgr.lock().await;
+    let chats = mgr.known_chats().await;
+    drop(mgr);
+
+    let mut sent = 0;
+    let mut failed = 0;
+    for chat_id in chats {
+        match bot.send_message(ChatId(chat_id), text).await {
+            Ok(_) => sent += 1,
+            Err(_) => failed += 1,
+        }
+    }
+
+    bot.send_message(
+        msg.chat.id,
+        format!("Broadcast sent to {sent} chat(s), {failed} failed."),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn handle_setlimit(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let mut parts = arg.split_whitespace();
+    let (user_id, cooldown_ms) = match (
+        parts.next().and_then(|s| s.parse::<i64>().ok()),
+        parts.next().and_then(|s| s.parse::<u64>().ok()),
+    ) {
+        (Some(user_id), Some(cooldown_ms)) => (user_id, cooldown_ms),
+        _ => {
+            bot.send_message(msg.chat.id, "Usage: /setlimit <telegram_user_id> <cooldown_ms>")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let mut mgr = state.access_mgr.lock().await;
+    match mgr.set_rate_limit(user_id, cooldown_ms).await {
+        Ok(()) => {
+            bot.send_message(
+                msg.chat.id,
+                format!("Rate-limit cooldown for user {user_id} set to {cooldown_ms}ms."),
+            )
+            .await?
+        }
+        Err(e) => bot.send_message(msg.chat.id, format!("Failed to set rate limit: {e}")).await?,
+    };
+    Ok(())
 }
 
 pub async fn handle_command(
@@ -42,9 +226,9 @@ pub async fn handle_command(
     state: AppState,
 ) -> anyhow::Result<()> {
     // Access control
-    if !state.check_access(&msg) {
-        bot.send_message(msg.chat.id, "Sorry, you are not authorized to use this bot.")
-            .await?;
+    if !state.check_access(&msg).await {
+        let text = state.t(msg.chat.id.0, "not-authorized", None).await;
+        bot.send_message(msg.chat.id, text).await?;
         return Ok(());
     }
 
@@ -59,6 +243,18 @@ pub async fn handle_command(
         BotCommand::New => handle_new(bot, msg, state).await,
         BotCommand::Status => handle_status(bot, msg, state).await,
         BotCommand::Live(arg) => handle_live(bot, msg, state, &arg).await,
+        BotCommand::Watch(arg) => handle_watch(bot, msg, state, &arg).await,
+        BotCommand::Lang(arg) => handle_lang(bot, msg, state, &arg).await,
+        BotCommand::Settings(arg) => handle_settings(bot, msg, state, &arg).await,
+        BotCommand::Kill => handle_kill(bot, msg, state).await,
+        BotCommand::Resize(arg) => handle_resize(bot, msg, state, &arg).await,
+        BotCommand::Read(arg) => handle_read(bot, msg, state, &arg).await,
+        BotCommand::Extract(arg) => handle_extract(bot, msg, state, &arg).await,
+        BotCommand::Snapshot(arg) => handle_snapshot(bot, msg, state, &arg).await,
+        BotCommand::Think(arg) => handle_think(bot, msg, state, &arg).await,
+        BotCommand::Host(arg) => handle_host(bot, msg, state, &arg).await,
+        BotCommand::Export(arg) => handle_export(bot, msg, state, &arg).await,
+        BotCommand::Stop => handle_stop(bot, msg, state).await,
     }
 }
 
@@ -100,19 +296,51 @@ async fn handle_help(bot: Bot, msg: Message) -> anyhow::Result<()> {
         /status - Show bot status\n\
         /help - Show this message\n\n\
         \u{1f517} Interactive:\n\
-        /live - Toggle persistent Pi session\n\n\
+        /live - Toggle persistent Pi session\n\
+        /watch - Toggle workspace file-watch mode\n\
+        /lang - View or change the bot's reply language\n\
+        /settings - View or change per-chat preferences\n\
+        /kill - Kill the active interactive shell session\n\
+        /resize <cols> <rows> - Resize the active shell's terminal\n\
+        /think <low|medium|high> - View or change this chat's thinking level\n\
+        /host - View or change which machine /shell runs commands on\n\
+        /export [text] - Export the current session as a downloadable transcript\n\
+        /stop - Cancel the current live-mode generation without ending the session\n\n\
+        \u{1f310} Web:\n\
+        /read <url> - Read a page's text content\n\
+        /extract <url> <selector> - Read one element's text\n\
+        /snapshot <url> - Get an accessibility-tree snapshot\n\n\
         \u{1f4a1} Tips:\n\
         \u{2022} Any text \u{2192} AI conversation\n\
         \u{2022} /shell runs instantly, no AI\n\
         \u{2022} /cd supports ~, .., relative paths\n\
-        \u{2022} /live enables mid-conversation interaction"
+        \u{2022} /live enables mid-conversation interaction\n\
+        \u{2022} /watch reruns Pi automatically on file changes\n\
+        \u{2022} /lang <code> sets your reply language, e.g. /lang es\n\
+        \u{2022} /settings <key> <value> tunes status updates, e.g. /settings preview_chars 200\n\
+        \u{2022} /think trades latency for depth on a per-chat basis, e.g. /think high\n\
+        \u{2022} /host add <name> <user@host[:port]> registers a machine, /host use <name> switches /shell to it, /host local switches back\n\
+        \u{2022} Interactive shells (e.g. /shell vim file.txt) stream live; plain text is sent as keystrokes until /kill\n\
+        \u{2022} /read, /extract, and /snapshot stage their result as context for your next message to Pi\n\
+        \u{2022} /export sends the session as Markdown by default; /export text sends plain text instead\n\
+        \u{2022} /stop interrupts the current response in live mode and leaves the session running for your next prompt"
     )
     .await?;
     Ok(())
 }
 
 async fn handle_pwd(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
-    let cwd = state.workspace_mgr.lock().await.get_workspace(msg.chat.id.0).await;
+    let chat_id = msg.chat.id.0;
+    let active = state.host_mgr.lock().await.active_target(chat_id).await;
+
+    if let ActiveTarget::Remote(name) = active {
+        let cwd = state.host_mgr.lock().await.remote_cwd(chat_id).await;
+        bot.send_message(msg.chat.id, format!("\u{1f4c1} {cwd} (on {name})"))
+            .await?;
+        return Ok(());
+    }
+
+    let cwd = state.workspace_mgr.lock().await.get_workspace(chat_id).await;
     let formatted = WorkspaceManager::format_path(&cwd);
     bot.send_message(msg.chat.id, format!("\u{1f4c1} {formatted}"))
         .await?;
@@ -121,9 +349,48 @@ async fn handle_pwd(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<(
 
 async fn handle_cd(bot: Bot, msg: Message, state: AppState, path: &str) -> anyhow::Result<()> {
     let path = if path.trim().is_empty() { "~" } else { path.trim() };
+    let chat_id = msg.chat.id.0;
+
+    let active = state.host_mgr.lock().await.active_target(chat_id).await;
+    if let ActiveTarget::Remote(name) = active {
+        let current = state.host_mgr.lock().await.remote_cwd(chat_id).await;
+        let backend = state.exec_session(chat_id).await;
+        let check_cmd = format!("cd {current} && cd {path} && pwd");
+        let result = run_shell_via(backend.as_ref(), &check_cmd, "/", state.config.shell_timeout_ms).await;
 
-    match state.workspace_mgr.lock().await.set_workspace(msg.chat.id.0, path).await {
+        if result.code == Some(0) && !result.stdout.trim().is_empty() {
+            let resolved = result.stdout.trim().to_string();
+            let mut mgr = state.host_mgr.lock().await;
+            let _ = mgr.set_remote_cwd(chat_id, resolved.clone()).await;
+            drop(mgr);
+            bot.send_message(msg.chat.id, format!("\u{1f4c1} {resolved} (on {name})"))
+                .await?;
+        } else {
+            let detail = if result.stderr.is_empty() {
+                "Directory not found".to_string()
+            } else {
+                result.stderr
+            };
+            bot.send_message(msg.chat.id, format!("Error: {detail}"))
+                .await?;
+        }
+        return Ok(());
+    }
+
+    match state.workspace_mgr.lock().await.set_workspace(chat_id, path).await {
         Ok(cwd) => {
+            let prior_chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+            let _ = state
+                .storage
+                .set(
+                    chat_id,
+                    ChatState {
+                        workspace: Some(cwd.clone()),
+                        ..prior_chat_state
+                    },
+                )
+                .await;
+
             let formatted = WorkspaceManager::format_path(&cwd);
             bot.send_message(msg.chat.id, format!("\u{1f4c1} {formatted}"))
                 .await?;
@@ -153,11 +420,28 @@ async fn handle_shell(
         return Ok(());
     }
 
-    let cwd = state.workspace_mgr.lock().await.get_workspace(msg.chat.id.0).await;
+    let chat_id = msg.chat.id.0;
+    let active = state.host_mgr.lock().await.active_target(chat_id).await;
     bot.send_chat_action(msg.chat.id, teloxide::types::ChatAction::Typing)
         .await?;
 
-    let result = run_shell(cmd, &cwd, state.config.shell_timeout_ms).await;
+    let result = match active {
+        ActiveTarget::Local => {
+            let cwd = state.workspace_mgr.lock().await.get_workspace(chat_id).await;
+            if is_interactive_command(cmd) {
+                return handle_shell_interactive(bot, msg, state, cmd, &cwd).await;
+            }
+            run_shell(cmd, &cwd, state.config.shell_timeout_ms).await
+        }
+        ActiveTarget::Remote(_) => {
+            // Interactive commands (vim, ssh, etc.) need a persistent PTY,
+            // which `ExecSession` doesn't provide yet; only one-shot
+            // commands run against a remote target for now.
+            let remote_cwd = state.host_mgr.lock().await.remote_cwd(chat_id).await;
+            let backend = state.exec_session(chat_id).await;
+            run_shell_via(backend.as_ref(), cmd, &remote_cwd, state.config.shell_timeout_ms).await
+        }
+    };
 
     let mut output = String::new();
     if !result.stdout.is_empty() {
@@ -185,6 +469,440 @@ async fn handle_shell(
     Ok(())
 }
 
+/// How often the streamed terminal message is re-edited with fresh output.
+/// Fixed for every chat, unlike live mode's per-chat `status_throttle_ms`.
+const PTY_EDIT_THROTTLE_MS: u64 = 800;
+
+async fn handle_shell_interactive(
+    bot: Bot,
+    msg: Message,
+    state: AppState,
+    cmd: &str,
+    cwd: &std::path::Path,
+) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    let shell = match state.pty_shells.start(chat_id, cmd, cwd, 24, 80).await {
+        Ok(shell) => shell,
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("Failed to start PTY session: {e}"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let status_msg = bot
+        .send_message(
+            msg.chat.id,
+            format!(
+                "\u{1f4bb} Shell session started: {cmd}\n\
+                Type messages to send input. /kill to stop, /resize <cols> <rows> to resize."
+            ),
+        )
+        .await?;
+
+    // Stream output as throttled edits and let subsequent plain-text
+    // messages be forwarded as keystrokes (see `handle_text` in
+    // handlers.rs) instead of blocking this dispatch task until the
+    // session ends.
+    tokio::spawn(stream_pty_session(
+        bot,
+        msg.chat.id,
+        state,
+        shell,
+        status_msg.id,
+    ));
+
+    Ok(())
+}
+
+async fn stream_pty_session(
+    bot: Bot,
+    chat: teloxide::types::ChatId,
+    state: AppState,
+    shell: std::sync::Arc<tokio::sync::Mutex<crate::pty_shell::PtyShell>>,
+    status_msg_id: teloxide::types::MessageId,
+) {
+    let chat_id = chat.0;
+    let deadline =
+        tokio::time::Instant::now() + tokio::time::Duration::from_millis(state.config.shell_timeout_ms);
+    let mut raw_output = String::new();
+    let mut last_edit = tokio::time::Instant::now();
+    let throttle = tokio::time::Duration::from_millis(PTY_EDIT_THROTTLE_MS);
+
+    loop {
+        if tokio::time::Instant::now() >= deadline {
+            state.pty_shells.stop(chat_id).await;
+            raw_output.push_str("\n\n[timeout: PTY session killed]");
+            break;
+        }
+
+        let chunk = {
+            let mut guard = shell.lock().await;
+            tokio::time::timeout(tokio::time::Duration::from_millis(200), guard.read()).await
+        };
+
+        match chunk {
+            Ok(Some(text)) => raw_output.push_str(&text),
+            Ok(None) => break, // PTY closed
+            Err(_) => {
+                let mut guard = shell.lock().await;
+                if !guard.is_alive() {
+                    break;
+                }
+                continue;
+            }
+        }
+
+        if last_edit.elapsed() < throttle {
+            continue;
+        }
+        last_edit = tokio::time::Instant::now();
+        let rendered = crate::pty_shell::render_for_telegram(&raw_output);
+        let _ = bot.edit_message_text(chat, status_msg_id, &rendered).await;
+    }
+
+    state.pty_shells.stop(chat_id).await;
+    let rendered = crate::pty_shell::render_for_telegram(&raw_output);
+    let final_text = format!("{rendered}\n\n[session ended]");
+    let _ = bot.edit_message_text(chat, status_msg_id, &final_text).await;
+}
+
+async fn handle_kill(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    if state.pty_shells.get(chat_id).await.is_some() {
+        state.pty_shells.stop(chat_id).await;
+        bot.send_message(msg.chat.id, "Shell session killed.").await?;
+    } else {
+        bot.send_message(msg.chat.id, "No active shell session.").await?;
+    }
+    Ok(())
+}
+
+async fn handle_resize(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let mut parts = arg.split_whitespace();
+    let (cols, rows) = match (
+        parts.next().and_then(|s| s.parse::<u16>().ok()),
+        parts.next().and_then(|s| s.parse::<u16>().ok()),
+    ) {
+        (Some(cols), Some(rows)) => (cols, rows),
+        _ => {
+            bot.send_message(msg.chat.id, "Usage: /resize <cols> <rows>")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    match state.pty_shells.get(msg.chat.id.0).await {
+        Some(shell) => {
+            let reply = match shell.lock().await.resize(rows, cols) {
+                Ok(()) => format!("Resized to {cols}x{rows}."),
+                Err(e) => format!("Resize failed: {e}"),
+            };
+            bot.send_message(msg.chat.id, reply).await?;
+        }
+        None => {
+            bot.send_message(msg.chat.id, "No active shell session.").await?;
+        }
+    }
+    Ok(())
+}
+
+async fn handle_think(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    let arg = arg.trim();
+
+    if arg.is_empty() {
+        let level = state.thinking_level(chat_id).await;
+        bot.send_message(
+            msg.chat.id,
+            format!("Thinking level: {level}\nUsage: /think <low|medium|high>"),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if !matches!(arg.to_lowercase().as_str(), "low" | "medium" | "high") {
+        bot.send_message(
+            msg.chat.id,
+            format!("Invalid thinking level: {arg}\nValid levels: low, medium, high"),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+    chat_state.thinking_level = Some(arg.to_lowercase());
+    let _ = state.storage.set(chat_id, chat_state).await;
+
+    bot.send_message(msg.chat.id, format!("Thinking level set to {arg}."))
+        .await?;
+    Ok(())
+}
+
+async fn handle_host(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    let arg = arg.trim();
+    let mut parts = arg.split_whitespace();
+    const USAGE: &str = "Usage: /host add <name> <user@host[:port]>\n       /host use <name>\n       /host local";
+
+    match parts.next() {
+        None => {
+            let mut mgr = state.host_mgr.lock().await;
+            let active = mgr.active_target(chat_id).await;
+            let hosts = mgr.list_hosts().await;
+            drop(mgr);
+
+            let active_line = match active {
+                ActiveTarget::Local => "local".to_string(),
+                ActiveTarget::Remote(name) => name,
+            };
+            let hosts_list = if hosts.is_empty() {
+                "(none registered)".to_string()
+            } else {
+                hosts
+                    .iter()
+                    .map(|(name, host)| format!("- {name}: {}@{}:{}", host.user, host.host, host.port))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+            bot.send_message(
+                msg.chat.id,
+                format!("Active target: {active_line}\n\nRegistered hosts:\n{hosts_list}\n\n{USAGE}"),
+            )
+            .await?;
+        }
+        Some("local") => {
+            state.host_mgr.lock().await.use_local(chat_id).await?;
+            bot.send_message(msg.chat.id, "Switched to local execution.")
+                .await?;
+        }
+        Some("add") => {
+            let (Some(name), Some(target)) = (parts.next(), parts.next()) else {
+                bot.send_message(msg.chat.id, USAGE).await?;
+                return Ok(());
+            };
+            let Some((user, rest)) = target.split_once('@') else {
+                bot.send_message(msg.chat.id, USAGE).await?;
+                return Ok(());
+            };
+            let (host, port) = match rest.split_once(':') {
+                Some((host, port_str)) => {
+                    let Ok(port) = port_str.parse::<u16>() else {
+                        bot.send_message(msg.chat.id, format!("Invalid port: {port_str}"))
+                            .await?;
+                        return Ok(());
+                    };
+                    (host.to_string(), port)
+                }
+                None => (rest.to_string(), 22),
+            };
+
+            state
+                .host_mgr
+                .lock()
+                .await
+                .add_host(name, user.to_string(), host, port)
+                .await?;
+            bot.send_message(
+                msg.chat.id,
+                format!("Added host '{name}'. Switch to it with /host use {name}."),
+            )
+            .await?;
+        }
+        Some("use") => {
+            let Some(name) = parts.next() else {
+                bot.send_message(msg.chat.id, "Usage: /host use <name>")
+                    .await?;
+                return Ok(());
+            };
+            match state.host_mgr.lock().await.use_host(chat_id, name).await {
+                Ok(()) => {
+                    bot.send_message(msg.chat.id, format!("Switched to host '{name}'."))
+                        .await?
+                }
+                Err(e) => bot.send_message(msg.chat.id, format!("Error: {e}")).await?,
+            };
+        }
+        Some(other) => {
+            bot.send_message(msg.chat.id, format!("Unknown /host subcommand: {other}\n{USAGE}"))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders the chat's active session via `crate::transcript` and sends it
+/// as a downloadable file: Markdown by default, or plain text if `arg` is
+/// "text" (e.g. `/export text`).
+async fn handle_export(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    let plain_text = arg.trim().eq_ignore_ascii_case("text");
+
+    let filename = state.session_mgr.lock().await.get_active_session_filename(chat_id).await;
+    let session_path = state.config.session_dir.join(&filename);
+
+    let transcript = match parse_transcript(&session_path).await {
+        Ok(transcript) => transcript,
+        Err(_) => {
+            bot.send_message(msg.chat.id, "No session to export yet.")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if transcript.entries.is_empty() {
+        bot.send_message(msg.chat.id, "Session is empty; nothing to export.")
+            .await?;
+        return Ok(());
+    }
+
+    let (contents, export_name) = if plain_text {
+        (transcript.to_plain_text(), format!("telegram-{chat_id}.txt"))
+    } else {
+        (transcript.to_markdown(), format!("telegram-{chat_id}.md"))
+    };
+
+    let input = InputFile::memory(contents.into_bytes()).file_name(export_name);
+    bot.send_document(msg.chat.id, input).await?;
+    Ok(())
+}
+
+/// Aborts whatever `pi` is currently generating for this chat's live
+/// session, without killing the whole process the way `/kill` does for
+/// `/shell` sessions.
+async fn handle_stop(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    match state.live_sessions.lock().await.cancel(chat_id).await {
+        Ok(true) => {
+            bot.send_message(msg.chat.id, "Stopping the current generation...")
+                .await?;
+        }
+        Ok(false) => {
+            bot.send_message(msg.chat.id, "Nothing to stop.").await?;
+        }
+        Err(_) => {
+            bot.send_message(msg.chat.id, "No active live session.")
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Stages `text` as the chat's pending web context, so the next call to
+/// `handle_text_oneshot`/`handle_text_live` in `handlers.rs` prepends it to
+/// the Pi prompt, then clears it.
+async fn stage_web_context(state: &AppState, chat_id: i64, text: &str) {
+    let prior_chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+    let _ = state
+        .storage
+        .set(
+            chat_id,
+            ChatState {
+                pending_web_context: Some(text.to_string()),
+                ..prior_chat_state
+            },
+        )
+        .await;
+}
+
+async fn handle_read(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let url = arg.trim();
+    if url.is_empty() {
+        bot.send_message(msg.chat.id, "Usage: /read <url>").await?;
+        return Ok(());
+    }
+
+    bot.send_chat_action(msg.chat.id, teloxide::types::ChatAction::Typing)
+        .await?;
+
+    match read_page(msg.chat.id.0, url).await {
+        Ok(content) => {
+            state.browser_pool.touch(msg.chat.id.0, Some(url.to_string())).await;
+            stage_web_context(&state, msg.chat.id.0, &content).await;
+            for chunk in split_message(&content) {
+                bot.send_message(msg.chat.id, chunk).await?;
+            }
+            bot.send_message(
+                msg.chat.id,
+                "(staged as context \u{2014} your next message to Pi will include it)",
+            )
+            .await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("Failed to read page: {e}"))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn handle_extract(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let mut parts = arg.trim().splitn(2, char::is_whitespace);
+    let (url, selector) = match (parts.next(), parts.next().map(str::trim)) {
+        (Some(url), Some(selector)) if !url.is_empty() && !selector.is_empty() => (url, selector),
+        _ => {
+            bot.send_message(msg.chat.id, "Usage: /extract <url> <selector>")
+                .await?;
+            return Ok(());
+        }
+    };
+
+    bot.send_chat_action(msg.chat.id, teloxide::types::ChatAction::Typing)
+        .await?;
+
+    match extract_selector(msg.chat.id.0, url, selector).await {
+        Ok(text) => {
+            state.browser_pool.touch(msg.chat.id.0, Some(url.to_string())).await;
+            stage_web_context(&state, msg.chat.id.0, &text).await;
+            for chunk in split_message(&text) {
+                bot.send_message(msg.chat.id, chunk).await?;
+            }
+            bot.send_message(
+                msg.chat.id,
+                "(staged as context \u{2014} your next message to Pi will include it)",
+            )
+            .await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("Failed to extract \"{selector}\": {e}"))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn handle_snapshot(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let url = arg.trim();
+    if url.is_empty() {
+        bot.send_message(msg.chat.id, "Usage: /snapshot <url>")
+            .await?;
+        return Ok(());
+    }
+
+    bot.send_chat_action(msg.chat.id, teloxide::types::ChatAction::Typing)
+        .await?;
+
+    match snapshot_page(msg.chat.id.0, url).await {
+        Ok(snapshot) => {
+            state.browser_pool.touch(msg.chat.id.0, Some(url.to_string())).await;
+            stage_web_context(&state, msg.chat.id.0, &snapshot).await;
+            for chunk in split_message(&snapshot) {
+                bot.send_message(msg.chat.id, chunk).await?;
+            }
+            bot.send_message(
+                msg.chat.id,
+                "(staged as context \u{2014} your next message to Pi will include it)",
+            )
+            .await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("Failed to snapshot page: {e}"))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
 async fn handle_session(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
     bot.send_chat_action(msg.chat.id, teloxide::types::ChatAction::Typing)
         .await?;
@@ -200,7 +918,7 @@ async fn handle_session(bot: Bot, msg: Message, state: AppState) -> anyhow::Resu
     let mut sessions_with_titles = Vec::new();
     for session in sessions.iter().take(10) {
         let title =
-            generate_session_title(&session.path, state.config.session_title_timeout_ms).await;
+            generate_session_title(&session.path, state.config.session_title_timeout).await;
         sessions_with_titles.push((session, title));
     }
 
@@ -249,12 +967,29 @@ async fn handle_new(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<(
         }
     }
 
+    // Stop watch mode if active; it would otherwise keep rerunning Pi
+    // against the session we're about to archive.
+    state.workspace_watcher.stop(chat_id).await;
+
     // Acquire lock to prevent concurrent Pi access
     let _guard = state.chat_locks.acquire(chat_id).await;
 
     let archived = archive_session(&state.config, chat_id).await;
     state.session_mgr.lock().await.clear_active_session(chat_id).await;
 
+    let prior_chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+    let _ = state
+        .storage
+        .set(
+            chat_id,
+            ChatState {
+                last_session_line_count: 0,
+                live_mode: false,
+                ..prior_chat_state
+            },
+        )
+        .await;
+
     let reply = if let Some(name) = archived {
         format!("Session archived as {name}\nStarting fresh conversation.")
     } else {
@@ -267,9 +1002,30 @@ async fn handle_new(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<(
 
 async fn handle_status(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
     let pi_ok = check_pi_auth().await;
-    let cwd = state.workspace_mgr.lock().await.get_workspace(msg.chat.id.0).await;
-    let formatted = WorkspaceManager::format_path(&cwd);
+    let chat_id = msg.chat.id.0;
+    let active_target = state.host_mgr.lock().await.active_target(chat_id).await;
+    let (target_line, formatted) = match &active_target {
+        ActiveTarget::Local => {
+            let cwd = state.workspace_mgr.lock().await.get_workspace(chat_id).await;
+            ("local".to_string(), WorkspaceManager::format_path(&cwd))
+        }
+        ActiveTarget::Remote(name) => {
+            let cwd = state.host_mgr.lock().await.remote_cwd(chat_id).await;
+            (name.clone(), cwd)
+        }
+    };
     let live_active = state.live_sessions.lock().await.is_active(msg.chat.id.0);
+    let watch_active = state.workspace_watcher.is_watching(msg.chat.id.0).await;
+    let browser_status = state.browser_pool.status(msg.chat.id.0).await;
+    let browser_line = match (browser_status.connected, browser_status.url) {
+        (true, Some(url)) => format!(
+            "connected, idle {}s, last page: {url}",
+            browser_status.idle_secs.unwrap_or(0)
+        ),
+        (true, None) => format!("connected, idle {}s", browser_status.idle_secs.unwrap_or(0)),
+        (false, _) => "not connected".to_string(),
+    };
+    let thinking_level = state.thinking_level(msg.chat.id.0).await;
 
     bot.send_message(
         msg.chat.id,
@@ -277,11 +1033,16 @@ async fn handle_status(bot: Bot, msg: Message, state: AppState) -> anyhow::Resul
             "Status:\n\
             - Pi: {}\n\
             - Chat ID: {}\n\
+            - Target: {target_line}\n\
             - Workspace: {formatted}\n\
-            - Live mode: {}",
+            - Live mode: {}\n\
+            - Watch mode: {}\n\
+            - Thinking level: {thinking_level}\n\
+            - Browser: {browser_line}",
             if pi_ok { "OK" } else { "Not available" },
             msg.chat.id,
             if live_active { "ON" } else { "OFF" },
+            if watch_active { "ON" } else { "OFF" },
         ),
     )
     .await?;
@@ -302,13 +1063,28 @@ async fn handle_live(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyh
 
             tokio::fs::create_dir_all(&state.config.session_dir).await?;
 
+            let thinking_level = state.thinking_level(chat_id).await;
             match state.live_sessions.lock().await.start_session(
                 chat_id,
                 &session_path,
                 &workspace,
-                state.config.thinking_level,
+                thinking_level,
+                state.config.pi_rpc_pty,
             ).await {
                 Ok(_) => {
+                    let prior_chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+                    let _ = state
+                        .storage
+                        .set(
+                            chat_id,
+                            ChatState {
+                                workspace: Some(workspace),
+                                live_mode: true,
+                                ..prior_chat_state
+                            },
+                        )
+                        .await;
+
                     bot.send_message(
                         msg.chat.id,
                         "\u{1f534} Live mode enabled!\nPi is now running persistently. Messages go directly to the active session.\nUse /live off to disable.",
@@ -323,6 +1099,19 @@ async fn handle_live(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyh
         }
         "off" | "" if is_active => {
             state.live_sessions.lock().await.stop_session(chat_id).await;
+
+            let prior_chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+            let _ = state
+                .storage
+                .set(
+                    chat_id,
+                    ChatState {
+                        live_mode: false,
+                        ..prior_chat_state
+                    },
+                )
+                .await;
+
             bot.send_message(
                 msg.chat.id,
                 "Live mode disabled. Switched back to one-shot mode.",
@@ -350,4 +1139,190 @@ async fn handle_live(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyh
     Ok(())
 }
 
+async fn handle_watch(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    let arg = arg.trim().to_lowercase();
+    let is_watching = state.workspace_watcher.is_watching(chat_id).await;
+
+    match arg.as_str() {
+        "on" | "" if !is_watching => {
+            let workspace = state.workspace_mgr.lock().await.get_workspace(chat_id).await;
+            let bot_for_updates = bot.clone();
+            let chat = msg.chat.id;
+            let result = state
+                .workspace_watcher
+                .start(
+                    chat_id,
+                    state.config.clone(),
+                    state.chat_locks.clone(),
+                    workspace,
+                    "Files changed in the workspace. Review the changes and continue.".to_string(),
+                    move |update| {
+                        let bot = bot_for_updates.clone();
+                        let text = if update.detail.is_empty() {
+                            "\u{1f440} Watch: rerunning Pi...".to_string()
+                        } else {
+                            format!("\u{1f440} Watch: {}", update.detail)
+                        };
+                        tokio::spawn(async move {
+                            let _ = bot.send_message(chat, text).await;
+                        });
+                    },
+                )
+                .await;
+
+            match result {
+                Ok(()) => {
+                    bot.send_message(
+                        msg.chat.id,
+                        "\u{1f440} Watch mode enabled!\nPi will rerun automatically when files in the workspace change.\nUse /watch off to disable.",
+                    )
+                    .await?;
+                }
+                Err(e) => {
+                    bot.send_message(msg.chat.id, format!("Failed to start watch mode: {e}"))
+                        .await?;
+                }
+            }
+        }
+        "off" | "" if is_watching => {
+            state.workspace_watcher.stop(chat_id).await;
+            bot.send_message(msg.chat.id, "Watch mode disabled.").await?;
+        }
+        "status" => {
+            let status = if is_watching { "ON" } else { "OFF" };
+            bot.send_message(msg.chat.id, format!("Watch mode: {status}"))
+                .await?;
+        }
+        "" => {
+            unreachable!()
+        }
+        _ => {
+            bot.send_message(
+                msg.chat.id,
+                "Usage: /watch [on|off|status]\nNo argument toggles the mode.",
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_lang(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    let arg = arg.trim();
+
+    if arg.is_empty() {
+        let chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+        let lang = chat_state
+            .lang
+            .unwrap_or_else(|| state.i18n.default_lang().to_string());
+        let mut args = fluent::FluentArgs::new();
+        args.set("lang", lang);
+        let text = state.t(chat_id, "language-current", Some(&args)).await;
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let Ok(lang_id) = arg.parse::<unic_langid::LanguageIdentifier>() else {
+        bot.send_message(msg.chat.id, format!("Invalid language tag: {arg}"))
+            .await?;
+        return Ok(());
+    };
+
+    if !state.i18n.has_locale(&lang_id) {
+        let mut args = fluent::FluentArgs::new();
+        args.set("lang", arg.to_string());
+        let text = state.t(chat_id, "language-unknown", Some(&args)).await;
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    let mut chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+    chat_state.lang = Some(arg.to_string());
+    let _ = state.storage.set(chat_id, chat_state).await;
+
+    let mut args = fluent::FluentArgs::new();
+    args.set("lang", arg.to_string());
+    let text = state.t(chat_id, "language-set", Some(&args)).await;
+    bot.send_message(msg.chat.id, text).await?;
+    Ok(())
+}
+
+async fn handle_settings(bot: Bot, msg: Message, state: AppState, arg: &str) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+    let arg = arg.trim();
+
+    if arg.is_empty() {
+        let settings = state.settings_mgr.lock().await.get(chat_id).await;
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "\u{2699}\u{fe0f} Chat settings:\n\
+                stream_preview = {}\n\
+                status_throttle_ms = {}\n\
+                preview_chars = {}\n\
+                send_tool_images = {}\n\n\
+                Usage: /settings <key> <value>",
+                settings.stream_preview,
+                settings.status_throttle_ms,
+                settings.preview_chars,
+                settings.send_tool_images
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut parts = arg.splitn(2, char::is_whitespace);
+    let key = parts.next().unwrap_or("").to_lowercase();
+    let value = parts.next().unwrap_or("").trim();
+
+    let mut mgr = state.settings_mgr.lock().await;
+    let result = mgr
+        .update(chat_id, |s| match key.as_str() {
+            "stream_preview" => s.stream_preview = value.eq_ignore_ascii_case("on"),
+            "status_throttle_ms" => {
+                if let Ok(n) = value.parse() {
+                    s.status_throttle_ms = n;
+                }
+            }
+            "preview_chars" => {
+                if let Ok(n) = value.parse() {
+                    s.preview_chars = n;
+                }
+            }
+            "send_tool_images" => s.send_tool_images = value.eq_ignore_ascii_case("on"),
+            _ => {}
+        })
+        .await;
+    drop(mgr);
+
+    match result {
+        Ok(_) if matches!(
+            key.as_str(),
+            "stream_preview" | "status_throttle_ms" | "preview_chars" | "send_tool_images"
+        ) => {
+            bot.send_message(msg.chat.id, format!("{key} updated."))
+                .await?;
+        }
+        Ok(_) => {
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "Unknown setting: {key}\nValid keys: stream_preview, status_throttle_ms, preview_chars, send_tool_images"
+                ),
+            )
+            .await?;
+        }
+        Err(e) => {
+            bot.send_message(msg.chat.id, format!("Failed to save settings: {e}"))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
 // This is used by pi_runner lock - re-export for use outside the module
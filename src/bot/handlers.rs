@@ -1,18 +1,23 @@
 use std::sync::Arc;
 
 use teloxide::prelude::*;
-use teloxide::types::{ChatAction, InputFile};
+use teloxide::types::{ChatAction, ChatId, InputFile};
 use tokio::time::{Duration, Instant};
 
-use super::util::split_message;
+use super::util::{split_message, take_complete_paragraph};
 use super::AppState;
+use super::media::{send_output_batch, OutputItem};
+use crate::config::ThinkingLevel;
 use crate::file_detector::{detect_files, snapshot_workspace};
-use crate::markdown::{markdown_to_html, strip_markdown};
+use crate::markdown::{floor_char_boundary, markdown_to_html, strip_markdown};
+use crate::media_group::GroupedFile;
+use crate::hosts::ActiveTarget;
 use crate::pi_rpc::PiEvent;
 use crate::pi_runner::{
-    extract_images_from_session, get_session_line_count, run_pi_with_streaming, ActivityType,
-    ActivityUpdate, RunPiOptions,
+    extract_images_from_session_via, get_session_line_count, get_session_line_count_via,
+    run_pi_with_streaming, ActivityType, ActivityUpdate, RunPiOptions,
 };
+use crate::storage::{ChatState, Storage};
 
 pub async fn handle_text(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
     let text = match msg.text() {
@@ -25,33 +30,65 @@ pub async fn handle_text(bot: Bot, msg: Message, state: AppState) -> anyhow::Res
         return Ok(());
     }
 
+    let chat_id = msg.chat.id.0;
+
     // Access control
-    if !state.check_access(&msg) {
-        bot.send_message(msg.chat.id, "Sorry, you are not authorized to use this bot.")
-            .await?;
+    if !state.check_access(&msg).await {
+        let text = state.t(chat_id, "not-authorized", None).await;
+        bot.send_message(msg.chat.id, text).await?;
         return Ok(());
     }
 
-    let chat_id = msg.chat.id.0;
+    // Forward to an active interactive shell session as keystrokes instead
+    // of treating it as an AI prompt, so /shell-spawned PTYs (vim, ssh,
+    // etc.) can be typed into directly until the process exits or /kill.
+    if let Some(shell) = state.pty_shells.get(chat_id).await {
+        let stdin = format!("{text}\n");
+        if let Err(e) = shell.lock().await.write_stdin(stdin.as_bytes()) {
+            bot.send_message(msg.chat.id, format!("Failed to send input: {e}"))
+                .await?;
+        }
+        return Ok(());
+    }
 
     // Rate limiting
     {
+        let cooldown_ms = state.cooldown_ms(&msg).await;
         let mut limiter = state.rate_limiter.lock().await;
-        let result = limiter.check(chat_id, state.config.rate_limit_cooldown_ms);
+        let result = limiter
+            .check(&*state.storage, chat_id, cooldown_ms)
+            .await;
         if !result.allowed {
             let secs = result.retry_after_ms.unwrap_or(0).div_ceil(1000);
-            bot.send_message(
-                msg.chat.id,
-                format!("\u{23f3} Please wait {secs}s before sending another message."),
-            )
-            .await?;
+            let mut args = fluent::FluentArgs::new();
+            args.set("secs", secs);
+            let text = state.t(chat_id, "rate-limited", Some(&args)).await;
+            bot.send_message(msg.chat.id, text).await?;
             return Ok(());
         }
     }
 
-    // Check if live mode is active
+    // Load persisted state. This is mostly a resume check: if the flag
+    // says live mode was active but the in-memory session manager (which
+    // can't survive a restart) disagrees, the process crashed mid-session
+    // and we fall back to one-shot rather than claim to be live.
+    let chat_state = state.storage.get(chat_id).await.unwrap_or_default();
     let live_active = state.live_sessions.lock().await.is_active(chat_id);
 
+    if chat_state.live_mode && !live_active {
+        tracing::warn!(chat_id, "Live mode flag left set by a previous crash; clearing it");
+        let _ = state
+            .storage
+            .set(
+                chat_id,
+                ChatState {
+                    live_mode: false,
+                    ..chat_state
+                },
+            )
+            .await;
+    }
+
     if live_active {
         handle_text_live(bot, msg, state, &text).await
     } else {
@@ -66,13 +103,37 @@ async fn handle_text_live(
     text: &str,
 ) -> anyhow::Result<()> {
     let chat_id = msg.chat.id.0;
+    let settings = state.settings_mgr.lock().await.get(chat_id).await;
+
+    let workspace = state
+        .workspace_mgr
+        .lock()
+        .await
+        .get_workspace(chat_id)
+        .await;
+    let mut chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+    chat_state.workspace = Some(workspace);
+    chat_state.live_mode = true;
+
+    // If `/read`, `/extract`, or `/snapshot` staged web context, prepend it
+    // to this prompt once, then clear it so it isn't reused on later turns.
+    let prompt = match chat_state.pending_web_context.take() {
+        Some(context) => format!("Context from a web page:\n{context}\n\n{text}"),
+        None => text.to_string(),
+    };
+
+    let _ = state.storage.set(chat_id, chat_state.clone()).await;
 
     // Send prompt to persistent RPC process
     {
         let mut live = state.live_sessions.lock().await;
-        if let Err(e) = live.send_prompt(chat_id, text).await {
-            bot.send_message(msg.chat.id, format!("Live mode error: {e}"))
-                .await?;
+        if let Err(e) = live.send_prompt(chat_id, &prompt).await {
+            chat_state.live_mode = false;
+            let _ = state.storage.set(chat_id, chat_state).await;
+            let mut args = fluent::FluentArgs::new();
+            args.set("error", e.to_string());
+            let text = state.t(chat_id, "live-mode-error", Some(&args)).await;
+            bot.send_message(msg.chat.id, text).await?;
             return Ok(());
         }
     }
@@ -97,26 +158,60 @@ async fn handle_text_live(
 
     // Accumulate response text from events
     let mut accumulated_text = String::new();
+    let mut flushed_len = 0usize;
     let mut last_status_update = Instant::now();
     let mut current_status = "\u{1f534} LIVE | Working...".to_string();
 
     loop {
-        let event = {
+        let (event, detected_files) = {
             let mut live = state.live_sessions.lock().await;
             // Use a timeout so we don't hold the lock forever
-            tokio::time::timeout(Duration::from_millis(100), live.recv_event(chat_id)).await
+            let event = tokio::time::timeout(Duration::from_millis(100), live.recv_event(chat_id)).await;
+            (event, live.try_recv_detected_files(chat_id))
         };
 
+        if let Some(files) = detected_files {
+            let outputs = build_output_items(Vec::new(), files);
+            let _ = send_output_batch(&bot, msg.chat.id, outputs).await;
+        }
+
         match event {
-            Ok(Some(PiEvent::TextDelta(delta))) => {
+            Ok(Some(PiEvent::TextDelta { delta, .. })) => {
                 accumulated_text.push_str(&delta);
 
+                // Flush any paragraph that's now complete (ends in a blank
+                // line) as a real message, so the user sees answer content
+                // as it's written instead of only a truncated preview.
+                if let Some((paragraph, consumed)) =
+                    take_complete_paragraph(&accumulated_text[flushed_len..])
+                {
+                    for chunk in split_message(paragraph.trim()) {
+                        match bot
+                            .send_message(msg.chat.id, markdown_to_html(&chunk))
+                            .parse_mode(teloxide::types::ParseMode::Html)
+                            .await
+                        {
+                            Ok(_) => {}
+                            Err(_) => {
+                                bot.send_message(msg.chat.id, strip_markdown(&chunk))
+                                    .await?;
+                            }
+                        }
+                    }
+                    flushed_len += consumed;
+                }
+
                 // Throttle status updates
-                if last_status_update.elapsed() > Duration::from_secs(2) {
-                    let preview = if accumulated_text.len() > 100 {
-                        format!("{}...", &accumulated_text[..100])
+                if settings.stream_preview
+                    && last_status_update.elapsed()
+                        > Duration::from_millis(settings.status_throttle_ms)
+                {
+                    let unflushed = &accumulated_text[flushed_len..];
+                    let preview = if unflushed.len() > settings.preview_chars {
+                        let end = floor_char_boundary(unflushed, settings.preview_chars);
+                        format!("{}...", &unflushed[..end])
                     } else {
-                        accumulated_text.clone()
+                        unflushed.to_string()
                     };
                     let new_status = format!("\u{1f534} LIVE | \u{270d}\u{fe0f} {preview}");
                     if new_status != current_status {
@@ -128,7 +223,7 @@ async fn handle_text_live(
                     }
                 }
             }
-            Ok(Some(PiEvent::ToolStart { name })) => {
+            Ok(Some(PiEvent::ToolStart { name, .. })) => {
                 let new_status =
                     format!("\u{1f534} LIVE | \u{26a1} Running {name}...");
                 if new_status != current_status {
@@ -139,9 +234,11 @@ async fn handle_text_live(
                     last_status_update = Instant::now();
                 }
             }
-            Ok(Some(PiEvent::AgentEnd)) => break,
-            Ok(Some(PiEvent::Error(e))) => {
+            Ok(Some(PiEvent::AgentEnd { .. })) => break,
+            Ok(Some(PiEvent::Error { message: e, .. })) => {
                 typing_handle.abort();
+                chat_state.live_mode = false;
+                let _ = state.storage.set(chat_id, chat_state).await;
                 let _ = bot.delete_message(msg.chat.id, status_msg.id).await;
                 bot.send_message(msg.chat.id, format!("Error: {e}"))
                     .await?;
@@ -155,12 +252,17 @@ async fn handle_text_live(
 
     typing_handle.abort();
 
+    chat_state.last_session_line_count = get_session_line_count(&state.config, chat_id).await;
+    chat_state.live_mode = false;
+    let _ = state.storage.set(chat_id, chat_state).await;
+
     // Delete status message
     let _ = bot.delete_message(msg.chat.id, status_msg.id).await;
 
-    // Send accumulated response
-    if !accumulated_text.is_empty() {
-        let chunks = split_message(accumulated_text.trim());
+    // Send whatever text hasn't already been flushed as a complete paragraph
+    let remainder = accumulated_text[flushed_len..].trim();
+    if !remainder.is_empty() {
+        let chunks = split_message(remainder);
         for chunk in chunks {
             match bot
                 .send_message(msg.chat.id, markdown_to_html(&chunk))
@@ -186,6 +288,7 @@ async fn handle_text_oneshot(
     text: &str,
 ) -> anyhow::Result<()> {
     let chat_id = msg.chat.id.0;
+    let settings = state.settings_mgr.lock().await.get(chat_id).await;
 
     let workspace = state
         .workspace_mgr
@@ -195,16 +298,41 @@ async fn handle_text_oneshot(
         .await;
     let workspace_str = workspace.to_string_lossy().to_string();
 
+    // Resolve the chat's active `/host` target so Pi runs wherever `/shell`
+    // already does, not always on this machine.
+    let active = state.host_mgr.lock().await.active_target(chat_id).await;
+    let exec_cwd = match &active {
+        ActiveTarget::Local => workspace_str.clone(),
+        ActiveTarget::Remote(_) => state.host_mgr.lock().await.remote_cwd(chat_id).await,
+    };
+    let backend = state.exec_session(chat_id).await;
+
+    // If `/read`, `/extract`, or `/snapshot` staged web context, prepend it
+    // to this prompt once, then clear it so it isn't reused on later turns.
+    let mut chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+    let thinking_level = chat_state
+        .thinking_level
+        .as_deref()
+        .map(ThinkingLevel::from_str)
+        .unwrap_or(state.config.thinking_level);
+    let prompt = match chat_state.pending_web_context.take() {
+        Some(context) => {
+            let _ = state.storage.set(chat_id, chat_state).await;
+            format!("Context from a web page:\n{context}\n\n{text}")
+        }
+        None => text.to_string(),
+    };
+
     // Snapshot workspace before execution
     let before_snapshot = snapshot_workspace(&workspace).await;
 
     // Track session line count for image extraction
-    let session_lines_before = get_session_line_count(&state.config, chat_id).await;
+    let session_lines_before =
+        get_session_line_count_via(backend.as_ref(), &state.config, chat_id).await;
 
     // Send initial status message
-    let status_msg = bot
-        .send_message(msg.chat.id, "\u{1f504} Working...")
-        .await?;
+    let working_text = state.t(chat_id, "working", None).await;
+    let status_msg = bot.send_message(msg.chat.id, working_text).await?;
 
     let last_status_update = Arc::new(std::sync::Mutex::new(Instant::now()));
 
@@ -226,9 +354,10 @@ async fn handle_text_oneshot(
     let status_msg_id = status_msg.id;
     let last_update = last_status_update.clone();
 
+    let status_throttle_ms = settings.status_throttle_ms;
     let on_activity = move |activity: ActivityUpdate| {
         let mut last = last_update.lock().unwrap();
-        if last.elapsed() < Duration::from_secs(2) {
+        if last.elapsed() < Duration::from_millis(status_throttle_ms) {
             return;
         }
         *last = Instant::now();
@@ -261,21 +390,68 @@ async fn handle_text_oneshot(
         }
     });
 
-    // Acquire lock and run Pi
+    // Acquire lock, then a global run permit so the host only ever runs
+    // `max_concurrent_runs` Pi processes at once. While waiting, the status
+    // message shows queue position instead of "Working...".
     let _guard = state.chat_locks.acquire(chat_id).await;
+    let bot_queue = bot.clone();
+    let chat_id_queue = msg.chat.id;
+    let status_msg_id_queue = status_msg.id;
+    let _permit = state
+        .run_queue
+        .acquire(move |position| {
+            let bot_inner = bot_queue.clone();
+            let text = if position == 0 {
+                "\u{23f3} Queued...".to_string()
+            } else {
+                format!("\u{23f3} Queued ({position} ahead)...")
+            };
+            tokio::spawn(async move {
+                let _ = bot_inner
+                    .edit_message_text(chat_id_queue, status_msg_id_queue, text)
+                    .await;
+            });
+        })
+        .await;
 
     let result = run_pi_with_streaming(
+        backend.as_ref(),
         &state.config,
         chat_id,
-        text,
-        &workspace_str,
+        &prompt,
+        &exec_cwd,
         on_activity,
-        None,
+        Some(RunPiOptions {
+            thinking_level: Some(thinking_level),
+            ..Default::default()
+        }),
     )
     .await;
 
     typing_handle.abort();
 
+    // Persist on completion (including error exits) so a crash mid-run
+    // leaves the next message resuming from an accurate line count rather
+    // than replaying tool images/activity we already delivered.
+    let prior_chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+    let _ = state
+        .storage
+        .set(
+            chat_id,
+            ChatState {
+                workspace: Some(workspace.clone()),
+                last_session_line_count: get_session_line_count_via(
+                    backend.as_ref(),
+                    &state.config,
+                    chat_id,
+                )
+                .await,
+                live_mode: false,
+                ..prior_chat_state
+            },
+        )
+        .await;
+
     // Delete status message
     let _ = bot.delete_message(msg.chat.id, status_msg.id).await;
 
@@ -303,48 +479,23 @@ async fn handle_text_oneshot(
         }
     }
 
-    // Extract and send tool images from session
-    let tool_images =
-        extract_images_from_session(&state.config, chat_id, session_lines_before).await;
-
-    for (i, img) in tool_images.iter().enumerate() {
-        let ext = img
-            .mime_type
-            .split('/')
-            .nth(1)
-            .unwrap_or("png");
-        let filename = format!("image_{}.{ext}", i + 1);
-        let input = InputFile::memory(img.data.clone()).file_name(filename);
-        if let Err(e) = bot.send_photo(msg.chat.id, input).await {
-            tracing::error!("Failed to send tool image: {e}");
-        }
-    }
-
-    // Detect and send new files from workspace
-    let detected_files = detect_files(&result.output, &workspace, &before_snapshot).await;
-
-    for file in detected_files {
-        let path_str = file.path.to_string_lossy().to_string();
-        let send_result = match file.file_type {
-            crate::file_detector::DetectedFileType::Photo => {
-                bot.send_photo(msg.chat.id, InputFile::file(&path_str))
-                    .caption(&file.filename)
-                    .await
-                    .map(|_| ())
-            }
-            crate::file_detector::DetectedFileType::Document => {
-                bot.send_document(msg.chat.id, InputFile::file(&path_str))
-                    .caption(&file.filename)
-                    .await
-                    .map(|_| ())
-            }
-        };
-        if send_result.is_err() {
-            bot.send_message(
-                msg.chat.id,
-                format!("(Could not send file: {})", file.filename),
-            )
-            .await?;
+    if settings.send_tool_images {
+        let tool_images = extract_images_from_session_via(
+            backend.as_ref(),
+            &state.config,
+            chat_id,
+            session_lines_before,
+        )
+        .await;
+        let detected_files = detect_files(&result.output, &workspace, &before_snapshot).await;
+        let outputs = build_output_items(tool_images, detected_files);
+
+        let failed = send_output_batch(&bot, msg.chat.id, outputs).await;
+        for filename in failed {
+            let mut args = fluent::FluentArgs::new();
+            args.set("filename", filename);
+            let text = state.t(chat_id, "could-not-send-file", Some(&args)).await;
+            bot.send_message(msg.chat.id, text).await?;
         }
     }
 
@@ -352,26 +503,28 @@ async fn handle_text_oneshot(
 }
 
 pub async fn handle_photo(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+
     // Access control
-    if !state.check_access(&msg) {
-        bot.send_message(msg.chat.id, "Sorry, you are not authorized to use this bot.")
-            .await?;
+    if !state.check_access(&msg).await {
+        let text = state.t(chat_id, "not-authorized", None).await;
+        bot.send_message(msg.chat.id, text).await?;
         return Ok(());
     }
 
-    let chat_id = msg.chat.id.0;
-
     // Rate limiting
     {
+        let cooldown_ms = state.cooldown_ms(&msg).await;
         let mut limiter = state.rate_limiter.lock().await;
-        let result = limiter.check(chat_id, state.config.rate_limit_cooldown_ms);
+        let result = limiter
+            .check(&*state.storage, chat_id, cooldown_ms)
+            .await;
         if !result.allowed {
             let secs = result.retry_after_ms.unwrap_or(0).div_ceil(1000);
-            bot.send_message(
-                msg.chat.id,
-                format!("Please wait {secs}s before sending another message."),
-            )
-            .await?;
+            let mut args = fluent::FluentArgs::new();
+            args.set("secs", secs);
+            let text = state.t(chat_id, "rate-limited", Some(&args)).await;
+            bot.send_message(msg.chat.id, text).await?;
             return Ok(());
         }
     }
@@ -400,6 +553,46 @@ pub async fn handle_photo(bot: Bot, msg: Message, state: AppState) -> anyhow::Re
     let response = reqwest::get(&file_url).await?;
     let image_bytes = response.bytes().await?;
 
+    // Part of an album: write into the workspace (not a temp dir, so it
+    // survives alongside whatever else the run touches) and buffer it with
+    // the rest of the group instead of running Pi on just this one photo.
+    if let Some(media_group_id) = msg.media_group_id().map(|s| s.to_string()) {
+        let workspace = state
+            .workspace_mgr
+            .lock()
+            .await
+            .get_workspace(chat_id)
+            .await;
+        let ext = file_path.split('.').next_back().unwrap_or("jpg");
+        let filename = format!("photo-{}.{ext}", chrono_timestamp());
+        let dest_path = workspace.join(&filename);
+        tokio::fs::write(&dest_path, &image_bytes).await?;
+
+        let group_caption = msg.caption().map(|s| s.to_string());
+        let group_file = GroupedFile {
+            path: dest_path,
+            filename,
+            is_image: true,
+        };
+        let bot_ready = bot.clone();
+        let chat = msg.chat.id;
+        let state_ready = state.clone();
+        state
+            .media_groups
+            .add(
+                media_group_id,
+                group_file,
+                group_caption,
+                move |files, caption| {
+                    tokio::spawn(async move {
+                        let _ = run_grouped_files(bot_ready, chat, state_ready, files, caption).await;
+                    });
+                },
+            )
+            .await;
+        return Ok(());
+    }
+
     // Save to temp file
     let ext = file_path.split('.').next_back().unwrap_or("jpg");
     let temp_dir = std::env::temp_dir().join("mini-claw");
@@ -408,9 +601,8 @@ pub async fn handle_photo(bot: Bot, msg: Message, state: AppState) -> anyhow::Re
     tokio::fs::write(&temp_path, &image_bytes).await?;
 
     // Send status
-    let status_msg = bot
-        .send_message(msg.chat.id, "\u{1f504} Analyzing image...")
-        .await?;
+    let analyzing_text = state.t(chat_id, "analyzing-image", None).await;
+    let status_msg = bot.send_message(msg.chat.id, analyzing_text).await?;
 
     let workspace = state
         .workspace_mgr
@@ -420,6 +612,15 @@ pub async fn handle_photo(bot: Bot, msg: Message, state: AppState) -> anyhow::Re
         .await;
     let workspace_str = workspace.to_string_lossy().to_string();
 
+    // Resolve the chat's active `/host` target so Pi runs wherever `/shell`
+    // already does, not always on this machine.
+    let active = state.host_mgr.lock().await.active_target(chat_id).await;
+    let exec_cwd = match &active {
+        ActiveTarget::Local => workspace_str.clone(),
+        ActiveTarget::Remote(_) => state.host_mgr.lock().await.remote_cwd(chat_id).await,
+    };
+    let backend = state.exec_session(chat_id).await;
+
     let before_snapshot = snapshot_workspace(&workspace).await;
 
     // Activity callback (simplified for photo)
@@ -463,18 +664,40 @@ pub async fn handle_photo(bot: Bot, msg: Message, state: AppState) -> anyhow::Re
         });
     };
 
-    // Acquire lock and run Pi with image
+    // Acquire lock, then a global run permit so the host only ever runs
+    // `max_concurrent_runs` Pi processes at once.
     let _guard = state.chat_locks.acquire(chat_id).await;
+    let bot_queue = bot.clone();
+    let chat_id_queue = msg.chat.id;
+    let status_msg_id_queue = status_msg.id;
+    let _permit = state
+        .run_queue
+        .acquire(move |position| {
+            let bot_inner = bot_queue.clone();
+            let text = if position == 0 {
+                "\u{23f3} Queued...".to_string()
+            } else {
+                format!("\u{23f3} Queued ({position} ahead)...")
+            };
+            tokio::spawn(async move {
+                let _ = bot_inner
+                    .edit_message_text(chat_id_queue, status_msg_id_queue, text)
+                    .await;
+            });
+        })
+        .await;
 
     let options = RunPiOptions {
         image_paths: vec![temp_path.clone()],
+        thinking_level: Some(state.thinking_level(chat_id).await),
     };
 
     let result = run_pi_with_streaming(
+        backend.as_ref(),
         &state.config,
         chat_id,
         &caption,
-        &workspace_str,
+        &exec_cwd,
         on_activity,
         Some(options),
     )
@@ -509,30 +732,322 @@ pub async fn handle_photo(bot: Bot, msg: Message, state: AppState) -> anyhow::Re
 
     // Detect and send files
     let detected_files = detect_files(&result.output, &workspace, &before_snapshot).await;
-    for file in detected_files {
-        let path_str = file.path.to_string_lossy().to_string();
-        match file.file_type {
-            crate::file_detector::DetectedFileType::Photo => {
-                let _ = bot
-                    .send_photo(msg.chat.id, InputFile::file(&path_str))
-                    .caption(&file.filename)
-                    .await;
-            }
-            crate::file_detector::DetectedFileType::Document => {
-                let _ = bot
-                    .send_document(msg.chat.id, InputFile::file(&path_str))
-                    .caption(&file.filename)
+    let outputs = build_output_items(Vec::new(), detected_files);
+    let _ = send_output_batch(&bot, msg.chat.id, outputs).await;
+
+    // Clean up temp file
+    let _ = tokio::fs::remove_file(&temp_path).await;
+
+    Ok(())
+}
+
+pub async fn handle_document(bot: Bot, msg: Message, state: AppState) -> anyhow::Result<()> {
+    let chat_id = msg.chat.id.0;
+
+    // Access control
+    if !state.check_access(&msg).await {
+        let text = state.t(chat_id, "not-authorized", None).await;
+        bot.send_message(msg.chat.id, text).await?;
+        return Ok(());
+    }
+
+    // Rate limiting
+    {
+        let cooldown_ms = state.cooldown_ms(&msg).await;
+        let mut limiter = state.rate_limiter.lock().await;
+        let result = limiter
+            .check(&*state.storage, chat_id, cooldown_ms)
+            .await;
+        if !result.allowed {
+            let secs = result.retry_after_ms.unwrap_or(0).div_ceil(1000);
+            let mut args = fluent::FluentArgs::new();
+            args.set("secs", secs);
+            let text = state.t(chat_id, "rate-limited", Some(&args)).await;
+            bot.send_message(msg.chat.id, text).await?;
+            return Ok(());
+        }
+    }
+
+    let Some(document) = msg.document() else {
+        return Ok(());
+    };
+
+    let workspace = state
+        .workspace_mgr
+        .lock()
+        .await
+        .get_workspace(chat_id)
+        .await;
+
+    let filename = document
+        .file_name
+        .clone()
+        .unwrap_or_else(|| format!("upload-{}", chrono_timestamp()));
+
+    // Download straight into the workspace (not a temp dir) so the agent
+    // can read and modify the file like any other workspace file.
+    let file = bot.get_file(document.file.id.clone()).await?;
+    let file_url = format!(
+        "https://api.telegram.org/file/bot{}/{}",
+        state.config.telegram_token, file.path
+    );
+    let response = reqwest::get(&file_url).await?;
+    let file_bytes = response.bytes().await?;
+    let dest_path = workspace.join(&filename);
+    tokio::fs::write(&dest_path, &file_bytes).await?;
+
+    let caption = msg.caption().map(|s| s.to_string());
+    let group_file = GroupedFile {
+        path: dest_path,
+        filename,
+        is_image: false,
+    };
+
+    if let Some(media_group_id) = msg.media_group_id().map(|s| s.to_string()) {
+        let bot_ready = bot.clone();
+        let chat = msg.chat.id;
+        let state_ready = state.clone();
+        state
+            .media_groups
+            .add(
+                media_group_id,
+                group_file,
+                caption,
+                move |files, caption| {
+                    tokio::spawn(async move {
+                        let _ = run_grouped_files(bot_ready, chat, state_ready, files, caption).await;
+                    });
+                },
+            )
+            .await;
+        return Ok(());
+    }
+
+    run_grouped_files(bot, msg.chat.id, state, vec![group_file], caption).await
+}
+
+/// Runs a single Pi turn over a batch of files already written into the
+/// chat's workspace — either one document/photo sent on its own, or an
+/// entire Telegram album collected by `MediaGroupBuffer`. Mirrors
+/// `handle_text_oneshot`'s status/queue/output handling so multi-file
+/// uploads get the same experience as a one-shot text prompt.
+async fn run_grouped_files(
+    bot: Bot,
+    chat: ChatId,
+    state: AppState,
+    files: Vec<GroupedFile>,
+    caption: Option<String>,
+) -> anyhow::Result<()> {
+    let chat_id = chat.0;
+    let settings = state.settings_mgr.lock().await.get(chat_id).await;
+
+    let prompt = caption.unwrap_or_else(|| {
+        let names = files
+            .iter()
+            .map(|f| f.filename.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("I've uploaded the following file(s): {names}. Please take a look.")
+    });
+
+    let workspace = state
+        .workspace_mgr
+        .lock()
+        .await
+        .get_workspace(chat_id)
+        .await;
+    let workspace_str = workspace.to_string_lossy().to_string();
+
+    // Resolve the chat's active `/host` target so Pi runs wherever `/shell`
+    // already does, not always on this machine.
+    let active = state.host_mgr.lock().await.active_target(chat_id).await;
+    let exec_cwd = match &active {
+        ActiveTarget::Local => workspace_str.clone(),
+        ActiveTarget::Remote(_) => state.host_mgr.lock().await.remote_cwd(chat_id).await,
+    };
+    let backend = state.exec_session(chat_id).await;
+
+    let session_lines_before =
+        get_session_line_count_via(backend.as_ref(), &state.config, chat_id).await;
+    let before_snapshot = snapshot_workspace(&workspace).await;
+
+    let working_text = state.t(chat_id, "working", None).await;
+    let status_msg = bot.send_message(chat, working_text).await?;
+
+    let bot_cb = bot.clone();
+    let status_msg_id = status_msg.id;
+    let last_update = Arc::new(std::sync::Mutex::new(Instant::now()));
+    let status_throttle_ms = settings.status_throttle_ms;
+
+    let on_activity = move |activity: ActivityUpdate| {
+        let mut last = last_update.lock().unwrap();
+        if last.elapsed() < Duration::from_millis(status_throttle_ms) {
+            return;
+        }
+        *last = Instant::now();
+
+        let emoji = match activity.activity_type {
+            ActivityType::Thinking => "\u{1f9e0}",
+            ActivityType::Reading => "\u{1f4d6}",
+            ActivityType::Writing => "\u{270d}\u{fe0f}",
+            ActivityType::Running => "\u{26a1}",
+            ActivityType::Searching => "\u{1f50d}",
+            ActivityType::Working => "\u{1f504}",
+        };
+        let text = format!("{emoji} Working... ({}s)", activity.elapsed);
+
+        let bot_inner = bot_cb.clone();
+        tokio::spawn(async move {
+            let _ = bot_inner.edit_message_text(chat, status_msg_id, text).await;
+        });
+    };
+
+    // Acquire lock, then a global run permit, exactly like `handle_text_oneshot`.
+    let _guard = state.chat_locks.acquire(chat_id).await;
+    let bot_queue = bot.clone();
+    let status_msg_id_queue = status_msg.id;
+    let _permit = state
+        .run_queue
+        .acquire(move |position| {
+            let bot_inner = bot_queue.clone();
+            let text = if position == 0 {
+                "\u{23f3} Queued...".to_string()
+            } else {
+                format!("\u{23f3} Queued ({position} ahead)...")
+            };
+            tokio::spawn(async move {
+                let _ = bot_inner
+                    .edit_message_text(chat, status_msg_id_queue, text)
                     .await;
+            });
+        })
+        .await;
+
+    let image_paths = files
+        .iter()
+        .filter(|f| f.is_image)
+        .map(|f| f.path.clone())
+        .collect();
+    let options = RunPiOptions {
+        image_paths,
+        thinking_level: Some(state.thinking_level(chat_id).await),
+    };
+
+    let result = run_pi_with_streaming(
+        backend.as_ref(),
+        &state.config,
+        chat_id,
+        &prompt,
+        &exec_cwd,
+        on_activity,
+        Some(options),
+    )
+    .await;
+
+    let prior_chat_state = state.storage.get(chat_id).await.unwrap_or_default();
+    let _ = state
+        .storage
+        .set(
+            chat_id,
+            ChatState {
+                workspace: Some(workspace.clone()),
+                last_session_line_count: get_session_line_count_via(
+                    backend.as_ref(),
+                    &state.config,
+                    chat_id,
+                )
+                .await,
+                live_mode: false,
+                ..prior_chat_state
+            },
+        )
+        .await;
+
+    let _ = bot.delete_message(chat, status_msg.id).await;
+
+    if let Some(ref error) = result.error {
+        bot.send_message(chat, format!("Error: {error}")).await?;
+    }
+
+    if !result.output.is_empty() {
+        let chunks = split_message(result.output.trim());
+        for chunk in chunks {
+            match bot
+                .send_message(chat, markdown_to_html(&chunk))
+                .parse_mode(teloxide::types::ParseMode::Html)
+                .await
+            {
+                Ok(_) => {}
+                Err(_) => {
+                    bot.send_message(chat, strip_markdown(&chunk)).await?;
+                }
             }
         }
     }
 
-    // Clean up temp file
-    let _ = tokio::fs::remove_file(&temp_path).await;
+    if settings.send_tool_images {
+        let tool_images = extract_images_from_session_via(
+            backend.as_ref(),
+            &state.config,
+            chat_id,
+            session_lines_before,
+        )
+        .await;
+        let detected_files = detect_files(&result.output, &workspace, &before_snapshot).await;
+        let outputs = build_output_items(tool_images, detected_files);
+
+        let failed = send_output_batch(&bot, chat, outputs).await;
+        for filename in failed {
+            let mut args = fluent::FluentArgs::new();
+            args.set("filename", filename);
+            let text = state.t(chat_id, "could-not-send-file", Some(&args)).await;
+            bot.send_message(chat, text).await?;
+        }
+    }
 
     Ok(())
 }
 
+/// Converts a run's raw outputs (tool images pulled from the session log,
+/// files detected as new/changed in the workspace) into the ordered
+/// `OutputItem` batch `send_output_batch` expects.
+fn build_output_items(
+    tool_images: Vec<crate::pi_runner::ExtractedImage>,
+    detected_files: Vec<crate::file_detector::DetectedFile>,
+) -> Vec<OutputItem> {
+    let mut items = Vec::with_capacity(tool_images.len() + detected_files.len());
+
+    for (i, img) in tool_images.into_iter().enumerate() {
+        let ext = img.mime_type.split('/').nth(1).unwrap_or("png");
+        let filename = format!("image_{}.{ext}", i + 1);
+        let input = InputFile::memory(img.data).file_name(filename.clone());
+        items.push(OutputItem::Photo {
+            input,
+            caption: None,
+            name: filename,
+        });
+    }
+
+    for file in detected_files {
+        let path_str = file.path.to_string_lossy().to_string();
+        let input = InputFile::file(&path_str);
+        items.push(match file.file_type {
+            crate::file_detector::DetectedFileType::Photo => OutputItem::Photo {
+                input,
+                caption: Some(file.filename.clone()),
+                name: file.filename,
+            },
+            crate::file_detector::DetectedFileType::Document => OutputItem::Document {
+                input,
+                caption: Some(file.filename.clone()),
+                name: file.filename,
+            },
+        });
+    }
+
+    items
+}
+
 fn chrono_timestamp() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::SystemTime::UNIX_EPOCH)
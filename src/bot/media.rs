@@ -0,0 +1,135 @@
+use teloxide::prelude::*;
+use teloxide::types::{InputFile, InputMedia, InputMediaDocument, InputMediaPhoto};
+
+/// Telegram caps a single `sendMediaGroup` call at 10 items.
+const MAX_ALBUM_SIZE: usize = 10;
+
+/// One generated output file waiting to be delivered, already loaded as an
+/// `InputFile` (in-memory bytes for tool images, a path for detected
+/// workspace files). `name` is only used to report a failed send back to
+/// the caller — it isn't sent to Telegram unless it's also the caption.
+pub enum OutputItem {
+    Photo {
+        input: InputFile,
+        caption: Option<String>,
+        name: String,
+    },
+    Document {
+        input: InputFile,
+        caption: Option<String>,
+        name: String,
+    },
+}
+
+impl OutputItem {
+    fn name(&self) -> &str {
+        match self {
+            OutputItem::Photo { name, .. } | OutputItem::Document { name, .. } => name,
+        }
+    }
+
+    fn into_media(self) -> InputMedia {
+        match self {
+            OutputItem::Photo { input, caption, .. } => {
+                let mut media = InputMediaPhoto::new(input);
+                if let Some(caption) = caption {
+                    media = media.caption(caption);
+                }
+                InputMedia::Photo(media)
+            }
+            OutputItem::Document { input, caption, .. } => {
+                let mut media = InputMediaDocument::new(input);
+                if let Some(caption) = caption {
+                    media = media.caption(caption);
+                }
+                InputMedia::Document(media)
+            }
+        }
+    }
+
+    fn to_media(&self) -> InputMedia {
+        match self {
+            OutputItem::Photo { input, caption, .. } => OutputItem::Photo {
+                input: input.clone(),
+                caption: caption.clone(),
+                name: String::new(),
+            }
+            .into_media(),
+            OutputItem::Document { input, caption, .. } => OutputItem::Document {
+                input: input.clone(),
+                caption: caption.clone(),
+                name: String::new(),
+            }
+            .into_media(),
+        }
+    }
+}
+
+/// Sends generated outputs (tool images, detected workspace files) as
+/// ordered albums instead of one `send_photo`/`send_document` call per
+/// item, so multiple outputs from a single run arrive together rather than
+/// flooding the chat. Splits into multiple `sendMediaGroup` calls beyond
+/// Telegram's 10-item album limit, and falls back to sending each item
+/// individually if an album call fails (e.g. a single bad file shouldn't
+/// sink the rest of the batch). Returns the names of any items that still
+/// failed after the individual-send fallback.
+pub async fn send_output_batch(bot: &Bot, chat: ChatId, items: Vec<OutputItem>) -> Vec<String> {
+    let mut failed = Vec::new();
+
+    for chunk in to_chunks(items, MAX_ALBUM_SIZE) {
+        if chunk.len() == 1 {
+            failed.extend(send_individually(bot, chat, chunk).await);
+            continue;
+        }
+
+        let media: Vec<InputMedia> = chunk.iter().map(OutputItem::to_media).collect();
+
+        if bot.send_media_group(chat, media).await.is_err() {
+            failed.extend(send_individually(bot, chat, chunk).await);
+        }
+    }
+
+    failed
+}
+
+async fn send_individually(bot: &Bot, chat: ChatId, items: Vec<OutputItem>) -> Vec<String> {
+    let mut failed = Vec::new();
+    for item in items {
+        let name = item.name().to_string();
+        let sent = match item {
+            OutputItem::Photo { input, caption, .. } => {
+                let mut req = bot.send_photo(chat, input);
+                if let Some(caption) = caption {
+                    req = req.caption(caption);
+                }
+                req.await.is_ok()
+            }
+            OutputItem::Document { input, caption, .. } => {
+                let mut req = bot.send_document(chat, input);
+                if let Some(caption) = caption {
+                    req = req.caption(caption);
+                }
+                req.await.is_ok()
+            }
+        };
+        if !sent {
+            failed.push(name);
+        }
+    }
+    failed
+}
+
+fn to_chunks(items: Vec<OutputItem>, size: usize) -> Vec<Vec<OutputItem>> {
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    for item in items {
+        current.push(item);
+        if current.len() == size {
+            chunks.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
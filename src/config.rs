@@ -1,5 +1,6 @@
 use std::fmt;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::error::MiniClawError;
 
@@ -10,10 +11,106 @@ pub struct Config {
     pub session_dir: PathBuf,
     pub thinking_level: ThinkingLevel,
     pub allowed_users: Vec<i64>,
+    /// Telegram user IDs allowed to run `AdminBotCommand`s (`/adduser`,
+    /// `/broadcast`, etc). Unlike `allowed_users`, this list is not runtime
+    /// mutable — promoting an admin still requires editing `.env`.
+    pub admins: Vec<i64>,
     pub rate_limit_cooldown_ms: u64,
     pub pi_timeout_ms: u64,
     pub shell_timeout_ms: u64,
-    pub session_title_timeout_ms: u64,
+    /// How long `generate_session_title` waits for `pi --print` before
+    /// falling back to the first few words of the user's message. Parsed
+    /// from a human-friendly string (e.g. `"10s"`) via `parse_duration`.
+    pub session_title_timeout: Duration,
+    pub exec_target: ExecTarget,
+    pub storage_backend: StorageBackend,
+    pub max_concurrent_runs: usize,
+    pub locales_dir: PathBuf,
+    pub default_lang: String,
+    /// How long a chat's browser session (see `crate::browser_pool`) can sit
+    /// idle before it's reaped.
+    pub browser_idle_ms: u64,
+    /// How many of the most recent hourly/daily/weekly/monthly buckets
+    /// `cleanup_old_sessions` keeps one archived session in, per chat. See
+    /// `crate::sessions` for the slotted retention scheme this drives.
+    pub retention_hourly_slots: usize,
+    pub retention_daily_slots: usize,
+    pub retention_weekly_slots: usize,
+    pub retention_monthly_slots: usize,
+    /// How often `spawn_session_cleanup` runs `cleanup_old_sessions` in the
+    /// background. Parsed from a human-friendly string (e.g. `"daily"`) via
+    /// `parse_duration`.
+    pub cleanup_interval: Duration,
+    /// When set, `PiRpcProcess::spawn` attaches `pi` to a pseudo-terminal
+    /// (see `crate::pty_shell`) instead of plain pipes, so tools that check
+    /// `isatty()` behave the same way they would in a real terminal.
+    pub pi_rpc_pty: bool,
+}
+
+/// Parses the human-friendly duration strings `.env` uses instead of magic
+/// millisecond integers: suffixed values like `"800ms"`, `"30s"`, `"2h"`,
+/// `"7d"`, or one of the named presets `"hourly"`, `"daily"`,
+/// `"twice-daily"`, `"weekly"`.
+pub fn parse_duration(s: &str) -> Result<Duration, MiniClawError> {
+    let s = s.trim();
+
+    match s.to_lowercase().as_str() {
+        "hourly" => return Ok(Duration::from_secs(3600)),
+        "daily" => return Ok(Duration::from_secs(24 * 3600)),
+        "twice-daily" => return Ok(Duration::from_secs(12 * 3600)),
+        "weekly" => return Ok(Duration::from_secs(7 * 24 * 3600)),
+        _ => {}
+    }
+
+    let split_at = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(|| {
+        MiniClawError::Config(format!("duration '{s}' is missing a unit (ms/s/m/h/d)"))
+    })?;
+    let (digits, unit) = s.split_at(split_at);
+
+    let value: u64 = digits.parse().map_err(|_| {
+        MiniClawError::Config(format!("duration '{s}' has no numeric value before the unit"))
+    })?;
+
+    let unit_ms = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60 * 1_000,
+        "h" => 3600 * 1_000,
+        "d" => 24 * 3600 * 1_000,
+        other => {
+            return Err(MiniClawError::Config(format!(
+                "duration '{s}' has unrecognized unit '{other}' (expected ms/s/m/h/d, or a preset like 'daily'/'weekly')"
+            )))
+        }
+    };
+
+    Ok(Duration::from_millis(value * unit_ms))
+}
+
+/// Which `Storage` backend (see `crate::storage`) persists per-chat state
+/// across restarts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageBackend {
+    Memory,
+    Sqlite { path: PathBuf },
+}
+
+/// Where `run_pi_with_streaming` and `run_shell` actually execute: the local
+/// machine, or a remote host reached over SSH.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecTarget {
+    Local,
+    Ssh {
+        host: String,
+        port: u16,
+        user: String,
+        /// Blind-accepts any host key instead of verifying against
+        /// `~/.ssh/known_hosts`. Off by default — opt in only for hosts
+        /// where host-key pinning genuinely isn't possible (e.g. a
+        /// throwaway container), since this makes the connection
+        /// MITM-able.
+        insecure_accept_any_host_key: bool,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -34,7 +131,7 @@ impl fmt::Display for ThinkingLevel {
 }
 
 impl ThinkingLevel {
-    fn from_str(s: &str) -> Self {
+    pub(crate) fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "medium" => ThinkingLevel::Medium,
             "high" => ThinkingLevel::High,
@@ -100,6 +197,17 @@ pub fn load_config() -> Result<Config, MiniClawError> {
         })
         .unwrap_or_default();
 
+    let admins = std::env::var("ADMINS")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.split(',')
+                .filter_map(|id| id.trim().parse::<i64>().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
     let rate_limit_cooldown_ms = std::env::var("RATE_LIMIT_COOLDOWN_MS")
         .ok()
         .and_then(|s| s.trim().parse().ok())
@@ -115,10 +223,112 @@ pub fn load_config() -> Result<Config, MiniClawError> {
         .and_then(|s| s.trim().parse().ok())
         .unwrap_or(60_000);
 
-    let session_title_timeout_ms = std::env::var("SESSION_TITLE_TIMEOUT_MS")
+    let session_title_timeout = std::env::var("SESSION_TITLE_TIMEOUT")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|s| parse_duration(&s))
+        .transpose()?
+        .unwrap_or(Duration::from_secs(10));
+
+    let exec_target = std::env::var("SSH_HOST")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|host| {
+            let port = std::env::var("SSH_PORT")
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+                .unwrap_or(22);
+            let user = std::env::var("SSH_USER")
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "root".to_string());
+            let insecure_accept_any_host_key = std::env::var("SSH_INSECURE_ACCEPT_ANY_HOST_KEY")
+                .ok()
+                .map(|s| s.trim().eq_ignore_ascii_case("true"))
+                .unwrap_or(false);
+            ExecTarget::Ssh {
+                host,
+                port,
+                user,
+                insecure_accept_any_host_key,
+            }
+        })
+        .unwrap_or(ExecTarget::Local);
+
+    let storage_backend = std::env::var("STORAGE_BACKEND")
+        .ok()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| s == "sqlite")
+        .map(|_| {
+            let path = std::env::var("STORAGE_PATH")
+                .ok()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".mini-claw").join("storage.db"));
+            StorageBackend::Sqlite { path }
+        })
+        .unwrap_or(StorageBackend::Memory);
+
+    let max_concurrent_runs = std::env::var("MAX_CONCURRENT_RUNS")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(3);
+
+    let locales_dir = std::env::var("LOCALES_DIR")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("locales"));
+
+    let default_lang = std::env::var("DEFAULT_LANG")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "en".to_string());
+
+    let browser_idle_ms = std::env::var("BROWSER_IDLE_MS")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(5 * 60 * 1000);
+
+    let retention_hourly_slots = std::env::var("RETENTION_HOURLY_SLOTS")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(24);
+
+    let retention_daily_slots = std::env::var("RETENTION_DAILY_SLOTS")
         .ok()
         .and_then(|s| s.trim().parse().ok())
-        .unwrap_or(10_000);
+        .unwrap_or(7);
+
+    let retention_weekly_slots = std::env::var("RETENTION_WEEKLY_SLOTS")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(4);
+
+    let retention_monthly_slots = std::env::var("RETENTION_MONTHLY_SLOTS")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(12);
+
+    let cleanup_interval = std::env::var("CLEANUP_INTERVAL")
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .map(|s| parse_duration(&s))
+        .transpose()?
+        .unwrap_or_else(|| parse_duration("daily").expect("preset \"daily\" always parses"));
+
+    let pi_rpc_pty = std::env::var("PI_RPC_PTY")
+        .ok()
+        .map(|s| s.trim().eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
 
     Ok(Config {
         telegram_token,
@@ -126,10 +336,23 @@ pub fn load_config() -> Result<Config, MiniClawError> {
         session_dir,
         thinking_level,
         allowed_users,
+        admins,
         rate_limit_cooldown_ms,
         pi_timeout_ms,
         shell_timeout_ms,
-        session_title_timeout_ms,
+        session_title_timeout,
+        exec_target,
+        storage_backend,
+        max_concurrent_runs,
+        locales_dir,
+        default_lang,
+        browser_idle_ms,
+        retention_hourly_slots,
+        retention_daily_slots,
+        retention_weekly_slots,
+        retention_monthly_slots,
+        cleanup_interval,
+        pi_rpc_pty,
     })
 }
 
@@ -137,6 +360,33 @@ pub fn load_config() -> Result<Config, MiniClawError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_duration_suffixed_values() {
+        assert_eq!(parse_duration("800ms").unwrap(), Duration::from_millis(800));
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(2 * 3600));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::from_secs(7 * 24 * 3600));
+    }
+
+    #[test]
+    fn test_parse_duration_presets() {
+        assert_eq!(parse_duration("hourly").unwrap(), Duration::from_secs(3600));
+        assert_eq!(parse_duration("daily").unwrap(), Duration::from_secs(24 * 3600));
+        assert_eq!(parse_duration("twice-daily").unwrap(), Duration::from_secs(12 * 3600));
+        assert_eq!(parse_duration("weekly").unwrap(), Duration::from_secs(7 * 24 * 3600));
+        assert_eq!(parse_duration("DAILY").unwrap(), Duration::from_secs(24 * 3600));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_missing_unit() {
+        assert!(parse_duration("30").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_unknown_unit() {
+        assert!(parse_duration("30x").is_err());
+    }
+
     #[test]
     fn test_thinking_level_display() {
         assert_eq!(ThinkingLevel::Low.to_string(), "low");
@@ -0,0 +1,455 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::io::AsyncBufReadExt;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::config::{Config, ExecTarget};
+
+/// Output of a spawned command, reduced to the line-oriented surface that
+/// `run_pi_with_streaming` and `run_shell` actually consume, regardless of
+/// whether the process is local or running on a remote host over SSH.
+pub struct RemoteChild {
+    pub stdout: mpsc::UnboundedReceiver<String>,
+    pub stderr: mpsc::UnboundedReceiver<String>,
+    kill_tx: Option<oneshot::Sender<()>>,
+    wait_rx: oneshot::Receiver<Option<i32>>,
+}
+
+impl RemoteChild {
+    /// Kills the process on the host it is running on: `Child::kill` on the
+    /// local `bash`, or a KILL signal over the SSH channel. Only that process
+    /// is signalled, not its process group. Dropping a `RemoteChild` has the
+    /// same effect, which is how a timed-out `run_pi_with_streaming` stops
+    /// its child.
+    pub fn kill(&mut self) {
+        if let Some(tx) = self.kill_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    pub async fn wait(self) -> Option<i32> {
+        self.wait_rx.await.ok().flatten()
+    }
+}
+
+/// A place commands and file reads/writes can execute: the local machine or
+/// a remote host reached over SSH. `run_pi_with_streaming` reads
+/// `telegram-{chat_id}.jsonl` through this trait so the session file can live
+/// on whichever host actually ran Pi.
+#[async_trait]
+pub trait ExecSession: Send + Sync {
+    async fn spawn_command(
+        &self,
+        cmd: &str,
+        cwd: &str,
+        env: &[(String, String)],
+    ) -> anyhow::Result<RemoteChild>;
+
+    async fn read_file(&self, path: &Path) -> anyhow::Result<Vec<u8>>;
+
+    async fn write_file(&self, path: &Path, data: &[u8]) -> anyhow::Result<()>;
+}
+
+/// Runs commands with `tokio::process::Command` and reads/writes files with
+/// `tokio::fs`, exactly like the pre-existing local-only code paths.
+pub struct LocalExecSession;
+
+#[async_trait]
+impl ExecSession for LocalExecSession {
+    async fn spawn_command(
+        &self,
+        cmd: &str,
+        cwd: &str,
+        env: &[(String, String)],
+    ) -> anyhow::Result<RemoteChild> {
+        let mut command = tokio::process::Command::new("bash");
+        command
+            .arg("-c")
+            .arg(cmd)
+            .current_dir(cwd)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+        for (key, value) in env {
+            command.env(key, value);
+        }
+
+        let mut child = command.spawn()?;
+        let stdout = child.stdout.take().expect("stdout piped");
+        let stderr = child.stderr.take().expect("stderr piped");
+
+        let (stdout_tx, stdout_rx) = mpsc::unbounded_channel();
+        let (stderr_tx, stderr_rx) = mpsc::unbounded_channel();
+        let (kill_tx, mut kill_rx) = oneshot::channel();
+        let (wait_tx, wait_rx) = oneshot::channel();
+
+        spawn_line_pump(stdout, stdout_tx);
+        spawn_line_pump(stderr, stderr_tx);
+
+        tokio::spawn(async move {
+            let status = tokio::select! {
+                status = child.wait() => status.ok().and_then(|s| s.code()),
+                _ = &mut kill_rx => {
+                    let _ = child.kill().await;
+                    None
+                }
+            };
+            let _ = wait_tx.send(status);
+        });
+
+        Ok(RemoteChild {
+            stdout: stdout_rx,
+            stderr: stderr_rx,
+            kill_tx: Some(kill_tx),
+            wait_rx,
+        })
+    }
+
+    async fn read_file(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        Ok(tokio::fs::read(path).await?)
+    }
+
+    async fn write_file(&self, path: &Path, data: &[u8]) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, data).await?;
+        Ok(())
+    }
+}
+
+fn spawn_line_pump<R>(reader: R, tx: mpsc::UnboundedSender<String>)
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = tokio::io::BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Runs commands on a remote host over an SSH channel per invocation, and
+/// reads/writes files by shelling out to `base64` over the same channel
+/// mechanism (no separate SFTP subsystem needed).
+pub struct SshExecSession {
+    host: String,
+    port: u16,
+    user: String,
+    insecure_accept_any_host_key: bool,
+}
+
+impl SshExecSession {
+    pub fn new(host: String, port: u16, user: String, insecure_accept_any_host_key: bool) -> Self {
+        Self {
+            host,
+            port,
+            user,
+            insecure_accept_any_host_key,
+        }
+    }
+
+    fn destination(&self) -> String {
+        format!("{}@{}", self.user, self.host)
+    }
+}
+
+/// Verifies the remote host key against `~/.ssh/known_hosts` by default.
+/// `AcceptAll` blind-accepts any key instead, and only exists because a
+/// caller explicitly opted in via `insecure_accept_any_host_key` in config —
+/// never the default, since it makes the connection MITM-able.
+enum HostKeyPolicy {
+    Verify { host: String, port: u16 },
+    AcceptAll,
+}
+
+#[async_trait]
+impl russh::client::Handler for HostKeyPolicy {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &russh_keys::key::PublicKey,
+    ) -> Result<bool, Self::Error> {
+        match self {
+            HostKeyPolicy::AcceptAll => {
+                eprintln!(
+                    "WARNING: insecure_accept_any_host_key is set — accepting SSH host key \
+                     without verification"
+                );
+                Ok(true)
+            }
+            HostKeyPolicy::Verify { host, port } => {
+                let known_hosts = dirs::home_dir()
+                    .unwrap_or_default()
+                    .join(".ssh")
+                    .join("known_hosts");
+                Ok(
+                    russh_keys::check_known_hosts_path(host, *port, server_public_key, &known_hosts)
+                        .unwrap_or(false),
+                )
+            }
+        }
+    }
+}
+
+impl SshExecSession {
+    async fn connect(&self) -> anyhow::Result<russh::client::Handle<HostKeyPolicy>> {
+        let config = Arc::new(russh::client::Config::default());
+        let policy = if self.insecure_accept_any_host_key {
+            HostKeyPolicy::AcceptAll
+        } else {
+            HostKeyPolicy::Verify {
+                host: self.host.clone(),
+                port: self.port,
+            }
+        };
+        let mut session =
+            russh::client::connect(config, (self.host.as_str(), self.port), policy).await?;
+
+        let key_path = dirs::home_dir()
+            .unwrap_or_default()
+            .join(".ssh")
+            .join("id_ed25519");
+        let key_pair = russh_keys::load_secret_key(&key_path, None)
+            .map_err(|e| anyhow::anyhow!("failed to load SSH key {}: {e}", key_path.display()))?;
+
+        let authenticated = session
+            .authenticate_publickey(&self.user, Arc::new(key_pair))
+            .await?;
+        if !authenticated {
+            anyhow::bail!("SSH authentication failed for {}@{}", self.user, self.host);
+        }
+
+        Ok(session)
+    }
+
+    /// Opens one exec channel for `remote_cmd` and pumps its stdout/stderr
+    /// (CDP-free here — russh tags each data frame by "extended data code",
+    /// where code 1 is stderr) into the same line channels every backend uses.
+    async fn exec(&self, remote_cmd: String) -> anyhow::Result<RemoteChild> {
+        let session = self.connect().await?;
+        let mut channel = session.channel_open_session().await?;
+        channel.exec(true, remote_cmd).await?;
+
+        let (stdout_tx, stdout_rx) = mpsc::unbounded_channel();
+        let (stderr_tx, stderr_rx) = mpsc::unbounded_channel();
+        let (kill_tx, mut kill_rx) = oneshot::channel();
+        let (wait_tx, wait_rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let mut exit_code = None;
+            loop {
+                tokio::select! {
+                    msg = channel.wait() => {
+                        match msg {
+                            Some(russh::ChannelMsg::Data { data }) => {
+                                let text = String::from_utf8_lossy(&data).into_owned();
+                                for line in text.lines() {
+                                    let _ = stdout_tx.send(line.to_string());
+                                }
+                            }
+                            Some(russh::ChannelMsg::ExtendedData { data, ext: 1 }) => {
+                                let text = String::from_utf8_lossy(&data).into_owned();
+                                for line in text.lines() {
+                                    let _ = stderr_tx.send(line.to_string());
+                                }
+                            }
+                            Some(russh::ChannelMsg::ExitStatus { exit_status }) => {
+                                exit_code = Some(exit_status as i32);
+                            }
+                            Some(russh::ChannelMsg::Eof) | Some(russh::ChannelMsg::Close) | None => break,
+                            Some(_) => {}
+                        }
+                    }
+                    _ = &mut kill_rx => {
+                        let _ = channel.signal(russh::Sig::KILL).await;
+                        break;
+                    }
+                }
+            }
+            let _ = wait_tx.send(exit_code);
+        });
+
+        Ok(RemoteChild {
+            stdout: stdout_rx,
+            stderr: stderr_rx,
+            kill_tx: Some(kill_tx),
+            wait_rx,
+        })
+    }
+}
+
+/// Single-quotes `s` for safe interpolation into a remote shell command
+/// line, escaping embedded single quotes with the standard `'\''` trick —
+/// so a cwd/path containing a space or shell metacharacter can't break the
+/// command or be used for injection.
+pub(crate) fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Builds the `cd` target for `spawn_command`. A bare `~` (the default
+/// `remote_cwd`, see `hosts.rs`) or `~/rest` needs tilde expansion, which
+/// only happens for an *unquoted* leading `~` — `shell_quote`-ing it whole
+/// would emit `cd '~'`, a literal nonexistent directory instead of the
+/// login directory. So expand to `$HOME` (itself left unquoted, same as
+/// `handle_cd`'s existing `cd {current}`) and only quote the remainder.
+fn shell_cd_target(cwd: &str) -> String {
+    match cwd.strip_prefix('~') {
+        Some("") => "$HOME".to_string(),
+        Some(rest) if rest.starts_with('/') => format!("$HOME{}", shell_quote(rest)),
+        _ => shell_quote(cwd),
+    }
+}
+
+#[async_trait]
+impl ExecSession for SshExecSession {
+    async fn spawn_command(
+        &self,
+        cmd: &str,
+        cwd: &str,
+        env: &[(String, String)],
+    ) -> anyhow::Result<RemoteChild> {
+        // Fold cwd/env into the remote command line; russh opens one
+        // exec channel per command rather than a persistent shell.
+        let mut remote_cmd = format!("cd {} && ", shell_cd_target(cwd));
+        for (key, value) in env {
+            remote_cmd.push_str(&format!("export {key}={value:?} && "));
+        }
+        remote_cmd.push_str(cmd);
+
+        self.exec(remote_cmd).await
+    }
+
+    async fn read_file(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        // base64 round-trip keeps binary-safe data intact over the
+        // line-oriented exec channel (symmetric with `write_file` below) —
+        // `cat`-ing raw bytes through the lossy-UTF-8, line-split stdout
+        // pump would corrupt non-UTF-8 data and normalize line endings.
+        let quoted_path = shell_quote(&path.display().to_string());
+        let mut child = self.exec(format!("base64 {quoted_path}")).await?;
+        let mut encoded = String::new();
+        while let Some(line) = child.stdout.recv().await {
+            encoded.push_str(&line);
+        }
+        let encoded: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+        base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &encoded)
+            .map_err(|e| anyhow::anyhow!("failed to decode remote file {}: {e}", path.display()))
+    }
+
+    async fn write_file(&self, path: &Path, data: &[u8]) -> anyhow::Result<()> {
+        // base64 round-trip keeps binary-safe data intact over the
+        // line-oriented exec channel used for everything else.
+        let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data);
+        let quoted_path = shell_quote(&path.display().to_string());
+        let remote_cmd = format!(
+            "mkdir -p $(dirname {quoted_path}) && echo {encoded} | base64 -d > {quoted_path}"
+        );
+        let child = self.exec(remote_cmd).await?;
+        child.wait().await;
+        Ok(())
+    }
+}
+
+/// Builds the exec session for the configured backend.
+pub fn build_exec_session(config: &Config) -> Arc<dyn ExecSession> {
+    match &config.exec_target {
+        ExecTarget::Local => Arc::new(LocalExecSession),
+        ExecTarget::Ssh {
+            host,
+            port,
+            user,
+            insecure_accept_any_host_key,
+        } => Arc::new(SshExecSession::new(
+            host.clone(),
+            *port,
+            user.clone(),
+            *insecure_accept_any_host_key,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_spawn_command_echo() {
+        let session = LocalExecSession;
+        let dir = tempfile::tempdir().unwrap();
+        let mut child = session
+            .spawn_command("echo hello", dir.path().to_str().unwrap(), &[])
+            .await
+            .unwrap();
+
+        let mut output = String::new();
+        while let Some(line) = child.stdout.recv().await {
+            output.push_str(&line);
+        }
+        assert_eq!(output, "hello");
+    }
+
+    #[tokio::test]
+    async fn test_local_read_write_file() {
+        let session = LocalExecSession;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.txt");
+        session.write_file(&path, b"hello world").await.unwrap();
+        let data = session.read_file(&path).await.unwrap();
+        assert_eq!(data, b"hello world");
+    }
+
+    #[test]
+    fn test_shell_cd_target_bare_tilde_expands_home() {
+        assert_eq!(shell_cd_target("~"), "$HOME");
+    }
+
+    #[test]
+    fn test_shell_cd_target_tilde_subdir_expands_home() {
+        assert_eq!(shell_cd_target("~/project"), "$HOME'/project'");
+    }
+
+    #[test]
+    fn test_shell_cd_target_absolute_path_quoted() {
+        assert_eq!(shell_cd_target("/home/alice/my project"), "'/home/alice/my project'");
+    }
+
+    #[test]
+    fn test_build_exec_session_local() {
+        let mut config = test_config();
+        config.exec_target = ExecTarget::Local;
+        let _session = build_exec_session(&config);
+    }
+
+    fn test_config() -> Config {
+        Config {
+            telegram_token: "token".into(),
+            workspace: std::path::PathBuf::from("/tmp"),
+            session_dir: std::path::PathBuf::from("/tmp"),
+            thinking_level: crate::config::ThinkingLevel::Low,
+            allowed_users: vec![],
+            admins: vec![],
+            rate_limit_cooldown_ms: 5000,
+            pi_timeout_ms: 1000,
+            shell_timeout_ms: 1000,
+            session_title_timeout: std::time::Duration::from_millis(1000),
+            exec_target: ExecTarget::Local,
+            storage_backend: crate::config::StorageBackend::Memory,
+            max_concurrent_runs: 3,
+            locales_dir: std::path::PathBuf::from("locales"),
+            default_lang: "en".into(),
+            browser_idle_ms: 5 * 60 * 1000,
+            retention_hourly_slots: 24,
+            retention_daily_slots: 7,
+            retention_weekly_slots: 4,
+            retention_monthly_slots: 12,
+            cleanup_interval: std::time::Duration::from_secs(86400),
+            pi_rpc_pty: false,
+        }
+    }
+}
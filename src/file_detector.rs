@@ -3,6 +3,8 @@ use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::time::SystemTime;
 
+use ignore::WalkBuilder;
+
 static IMAGE_EXTENSIONS: LazyLock<HashSet<&str>> =
     LazyLock::new(|| [".png", ".jpg", ".jpeg", ".gif", ".webp"].into());
 
@@ -13,6 +15,17 @@ static DOCUMENT_EXTENSIONS: LazyLock<HashSet<&str>> = LazyLock::new(|| {
     .into()
 });
 
+/// Every extension `snapshot_workspace` bothers to stat, so the recursive
+/// walk doesn't waste time on source files and other noise that
+/// `categorize_files` would discard anyway.
+static TRACKED_EXTENSIONS: LazyLock<HashSet<&str>> = LazyLock::new(|| {
+    IMAGE_EXTENSIONS
+        .iter()
+        .chain(DOCUMENT_EXTENSIONS.iter())
+        .copied()
+        .collect()
+});
+
 #[derive(Debug, Clone)]
 pub struct DetectedFile {
     pub path: PathBuf,
@@ -57,19 +70,47 @@ pub fn parse_output_for_files(output: &str) -> Vec<PathBuf> {
     files.into_iter().collect()
 }
 
+/// Lower-cased, dot-prefixed extension of `path` (e.g. `.png`), the form
+/// `TRACKED_EXTENSIONS`/`IMAGE_EXTENSIONS`/`DOCUMENT_EXTENSIONS` are keyed by.
+fn dotted_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| format!(".{}", e.to_lowercase()))
+        .unwrap_or_default()
+}
+
+/// Recursively walks `workspace` with the `ignore` crate, which skips
+/// whatever the tree's own `.gitignore`/`.ignore` files and hidden
+/// directories (`.git` included) already exclude, so output written into
+/// `out/`, `build/`, or any other subdirectory is still seen. Only files
+/// whose extension is in `TRACKED_EXTENSIONS` are stat'd; `ext_cache`
+/// remembers each extension's tracked/untracked verdict for the rest of the
+/// walk so repeated extensions (there are usually far fewer distinct
+/// extensions than files) skip the lookup.
 pub async fn snapshot_workspace(workspace: &Path) -> HashMap<PathBuf, u128> {
-    let mut files = HashMap::new();
-    let Ok(mut entries) = tokio::fs::read_dir(workspace).await else {
-        return files;
-    };
+    let workspace = workspace.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut files = HashMap::new();
+        let mut ext_cache: HashMap<String, bool> = HashMap::new();
+
+        for entry in WalkBuilder::new(&workspace).require_git(false).build().flatten() {
+            let Some(file_type) = entry.file_type() else {
+                continue;
+            };
+            if !file_type.is_file() {
+                continue;
+            }
 
-    while let Ok(Some(entry)) = entries.next_entry().await {
-        let Ok(ft) = entry.file_type().await else {
-            continue;
-        };
-        if ft.is_file() {
-            let path = entry.path();
-            if let Ok(meta) = tokio::fs::metadata(&path).await {
+            let path = entry.into_path();
+            let ext = dotted_extension(&path);
+            let tracked = *ext_cache
+                .entry(ext.clone())
+                .or_insert_with(|| TRACKED_EXTENSIONS.contains(ext.as_str()));
+            if !tracked {
+                continue;
+            }
+
+            if let Ok(meta) = std::fs::metadata(&path) {
                 if let Ok(mtime) = meta.modified() {
                     let ms = mtime
                         .duration_since(SystemTime::UNIX_EPOCH)
@@ -79,9 +120,11 @@ pub async fn snapshot_workspace(workspace: &Path) -> HashMap<PathBuf, u128> {
                 }
             }
         }
-    }
 
-    files
+        files
+    })
+    .await
+    .unwrap_or_default()
 }
 
 pub async fn detect_new_files(
@@ -106,11 +149,7 @@ pub fn categorize_files(file_paths: &[PathBuf]) -> Vec<DetectedFile> {
     let mut result = Vec::new();
 
     for path in file_paths {
-        let ext = path
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| format!(".{}", e.to_lowercase()))
-            .unwrap_or_default();
+        let ext = dotted_extension(path);
 
         let filename = path
             .file_name()
@@ -152,7 +191,106 @@ pub async fn detect_files(
         all_files.insert(f);
     }
 
-    categorize_files(&all_files.into_iter().collect::<Vec<_>>())
+    let categorized = categorize_files(&all_files.into_iter().collect::<Vec<_>>());
+    validate_detected_files(categorized).await
+}
+
+/// A `DetectedFile`'s size/mtime at one instant, used to confirm a writer
+/// has stopped touching it before `validate_detected_files` trusts the
+/// file enough to decode.
+struct FileEntry {
+    #[allow(dead_code)]
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+async fn stat_entry(path: &Path) -> Option<FileEntry> {
+    let meta = tokio::fs::metadata(path).await.ok()?;
+    Some(FileEntry {
+        path: path.to_path_buf(),
+        size: meta.len(),
+        modified: meta.modified().ok()?,
+    })
+}
+
+/// How long to wait between the two re-stats `is_stable` compares, chosen
+/// to be comfortably longer than a single `write()` syscall but short
+/// enough not to noticeably delay delivery.
+const STABILITY_CHECK_DELAY_MS: u64 = 150;
+
+/// Re-stats `path` after a short quiet window to confirm a writer isn't
+/// still flushing it — a zero-byte or half-written file would otherwise
+/// slip past `categorize_files` and fail to decode on Telegram's end.
+async fn is_stable(path: &Path) -> bool {
+    let Some(before) = stat_entry(path).await else {
+        return false;
+    };
+    if before.size == 0 {
+        return false;
+    }
+
+    tokio::time::sleep(std::time::Duration::from_millis(STABILITY_CHECK_DELAY_MS)).await;
+
+    let Some(after) = stat_entry(path).await else {
+        return false;
+    };
+    before.size == after.size && before.modified == after.modified
+}
+
+fn decodes_as_image(path: &Path) -> bool {
+    image::ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map(|reader| reader.decode().is_ok())
+        .unwrap_or(false)
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.eq_ignore_ascii_case("pdf"))
+        .unwrap_or(false)
+}
+
+fn opens_as_pdf(path: &Path) -> bool {
+    lopdf::Document::load(path).is_ok()
+}
+
+/// Filters out detected files that are still being written or fail to
+/// parse, so Telegram never rejects an upload as corrupted: `Photo`s must
+/// decode with the `image` crate, PDF `Document`s must open with `lopdf`,
+/// and any file still changing size/mtime across a short re-stat window is
+/// dropped outright regardless of type.
+pub async fn validate_detected_files(files: Vec<DetectedFile>) -> Vec<DetectedFile> {
+    let mut valid = Vec::with_capacity(files.len());
+
+    for file in files {
+        if !is_stable(&file.path).await {
+            continue;
+        }
+
+        let ok = match file.file_type {
+            DetectedFileType::Photo => {
+                let path = file.path.clone();
+                tokio::task::spawn_blocking(move || decodes_as_image(&path))
+                    .await
+                    .unwrap_or(false)
+            }
+            DetectedFileType::Document if is_pdf(&file.path) => {
+                let path = file.path.clone();
+                tokio::task::spawn_blocking(move || opens_as_pdf(&path))
+                    .await
+                    .unwrap_or(false)
+            }
+            DetectedFileType::Document => true,
+        };
+
+        if ok {
+            valid.push(file);
+        }
+    }
+
+    valid
 }
 
 #[cfg(test)]
@@ -231,6 +369,41 @@ mod tests {
         assert!(new.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_snapshot_finds_nested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::create_dir_all(dir.path().join("out/nested")).await.unwrap();
+        tokio::fs::write(dir.path().join("out/nested/report.pdf"), "report")
+            .await
+            .unwrap();
+
+        let snap = snapshot_workspace(dir.path()).await;
+        assert_eq!(snap.len(), 1);
+        assert!(snap.contains_key(&dir.path().join("out/nested/report.pdf")));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_skips_untracked_extensions() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("notes.rs"), "fn main() {}").await.unwrap();
+
+        let snap = snapshot_workspace(dir.path()).await;
+        assert!(snap.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join(".gitignore"), "ignored/\n").await.unwrap();
+        tokio::fs::create_dir_all(dir.path().join("ignored")).await.unwrap();
+        tokio::fs::write(dir.path().join("ignored/hidden.png"), "x").await.unwrap();
+        tokio::fs::write(dir.path().join("visible.png"), "x").await.unwrap();
+
+        let snap = snapshot_workspace(dir.path()).await;
+        assert_eq!(snap.len(), 1);
+        assert!(snap.contains_key(&dir.path().join("visible.png")));
+    }
+
     #[tokio::test]
     async fn test_detect_new_files_created() {
         let dir = tempfile::tempdir().unwrap();
@@ -243,4 +416,70 @@ mod tests {
         assert_eq!(new.len(), 1);
         assert!(new[0].ends_with("new.txt"));
     }
+
+    #[tokio::test]
+    async fn test_validate_rejects_zero_byte_photo() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("empty.png");
+        tokio::fs::write(&path, b"").await.unwrap();
+
+        let files = vec![DetectedFile {
+            path,
+            filename: "empty.png".to_string(),
+            file_type: DetectedFileType::Photo,
+        }];
+        assert!(validate_detected_files(files).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_undecodeable_photo() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fake.png");
+        tokio::fs::write(&path, b"not actually a png").await.unwrap();
+
+        let files = vec![DetectedFile {
+            path,
+            filename: "fake.png".to_string(),
+            file_type: DetectedFileType::Photo,
+        }];
+        assert!(validate_detected_files(files).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_malformed_pdf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fake.pdf");
+        tokio::fs::write(&path, b"not a pdf").await.unwrap();
+
+        let files = vec![DetectedFile {
+            path,
+            filename: "fake.pdf".to_string(),
+            file_type: DetectedFileType::Document,
+        }];
+        assert!(validate_detected_files(files).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_passes_non_pdf_document_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        tokio::fs::write(&path, b"# hello").await.unwrap();
+
+        let files = vec![DetectedFile {
+            path,
+            filename: "notes.md".to_string(),
+            file_type: DetectedFileType::Document,
+        }];
+        assert_eq!(validate_detected_files(files).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_missing_file() {
+        let files = vec![DetectedFile {
+            path: PathBuf::from("/nonexistent/ghost.png"),
+            filename: "ghost.png".to_string(),
+            file_type: DetectedFileType::Photo,
+        }];
+        assert!(validate_detected_files(files).await.is_empty());
+    }
 }
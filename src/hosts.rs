@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::MiniClawError;
+
+/// One named remote host `/host add` registered, reachable over SSH.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RemoteHost {
+    pub user: String,
+    pub host: String,
+    pub port: u16,
+}
+
+/// Which machine a chat's `/shell` commands run on: the local machine, or
+/// one of the named hosts registered with `/host add`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ActiveTarget {
+    Local,
+    Remote(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct HostData {
+    hosts: HashMap<String, RemoteHost>,
+    active: HashMap<i64, ActiveTarget>,
+    remote_cwd: HashMap<i64, String>,
+}
+
+/// Registry of named remote hosts plus, per chat, which target is active —
+/// the control-plane state behind `/host`. Persists to disk (mirroring
+/// `WorkspaceManager`/`AccessManager`) so a chat's chosen host survives a
+/// restart. `AppState::exec_session` resolves the active target into the
+/// `ExecSession` (see `crate::exec_backend`) that `/shell` actually runs
+/// against.
+pub struct HostManager {
+    data: HostData,
+    state_file: PathBuf,
+    loaded: bool,
+}
+
+impl HostManager {
+    pub fn new() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+        Self {
+            data: HostData::default(),
+            state_file: home.join(".mini-claw").join("hosts.json"),
+            loaded: false,
+        }
+    }
+
+    async fn load(&mut self) {
+        if self.loaded {
+            return;
+        }
+        if let Ok(raw) = tokio::fs::read_to_string(&self.state_file).await {
+            if let Ok(parsed) = serde_json::from_str(&raw) {
+                self.data = parsed;
+            }
+        }
+        self.loaded = true;
+    }
+
+    async fn save(&self) -> Result<(), MiniClawError> {
+        if let Some(dir) = self.state_file.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let json = serde_json::to_string_pretty(&self.data)?;
+        tokio::fs::write(&self.state_file, json).await?;
+        Ok(())
+    }
+
+    pub async fn add_host(
+        &mut self,
+        name: &str,
+        user: String,
+        host: String,
+        port: u16,
+    ) -> Result<(), MiniClawError> {
+        self.load().await;
+        self.data
+            .hosts
+            .insert(name.to_string(), RemoteHost { user, host, port });
+        self.save().await
+    }
+
+    pub async fn get_host(&mut self, name: &str) -> Option<RemoteHost> {
+        self.load().await;
+        self.data.hosts.get(name).cloned()
+    }
+
+    pub async fn list_hosts(&mut self) -> Vec<(String, RemoteHost)> {
+        self.load().await;
+        let mut hosts: Vec<_> = self
+            .data
+            .hosts
+            .iter()
+            .map(|(name, host)| (name.clone(), host.clone()))
+            .collect();
+        hosts.sort_by(|a, b| a.0.cmp(&b.0));
+        hosts
+    }
+
+    /// Points `chat_id` at the named host, resetting its remembered remote
+    /// directory to the host's login directory. Fails if `name` hasn't been
+    /// registered with `/host add`.
+    pub async fn use_host(&mut self, chat_id: i64, name: &str) -> Result<(), MiniClawError> {
+        self.load().await;
+        if !self.data.hosts.contains_key(name) {
+            return Err(MiniClawError::Config(format!("Unknown host: {name}")));
+        }
+        self.data
+            .active
+            .insert(chat_id, ActiveTarget::Remote(name.to_string()));
+        self.data.remote_cwd.insert(chat_id, "~".to_string());
+        self.save().await
+    }
+
+    pub async fn use_local(&mut self, chat_id: i64) -> Result<(), MiniClawError> {
+        self.load().await;
+        self.data.active.insert(chat_id, ActiveTarget::Local);
+        self.data.remote_cwd.remove(&chat_id);
+        self.save().await
+    }
+
+    pub async fn active_target(&mut self, chat_id: i64) -> ActiveTarget {
+        self.load().await;
+        self.data
+            .active
+            .get(&chat_id)
+            .cloned()
+            .unwrap_or(ActiveTarget::Local)
+    }
+
+    pub async fn remote_cwd(&mut self, chat_id: i64) -> String {
+        self.load().await;
+        self.data
+            .remote_cwd
+            .get(&chat_id)
+            .cloned()
+            .unwrap_or_else(|| "~".to_string())
+    }
+
+    pub async fn set_remote_cwd(&mut self, chat_id: i64, cwd: String) -> Result<(), MiniClawError> {
+        self.load().await;
+        self.data.remote_cwd.insert(chat_id, cwd);
+        self.save().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_mgr() -> HostManager {
+        let mut mgr = HostManager::new();
+        mgr.state_file =
+            std::env::temp_dir().join(format!("mini-claw-hosts-test-{}.json", std::process::id()));
+        mgr.loaded = true;
+        mgr
+    }
+
+    #[tokio::test]
+    async fn test_add_and_list_host() {
+        let mut mgr = test_mgr();
+        mgr.add_host("box", "alice".into(), "example.com".into(), 22)
+            .await
+            .unwrap();
+
+        let hosts = mgr.list_hosts().await;
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(hosts[0].0, "box");
+        assert_eq!(hosts[0].1.host, "example.com");
+
+        let _ = tokio::fs::remove_file(&mgr.state_file).await;
+    }
+
+    #[tokio::test]
+    async fn test_default_target_is_local() {
+        let mut mgr = test_mgr();
+        assert_eq!(mgr.active_target(1).await, ActiveTarget::Local);
+    }
+
+    #[tokio::test]
+    async fn test_use_unknown_host_fails() {
+        let mut mgr = test_mgr();
+        assert!(mgr.use_host(1, "nope").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_use_host_and_back_to_local() {
+        let mut mgr = test_mgr();
+        mgr.add_host("box", "alice".into(), "example.com".into(), 22)
+            .await
+            .unwrap();
+
+        mgr.use_host(1, "box").await.unwrap();
+        assert_eq!(
+            mgr.active_target(1).await,
+            ActiveTarget::Remote("box".into())
+        );
+        assert_eq!(mgr.remote_cwd(1).await, "~");
+
+        mgr.use_local(1).await.unwrap();
+        assert_eq!(mgr.active_target(1).await, ActiveTarget::Local);
+
+        let _ = tokio::fs::remove_file(&mgr.state_file).await;
+    }
+
+    #[tokio::test]
+    async fn test_remote_cwd_roundtrip() {
+        let mut mgr = test_mgr();
+        mgr.add_host("box", "alice".into(), "example.com".into(), 22)
+            .await
+            .unwrap();
+        mgr.use_host(1, "box").await.unwrap();
+
+        mgr.set_remote_cwd(1, "/home/alice/project".into())
+            .await
+            .unwrap();
+        assert_eq!(mgr.remote_cwd(1).await, "/home/alice/project");
+
+        let _ = tokio::fs::remove_file(&mgr.state_file).await;
+    }
+}
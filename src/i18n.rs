@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use fluent::{FluentArgs, FluentBundle, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+/// Loads `.ftl` resources from `<locales_dir>/<lang>/main.ftl` into one
+/// `FluentBundle` per locale, and resolves message lookups with a fallback
+/// to `default_lang` and finally to the bare key if nothing matches.
+pub struct Localizer {
+    bundles: HashMap<LanguageIdentifier, FluentBundle<FluentResource>>,
+    default_lang: LanguageIdentifier,
+}
+
+impl Localizer {
+    /// Reads every locale subdirectory under `locales_dir`. A locale whose
+    /// `main.ftl` is missing or fails to parse is skipped rather than
+    /// failing startup — localization is a nicety, not a hard dependency.
+    pub fn load(locales_dir: &Path, default_lang: LanguageIdentifier) -> Self {
+        let mut bundles = HashMap::new();
+
+        if let Ok(entries) = std::fs::read_dir(locales_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !path.is_dir() {
+                    continue;
+                }
+                let Some(lang_str) = path.file_name().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let Ok(lang_id) = lang_str.parse::<LanguageIdentifier>() else {
+                    continue;
+                };
+                let Ok(source) = std::fs::read_to_string(path.join("main.ftl")) else {
+                    continue;
+                };
+                let Ok(resource) = FluentResource::try_new(source) else {
+                    continue;
+                };
+
+                let mut bundle = FluentBundle::new(vec![lang_id.clone()]);
+                bundle.set_use_isolating(false);
+                if bundle.add_resource(resource).is_ok() {
+                    bundles.insert(lang_id, bundle);
+                }
+            }
+        }
+
+        Self {
+            bundles,
+            default_lang,
+        }
+    }
+
+    pub fn default_lang(&self) -> &LanguageIdentifier {
+        &self.default_lang
+    }
+
+    pub fn has_locale(&self, lang: &LanguageIdentifier) -> bool {
+        self.bundles.contains_key(lang)
+    }
+
+    /// Resolves `key` in `lang`'s bundle, falling back to the default
+    /// locale, then to the bare key if neither bundle has it.
+    pub fn t(&self, lang: &LanguageIdentifier, key: &str, args: Option<&FluentArgs>) -> String {
+        for candidate in [lang, &self.default_lang] {
+            let Some(bundle) = self.bundles.get(candidate) else {
+                continue;
+            };
+            let Some(message) = bundle.get_message(key) else {
+                continue;
+            };
+            let Some(pattern) = message.value() else {
+                continue;
+            };
+            let mut errors = Vec::new();
+            return bundle
+                .format_pattern(pattern, args, &mut errors)
+                .into_owned();
+        }
+
+        key.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_ftl(dir: &Path, lang: &str, source: &str) {
+        let lang_dir = dir.join(lang);
+        std::fs::create_dir_all(&lang_dir).unwrap();
+        std::fs::write(lang_dir.join("main.ftl"), source).unwrap();
+    }
+
+    #[test]
+    fn test_load_and_resolve_simple_message() {
+        let dir = tempfile::tempdir().unwrap();
+        write_ftl(dir.path(), "en", "greeting = Hello!\n");
+
+        let localizer = Localizer::load(dir.path(), "en".parse().unwrap());
+        let lang: LanguageIdentifier = "en".parse().unwrap();
+        assert_eq!(localizer.t(&lang, "greeting", None), "Hello!");
+    }
+
+    #[test]
+    fn test_resolve_with_args() {
+        let dir = tempfile::tempdir().unwrap();
+        write_ftl(dir.path(), "en", "rate-limited = Wait { $secs }s\n");
+
+        let localizer = Localizer::load(dir.path(), "en".parse().unwrap());
+        let lang: LanguageIdentifier = "en".parse().unwrap();
+        let mut args = FluentArgs::new();
+        args.set("secs", 5);
+        assert_eq!(localizer.t(&lang, "rate-limited", Some(&args)), "Wait 5s");
+    }
+
+    #[test]
+    fn test_missing_locale_falls_back_to_default() {
+        let dir = tempfile::tempdir().unwrap();
+        write_ftl(dir.path(), "en", "greeting = Hello!\n");
+
+        let localizer = Localizer::load(dir.path(), "en".parse().unwrap());
+        let lang: LanguageIdentifier = "fr".parse().unwrap();
+        assert_eq!(localizer.t(&lang, "greeting", None), "Hello!");
+    }
+
+    #[test]
+    fn test_missing_key_falls_back_to_bare_key() {
+        let dir = tempfile::tempdir().unwrap();
+        write_ftl(dir.path(), "en", "greeting = Hello!\n");
+
+        let localizer = Localizer::load(dir.path(), "en".parse().unwrap());
+        let lang: LanguageIdentifier = "en".parse().unwrap();
+        assert_eq!(localizer.t(&lang, "nonexistent-key", None), "nonexistent-key");
+    }
+}
@@ -1,12 +1,25 @@
+mod access;
 mod bot;
+mod browser_pool;
+mod browser_runner;
 mod config;
 mod error;
+mod exec_backend;
 mod file_detector;
+mod hosts;
+mod i18n;
 mod markdown;
+mod media_group;
+mod pi_rpc;
 mod pi_runner;
+mod pty_shell;
 mod rate_limiter;
 mod sessions;
+mod settings;
+mod storage;
+mod transcript;
 mod workspace;
+mod workspace_watcher;
 
 use anyhow::Result;
 use teloxide::prelude::*;
@@ -79,7 +92,7 @@ async fn main() -> Result<()> {
     }
 
     // Build shared state
-    let state = bot::AppState::new(config.clone());
+    let state = bot::AppState::new(config.clone()).await?;
 
     // Create bot
     let bot = Bot::new(&config.telegram_token);
@@ -1,75 +1,312 @@
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
 fn escape_html(text: &str) -> String {
     text.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
 }
 
+fn escape_attr(text: &str) -> String {
+    escape_html(text).replace('"', "&quot;")
+}
+
+/// Renders markdown to the subset of HTML Telegram's `parse_mode: "HTML"`
+/// understands (`<b>`, `<i>`, `<s>`, `<code>`, `<pre>`, `<a href>`,
+/// `<blockquote>`). Built on `pulldown-cmark`'s event stream rather than
+/// regex substitutions, so nested emphasis, lists, and blockquotes nest
+/// correctly instead of corrupting on overlapping markers. Telegram ignores
+/// `<ul>`/`<li>`/`<h1>`-style tags entirely, so list items and headings are
+/// flattened to plain lines with a bullet prefix / bold text instead.
 pub fn markdown_to_html(text: &str) -> String {
-    // Use null character as delimiter for placeholders (same approach as TS version)
-    const PH: char = '\x00';
-
-    // Extract and preserve code blocks
-    let mut code_blocks: Vec<String> = Vec::new();
-    let mut processed = {
-        let re = regex::Regex::new(r"```(\w*)\n?([\s\S]*?)```").unwrap();
-        re.replace_all(text, |caps: &regex::Captures| {
-            let idx = code_blocks.len();
-            let code = caps.get(2).map_or("", |m| m.as_str()).trim();
-            code_blocks.push(format!("<pre>{}</pre>", escape_html(code)));
-            format!("{PH}CODE_BLOCK_{idx}{PH}")
-        })
-        .into_owned()
-    };
-
-    // Extract inline code
-    let mut inline_codes: Vec<String> = Vec::new();
-    processed = {
-        let re = regex::Regex::new(r"`([^`]+)`").unwrap();
-        re.replace_all(&processed, |caps: &regex::Captures| {
-            let idx = inline_codes.len();
-            let code = caps.get(1).map_or("", |m| m.as_str());
-            inline_codes.push(format!("<code>{}</code>", escape_html(code)));
-            format!("{PH}INLINE_CODE_{idx}{PH}")
-        })
-        .into_owned()
-    };
-
-    // Escape HTML in remaining text
-    processed = escape_html(&processed);
-
-    // Bold: **text** or __text__
-    let re = regex::Regex::new(r"\*\*([^*]+)\*\*").unwrap();
-    processed = re.replace_all(&processed, "<b>$1</b>").into_owned();
-    let re = regex::Regex::new(r"__([^_]+)__").unwrap();
-    processed = re.replace_all(&processed, "<b>$1</b>").into_owned();
+    let parser = Parser::new_ext(text, Options::all());
+
+    let mut out = String::new();
+    let mut list_depth: usize = 0;
+    let mut quote_starts: Vec<usize> = Vec::new();
+    let mut code_block: Option<(Option<String>, String)> = None;
+
+    for event in parser {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Strong => out.push_str("<b>"),
+                Tag::Emphasis => out.push_str("<i>"),
+                Tag::Strikethrough => out.push_str("<s>"),
+                Tag::BlockQuote(_) => quote_starts.push(out.len()),
+                Tag::Link { dest_url, .. } => {
+                    out.push_str(&format!(r#"<a href="{}">"#, escape_attr(&dest_url)));
+                }
+                Tag::CodeBlock(kind) => {
+                    let lang = match kind {
+                        CodeBlockKind::Fenced(lang) if !lang.is_empty() => {
+                            Some(lang.to_string())
+                        }
+                        _ => None,
+                    };
+                    code_block = Some((lang, String::new()));
+                }
+                Tag::Heading { .. } => out.push_str("<b>"),
+                Tag::List(_) => list_depth += 1,
+                Tag::Item => {
+                    let marker = if list_depth <= 1 { '•' } else { '▪' };
+                    let indent = "  ".repeat(list_depth.saturating_sub(1));
+                    out.push_str(&format!("{indent}{marker} "));
+                }
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Strong => out.push_str("</b>"),
+                TagEnd::Emphasis => out.push_str("</i>"),
+                TagEnd::Strikethrough => out.push_str("</s>"),
+                TagEnd::BlockQuote(_) => {
+                    if let Some(start) = quote_starts.pop() {
+                        while out.ends_with('\n') {
+                            out.pop();
+                        }
+                        out.insert_str(start, "<blockquote>");
+                        out.push_str("</blockquote>\n");
+                    }
+                }
+                TagEnd::Link => out.push_str("</a>"),
+                TagEnd::CodeBlock => {
+                    if let Some((lang, code)) = code_block.take() {
+                        let code = escape_html(code.trim_end_matches('\n'));
+                        match lang {
+                            Some(lang) => out.push_str(&format!(
+                                r#"<pre><code class="language-{}">{code}</code></pre>"#,
+                                escape_attr(&lang)
+                            )),
+                            None => out.push_str(&format!("<pre><code>{code}</code></pre>")),
+                        }
+                        out.push('\n');
+                    }
+                }
+                TagEnd::Heading(_) => out.push_str("</b>\n"),
+                TagEnd::List(_) => list_depth = list_depth.saturating_sub(1),
+                TagEnd::Item => out.push('\n'),
+                TagEnd::Paragraph => out.push('\n'),
+                _ => {}
+            },
+            Event::Text(text) => {
+                if let Some((_, code)) = code_block.as_mut() {
+                    code.push_str(&text);
+                } else {
+                    out.push_str(&escape_html(&text));
+                }
+            }
+            Event::Code(code) => out.push_str(&format!("<code>{}</code>", escape_html(&code))),
+            Event::Html(html) | Event::InlineHtml(html) => out.push_str(&escape_html(&html)),
+            Event::SoftBreak | Event::HardBreak => {
+                if let Some((_, code)) = code_block.as_mut() {
+                    code.push('\n');
+                } else {
+                    out.push('\n');
+                }
+            }
+            _ => {}
+        }
+    }
 
-    // Italic: *text* or _text_ (bold ** already processed above)
-    let re = regex::Regex::new(r"\*([^*]+)\*").unwrap();
-    processed = re.replace_all(&processed, "<i>$1</i>").into_owned();
-    let re = regex::Regex::new(r"\b_([^_]+)_\b").unwrap();
-    processed = re.replace_all(&processed, "<i>$1</i>").into_owned();
+    out.trim().to_string()
+}
 
-    // Strikethrough: ~~text~~
-    let re = regex::Regex::new(r"~~([^~]+)~~").unwrap();
-    processed = re.replace_all(&processed, "<s>$1</s>").into_owned();
+/// A tag still open at a cut point, along with the literal text that opened
+/// it so the next chunk can reopen it verbatim (e.g. `<a href="...">`, not
+/// just `<a>`).
+struct OpenTag {
+    name: String,
+    text: String,
+}
 
-    // Links: [text](url)
-    let re = regex::Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap();
-    processed = re
-        .replace_all(&processed, r#"<a href="$2">$1</a>"#)
-        .into_owned();
+/// Splits an HTML tag like `<b>`, `</code>`, or `<a href="...">` into its
+/// name and whether it's a closing tag.
+fn tag_name(tag: &str) -> (String, bool) {
+    let inner = tag.trim_start_matches('<').trim_end_matches('>');
+    match inner.strip_prefix('/') {
+        Some(name) => (name.to_string(), true),
+        None => (
+            inner.split_whitespace().next().unwrap_or("").to_string(),
+            false,
+        ),
+    }
+}
+
+/// The closing tags needed to end every tag still open in `stack`, innermost
+/// first.
+fn close_suffix(stack: &[OpenTag]) -> String {
+    stack.iter().rev().map(|t| format!("</{}>", t.name)).collect()
+}
 
-    // Restore code blocks
-    for (i, block) in code_blocks.iter().enumerate() {
-        processed = processed.replace(&format!("{PH}CODE_BLOCK_{i}{PH}"), block);
+/// The opening tags needed to resume every tag in `stack`, outermost first.
+fn reopen_prefix(stack: &[OpenTag]) -> String {
+    stack.iter().map(|t| t.text.clone()).collect()
+}
+
+/// Finds the largest byte index `<= idx` that lands on a UTF-8 char
+/// boundary, so a hard split can never slice a multi-byte codepoint.
+pub(crate) fn floor_char_boundary(s: &str, idx: usize) -> usize {
+    let mut i = idx.min(s.len());
+    while i > 0 && !s.is_char_boundary(i) {
+        i -= 1;
     }
+    i
+}
 
-    // Restore inline code
-    for (i, code) in inline_codes.iter().enumerate() {
-        processed = processed.replace(&format!("{PH}INLINE_CODE_{i}{PH}"), code);
+/// Closes every tag still open in `stack` to terminate the outgoing chunk,
+/// then reopens them to seed the next one.
+fn flush(current: &mut String, chunks: &mut Vec<String>, stack: &[OpenTag]) {
+    if current.is_empty() {
+        return;
     }
+    let mut chunk = std::mem::take(current);
+    chunk.push_str(&close_suffix(stack));
+    chunks.push(chunk);
+    *current = reopen_prefix(stack);
+}
 
-    processed
+/// Appends a single `<...>` tag, cutting to a fresh chunk first if adding it
+/// (plus its own eventual closing tag) would overrun `limit`. Closing tags
+/// are always safe to append as-is: `push_text`'s budget already reserves
+/// room for every open tag's close text, and a closing tag only shrinks
+/// that reservation.
+fn push_tag(tag: &str, limit: usize, current: &mut String, chunks: &mut Vec<String>, stack: &mut Vec<OpenTag>) {
+    let (name, is_closing) = tag_name(tag);
+    if !is_closing {
+        let projected = current.len() + 2 * tag.len() + close_suffix(stack).len();
+        if !current.is_empty() && projected > limit {
+            flush(current, chunks, stack);
+        }
+    }
+    current.push_str(tag);
+    if is_closing {
+        if stack.last().is_some_and(|t| t.name == name) {
+            stack.pop();
+        }
+    } else {
+        stack.push(OpenTag {
+            name,
+            text: tag.to_string(),
+        });
+    }
+}
+
+/// Appends a run of plain text, cutting at the last newline inside the
+/// remaining budget (reserving room for the currently open tags' closing
+/// text), or hard-cutting at a char boundary if no newline is available.
+fn push_text(text: &str, limit: usize, current: &mut String, chunks: &mut Vec<String>, stack: &[OpenTag]) {
+    let mut remaining = text;
+    while !remaining.is_empty() {
+        let suffix_len = close_suffix(stack).len();
+        let budget = limit.saturating_sub(current.len() + suffix_len);
+
+        if remaining.len() <= budget {
+            current.push_str(remaining);
+            return;
+        }
+
+        let window_end = floor_char_boundary(remaining, budget.max(1));
+        let window = &remaining[..window_end];
+        let mut split_at = window.rfind('\n').unwrap_or(window_end);
+        if split_at == 0 {
+            split_at = window_end.max(1);
+        }
+        split_at = floor_char_boundary(remaining, split_at);
+        if split_at == 0 {
+            // `window_end` itself landed on a char boundary of 0 (a
+            // multi-byte char sits right at the front); take that one char
+            // anyway so the loop always makes forward progress.
+            split_at = (1..=remaining.len())
+                .find(|&i| remaining.is_char_boundary(i))
+                .unwrap_or(remaining.len());
+        }
+
+        current.push_str(&remaining[..split_at]);
+        flush(current, chunks, stack);
+
+        remaining = remaining[split_at..].trim_start_matches('\n');
+    }
+}
+
+/// Places an entire `<pre>...</pre>` block (captured verbatim, including its
+/// nested `<code>` tag) without splitting it, as long as it fits either the
+/// current chunk or a fresh one. Only when the block alone is longer than
+/// `limit` does it fall back to walking inside it like ordinary content.
+fn push_pre_block(block: &str, limit: usize, current: &mut String, chunks: &mut Vec<String>, stack: &mut Vec<OpenTag>) {
+    if current.len() + block.len() <= limit {
+        current.push_str(block);
+        return;
+    }
+    if block.len() + reopen_prefix(stack).len() <= limit {
+        flush(current, chunks, stack);
+        current.push_str(block);
+        return;
+    }
+
+    if !current.is_empty() {
+        flush(current, chunks, stack);
+    }
+    walk(block, limit, current, chunks, stack, false);
+}
+
+/// Walks `html`, dispatching each tag/text run to `push_tag`/`push_text`.
+/// When `atomic_pre` is set, a `<pre>` tag triggers a lookahead to its
+/// matching `</pre>` so the whole code block can be kept intact via
+/// [`push_pre_block`] instead of being cut mid-block like ordinary tags.
+fn walk(mut rest: &str, limit: usize, current: &mut String, chunks: &mut Vec<String>, stack: &mut Vec<OpenTag>, atomic_pre: bool) {
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            push_text(rest, limit, current, chunks, stack);
+            break;
+        };
+        if start > 0 {
+            push_text(&rest[..start], limit, current, chunks, stack);
+        }
+        let tag_end = rest[start..]
+            .find('>')
+            .map(|e| start + e + 1)
+            .unwrap_or(rest.len());
+        let tag = &rest[start..tag_end];
+        let (name, is_closing) = tag_name(tag);
+
+        if atomic_pre && name == "pre" && !is_closing {
+            if let Some(rel_close) = rest[tag_end..].find("</pre>") {
+                let abs_close_end = tag_end + rel_close + "</pre>".len();
+                let block = &rest[start..abs_close_end];
+                push_pre_block(block, limit, current, chunks, stack);
+                rest = &rest[abs_close_end..];
+                continue;
+            }
+        }
+
+        push_tag(tag, limit, current, chunks, stack);
+        rest = &rest[tag_end..];
+    }
+}
+
+/// Splits Telegram-HTML `html` (as produced by [`markdown_to_html`]) into
+/// chunks no longer than `limit`, the way the send path can call before
+/// every `sendMessage` to stay under Telegram's 4096-character cap. Cuts
+/// prefer a newline, then a tag boundary, and never land inside a `<...>`
+/// tag or split open a `<pre>` block unless that block alone exceeds
+/// `limit`. Tags still open at a cut point are closed at the end of the
+/// outgoing chunk and reopened at the start of the next, so bold, italic,
+/// links, and code blocks keep rendering correctly across parts.
+pub fn split_html_message(html: &str, limit: usize) -> Vec<String> {
+    if html.len() <= limit {
+        return vec![html.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut stack: Vec<OpenTag> = Vec::new();
+
+    walk(html, limit, &mut current, &mut chunks, &mut stack, true);
+
+    if !current.is_empty() {
+        current.push_str(&close_suffix(&stack));
+        chunks.push(current);
+    }
+
+    chunks
 }
 
 pub fn strip_markdown(text: &str) -> String {
@@ -122,11 +359,19 @@ mod tests {
         assert_eq!(markdown_to_html("_italic_"), "<i>italic</i>");
     }
 
+    #[test]
+    fn test_nested_emphasis() {
+        assert_eq!(
+            markdown_to_html("**bold _and_ italic**"),
+            "<b>bold <i>and</i> italic</b>"
+        );
+    }
+
     #[test]
     fn test_code_block() {
         assert_eq!(
             markdown_to_html("```rust\nfn main() {}\n```"),
-            "<pre>fn main() {}</pre>"
+            r#"<pre><code class="language-rust">fn main() {}</code></pre>"#
         );
     }
 
@@ -144,7 +389,7 @@ mod tests {
     fn test_html_in_code_block() {
         assert_eq!(
             markdown_to_html("```\n<div>test</div>\n```"),
-            "<pre>&lt;div&gt;test&lt;/div&gt;</pre>"
+            "<pre><code>&lt;div&gt;test&lt;/div&gt;</code></pre>"
         );
     }
 
@@ -169,6 +414,19 @@ mod tests {
         assert!(!output.contains("<b>"));
     }
 
+    #[test]
+    fn test_list() {
+        assert_eq!(markdown_to_html("- one\n- two"), "• one\n• two");
+    }
+
+    #[test]
+    fn test_blockquote() {
+        assert_eq!(
+            markdown_to_html("> quoted text"),
+            "<blockquote>quoted text</blockquote>"
+        );
+    }
+
     #[test]
     fn test_strip_markdown_bold() {
         assert_eq!(strip_markdown("**bold**"), "bold");
@@ -188,4 +446,46 @@ mod tests {
     fn test_plain_text_unchanged() {
         assert_eq!(markdown_to_html("hello world"), "hello world");
     }
+
+    #[test]
+    fn test_split_html_message_under_limit() {
+        assert_eq!(
+            split_html_message("<b>short</b>", 100),
+            vec!["<b>short</b>"]
+        );
+    }
+
+    #[test]
+    fn test_split_html_message_reopens_tags() {
+        assert_eq!(
+            split_html_message("<b>0123456789</b>", 8),
+            vec![
+                "<b>0</b>", "<b>1</b>", "<b>2</b>", "<b>3</b>", "<b>4</b>", "<b>5</b>",
+                "<b>6</b>", "<b>7</b>", "<b>8</b>", "<b>9</b>",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_html_message_keeps_pre_block_atomic() {
+        let prefix = "x".repeat(10);
+        let pre_block = format!("<pre><code>{}</code></pre>", "y".repeat(10));
+        let html = format!("{prefix}{pre_block}");
+        let limit = prefix.len() + pre_block.len() - 1;
+        assert_eq!(split_html_message(&html, limit), vec![prefix, pre_block]);
+    }
+
+    #[test]
+    fn test_split_html_message_splits_oversized_pre_block() {
+        let inner = format!("{}\n{}", "a".repeat(20), "b".repeat(20));
+        let html = format!("<pre><code>{inner}</code></pre>");
+        let limit = 30;
+        let chunks = split_html_message(&html, limit);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= limit, "{chunk:?} exceeds limit {limit}");
+        }
+        assert!(chunks[0].starts_with("<pre><code>"));
+        assert!(chunks.last().unwrap().ends_with("</code></pre>"));
+    }
 }
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Duration;
+
+/// How long to wait after the last file in an album arrives before treating
+/// the group as complete. Longer than `WorkspaceWatcher`'s 300ms debounce
+/// window.
+const QUIET_PERIOD: Duration = Duration::from_millis(800);
+
+/// One file downloaded from an album message.
+#[derive(Debug, Clone)]
+pub struct GroupedFile {
+    pub path: PathBuf,
+    pub filename: String,
+    pub is_image: bool,
+}
+
+struct PendingGroup {
+    files: Vec<GroupedFile>,
+    caption: Option<String>,
+    bump_tx: mpsc::UnboundedSender<()>,
+}
+
+/// Buffers Telegram media-group (album) messages by `media_group_id`. Each
+/// message in an album arrives as its own update, so the first file to
+/// arrive for a group starts a debounce timer; every subsequent file resets
+/// it. Once `QUIET_PERIOD` passes with no new arrivals, `on_ready` fires
+/// once with every file collected so far, so an album triggers a single Pi
+/// run instead of one per file.
+pub struct MediaGroupBuffer {
+    pending: Mutex<HashMap<String, Arc<Mutex<PendingGroup>>>>,
+}
+
+impl MediaGroupBuffer {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn add<F>(
+        self: &Arc<Self>,
+        media_group_id: String,
+        file: GroupedFile,
+        caption: Option<String>,
+        on_ready: F,
+    ) where
+        F: FnOnce(Vec<GroupedFile>, Option<String>) + Send + 'static,
+    {
+        let mut pending = self.pending.lock().await;
+        if let Some(group) = pending.get(&media_group_id) {
+            let group = group.clone();
+            drop(pending);
+            let mut g = group.lock().await;
+            g.files.push(file);
+            if g.caption.is_none() {
+                g.caption = caption;
+            }
+            let _ = g.bump_tx.send(());
+            return;
+        }
+
+        let (bump_tx, mut bump_rx) = mpsc::unbounded_channel();
+        let group = Arc::new(Mutex::new(PendingGroup {
+            files: vec![file],
+            caption,
+            bump_tx,
+        }));
+        pending.insert(media_group_id.clone(), group.clone());
+        drop(pending);
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    got = bump_rx.recv() => {
+                        if got.is_none() {
+                            break;
+                        }
+                    }
+                    _ = tokio::time::sleep(QUIET_PERIOD) => break,
+                }
+            }
+
+            this.pending.lock().await.remove(&media_group_id);
+            let g = group.lock().await;
+            on_ready(g.files.clone(), g.caption.clone());
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_single_file_fires_after_quiet_period() {
+        let buffer = Arc::new(MediaGroupBuffer::new());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        buffer
+            .add(
+                "group1".to_string(),
+                GroupedFile {
+                    path: PathBuf::from("/tmp/a.txt"),
+                    filename: "a.txt".to_string(),
+                    is_image: false,
+                },
+                Some("look at this".to_string()),
+                move |files, caption| {
+                    let _ = tx.send((files, caption));
+                },
+            )
+            .await;
+
+        let (files, caption) = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("group should fire")
+            .expect("channel should not close");
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(caption.as_deref(), Some("look at this"));
+    }
+
+    #[tokio::test]
+    async fn test_grouped_files_collected_into_one_batch() {
+        let buffer = Arc::new(MediaGroupBuffer::new());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let first = GroupedFile {
+            path: PathBuf::from("/tmp/a.jpg"),
+            filename: "a.jpg".to_string(),
+            is_image: true,
+        };
+        let second = GroupedFile {
+            path: PathBuf::from("/tmp/b.jpg"),
+            filename: "b.jpg".to_string(),
+            is_image: true,
+        };
+
+        let tx2 = tx.clone();
+        buffer
+            .add("group1".to_string(), first, None, move |files, caption| {
+                let _ = tx2.send((files, caption));
+            })
+            .await;
+        buffer
+            .add("group1".to_string(), second, None, move |files, caption| {
+                let _ = tx.send((files, caption));
+            })
+            .await;
+
+        let (files, _caption) = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("group should fire")
+            .expect("channel should not close");
+
+        assert_eq!(files.len(), 2);
+    }
+}
@@ -1,31 +1,172 @@
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use notify::{RecursiveMode, Watcher};
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
 use tokio::process::{Child, Command};
 use tokio::sync::mpsc;
+use tokio::time::Duration;
 
 use crate::config::ThinkingLevel;
+use crate::file_detector::{categorize_files, DetectedFile};
 
+/// Each variant carries the `request_id` of the command it's a response
+/// to (the `req-N` id `send_prompt`/`send_steer`/`send_follow_up` stamp on
+/// outgoing commands, echoed back by `pi`), so a client juggling more than
+/// one in-flight request — or cancelling one — can tell which request an
+/// event belongs to. `None` when `pi` didn't echo an id back.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum PiEvent {
-    AgentStart,
-    TextDelta(String),
-    ThinkingDelta(String),
-    ToolStart { name: String },
-    ToolUpdate(String),
-    ToolEnd,
-    AgentEnd,
-    Error(String),
+    AgentStart { request_id: Option<String> },
+    TextDelta { delta: String, request_id: Option<String> },
+    ThinkingDelta { delta: String, request_id: Option<String> },
+    ToolStart { name: String, request_id: Option<String> },
+    ToolUpdate { output: String, request_id: Option<String> },
+    ToolEnd { request_id: Option<String> },
+    AgentEnd { request_id: Option<String> },
+    Error { message: String, request_id: Option<String> },
 }
 
+impl PiEvent {
+    fn request_id(&self) -> Option<&str> {
+        match self {
+            PiEvent::AgentStart { request_id }
+            | PiEvent::TextDelta { request_id, .. }
+            | PiEvent::ThinkingDelta { request_id, .. }
+            | PiEvent::ToolStart { request_id, .. }
+            | PiEvent::ToolUpdate { request_id, .. }
+            | PiEvent::ToolEnd { request_id }
+            | PiEvent::AgentEnd { request_id }
+            | PiEvent::Error { request_id, .. } => request_id.as_deref(),
+        }
+    }
+
+    /// Whether this event ends the turn it belongs to, one way or another.
+    fn is_terminal(&self) -> bool {
+        matches!(self, PiEvent::AgentEnd { .. } | PiEvent::Error { .. })
+    }
+}
+
+/// The spawned `pi` process, in whichever of the two shapes
+/// `PiRpcProcess::spawn` chose: a plain OS process with piped stdio, or one
+/// attached to a pseudo-terminal master (see `crate::pty_shell`, which does
+/// the same split for `/shell`'s interactive commands).
+enum RpcChild {
+    Piped(Child),
+    Pty(Box<dyn portable_pty::Child + Send + Sync>, Box<dyn MasterPty + Send>),
+}
+
+impl RpcChild {
+    fn is_alive(&mut self) -> bool {
+        match self {
+            RpcChild::Piped(child) => child.try_wait().ok().flatten().is_none(),
+            RpcChild::Pty(child, _) => child.try_wait().ok().flatten().is_none(),
+        }
+    }
+
+    async fn kill(&mut self) {
+        match self {
+            RpcChild::Piped(child) => {
+                let _ = child.kill().await;
+            }
+            RpcChild::Pty(child, _) => {
+                let _ = child.kill();
+            }
+        }
+    }
+
+    /// Reaps the child and returns its exit status as a display string, for
+    /// inclusion in the diagnostic `PiEvent::Error` `LiveSessionManager`
+    /// emits when a session dies unexpectedly. Only meaningful once the
+    /// child has actually exited (e.g. after its reader task has seen EOF).
+    async fn exit_status(&mut self) -> String {
+        match self {
+            RpcChild::Piped(child) => match child.wait().await {
+                Ok(status) => status.to_string(),
+                Err(e) => format!("unknown (failed to read exit status: {e})"),
+            },
+            RpcChild::Pty(child, _) => match child.wait() {
+                Ok(status) => format!("{status:?}"),
+                Err(e) => format!("unknown (failed to read exit status: {e})"),
+            },
+        }
+    }
+
+    /// Resizes the pseudo-terminal, if this child has one. A no-op for the
+    /// piped backend, which has no terminal to resize.
+    fn resize(&self, rows: u16, cols: u16) -> anyhow::Result<()> {
+        if let RpcChild::Pty(_, master) = self {
+            master.resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Where `send_prompt`/`send_steer`/`send_follow_up` write their JSON-lines
+/// commands, matching whichever `RpcChild` variant is in use.
+enum RpcWriter {
+    Piped(BufWriter<tokio::process::ChildStdin>),
+    Pty(Box<dyn Write + Send>),
+}
+
+impl RpcWriter {
+    async fn write_line(&mut self, line: &str) -> anyhow::Result<()> {
+        match self {
+            RpcWriter::Piped(writer) => {
+                writer.write_all(line.as_bytes()).await?;
+                writer.flush().await?;
+            }
+            // portable-pty's writer is a plain synchronous `Write`, the same
+            // as `PtyShell::write_stdin` uses directly without a
+            // `spawn_blocking`, since a pty buffer write doesn't block.
+            RpcWriter::Pty(writer) => {
+                writer.write_all(line.as_bytes())?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How many trailing stderr lines `spawn_piped`'s stderr reader keeps
+/// around, for inclusion in the diagnostic event emitted when a session
+/// dies unexpectedly.
+const STDERR_TAIL_LINES: usize = 20;
+
 pub struct PiRpcProcess {
-    child: Child,
-    stdin: BufWriter<tokio::process::ChildStdin>,
+    child: RpcChild,
+    stdin: RpcWriter,
     event_rx: mpsc::UnboundedReceiver<PiEvent>,
     request_counter: u64,
     _reader_handle: tokio::task::JoinHandle<()>,
+    detected_files_rx: mpsc::UnboundedReceiver<Vec<DetectedFile>>,
+    _watcher: notify::RecommendedWatcher,
+    _watcher_handle: tokio::task::JoinHandle<()>,
+    /// The last `STDERR_TAIL_LINES` lines the child wrote to stderr. Only
+    /// populated in piped mode; in PTY mode stderr is already merged into
+    /// the terminal's combined output stream, so there's nothing extra to
+    /// capture here.
+    stderr_tail: Arc<std::sync::Mutex<VecDeque<String>>>,
+    session_path: PathBuf,
+    workspace: PathBuf,
+    thinking_level: ThinkingLevel,
+    use_pty: bool,
+    /// The id of the most recently sent prompt/steer/follow-up command,
+    /// i.e. the request `cancel()` targets when none is given explicitly.
+    last_request_id: Option<String>,
+    /// Set by `cancel()` to the id of the request being aborted, so
+    /// `recv_event` can keep dropping stray events for it until its
+    /// terminal event (`AgentEnd`/`Error`) arrives.
+    cancelled_request_id: Option<String>,
 }
 
 impl PiRpcProcess {
@@ -33,129 +174,395 @@ impl PiRpcProcess {
         session_path: &Path,
         workspace: &Path,
         thinking_level: ThinkingLevel,
+        use_pty: bool,
     ) -> anyhow::Result<Self> {
         let home = dirs::home_dir().unwrap_or_default();
         let pi_agent_dir = home.join(".pi").join("agent");
 
-        let mut child = Command::new("pi")
-            .arg("--mode")
-            .arg("rpc")
-            .arg("--session")
-            .arg(session_path)
-            .arg("--thinking")
-            .arg(thinking_level.to_string())
-            .current_dir(workspace)
-            .env("PI_AGENT_DIR", &pi_agent_dir)
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::null())
-            .spawn()?;
-
-        let stdout = child.stdout.take().ok_or_else(|| {
-            anyhow::anyhow!("Failed to capture Pi stdout")
-        })?;
-        let stdin = child.stdin.take().ok_or_else(|| {
-            anyhow::anyhow!("Failed to capture Pi stdin")
-        })?;
-
-        let (event_tx, event_rx) = mpsc::unbounded_channel();
-
-        // Spawn stdout reader task
-        let reader_handle = tokio::spawn(async move {
-            let mut reader = BufReader::new(stdout).lines();
-            while let Ok(Some(line)) = reader.next_line().await {
-                let event = parse_rpc_event(&line);
-                if event_tx.send(event).is_err() {
-                    break;
-                }
-            }
-        });
+        let stderr_tail = Arc::new(std::sync::Mutex::new(VecDeque::with_capacity(STDERR_TAIL_LINES)));
+
+        let (child, stdin, event_rx, reader_handle) = if use_pty {
+            spawn_pty_backed(session_path, workspace, thinking_level, &pi_agent_dir)?
+        } else {
+            spawn_piped(session_path, workspace, thinking_level, &pi_agent_dir, stderr_tail.clone())?
+        };
+
+        let (detected_files_tx, detected_files_rx) = mpsc::unbounded_channel();
+        let (watcher, watcher_handle) = spawn_file_watcher(workspace.to_path_buf(), detected_files_tx)?;
 
         Ok(Self {
             child,
-            stdin: BufWriter::new(stdin),
+            stdin,
             event_rx,
             request_counter: 0,
             _reader_handle: reader_handle,
+            detected_files_rx,
+            _watcher: watcher,
+            _watcher_handle: watcher_handle,
+            stderr_tail,
+            session_path: session_path.to_path_buf(),
+            workspace: workspace.to_path_buf(),
+            thinking_level,
+            use_pty,
+            last_request_id: None,
+            cancelled_request_id: None,
         })
     }
 
-    pub async fn send_prompt(&mut self, message: &str) -> anyhow::Result<()> {
+    /// Joins the captured stderr tail into a single block of text, for
+    /// inclusion in a supervisor's diagnostic message. Empty in PTY mode.
+    fn stderr_tail_text(&self) -> String {
+        let tail = self.stderr_tail.lock().unwrap();
+        tail.iter().cloned().collect::<Vec<_>>().join("\n")
+    }
+
+    /// Stamps and records a new `req-N` id for an outgoing command, so
+    /// `cancel()` without an explicit id knows what's currently in flight.
+    fn next_request_id(&mut self) -> String {
         self.request_counter += 1;
+        let id = format!("req-{}", self.request_counter);
+        self.last_request_id = Some(id.clone());
+        self.cancelled_request_id = None;
+        id
+    }
+
+    pub async fn send_prompt(&mut self, message: &str) -> anyhow::Result<()> {
+        let id = self.next_request_id();
         let cmd = serde_json::json!({
-            "id": format!("req-{}", self.request_counter),
+            "id": id,
             "type": "prompt",
             "message": message,
         });
         let mut line = serde_json::to_string(&cmd)?;
         line.push('\n');
-        self.stdin.write_all(line.as_bytes()).await?;
-        self.stdin.flush().await?;
+        self.stdin.write_line(&line).await?;
         Ok(())
     }
 
     #[allow(dead_code)]
     pub async fn send_steer(&mut self, message: &str) -> anyhow::Result<()> {
-        self.request_counter += 1;
+        let id = self.next_request_id();
         let cmd = serde_json::json!({
-            "id": format!("req-{}", self.request_counter),
+            "id": id,
             "type": "steer",
             "message": message,
         });
         let mut line = serde_json::to_string(&cmd)?;
         line.push('\n');
-        self.stdin.write_all(line.as_bytes()).await?;
-        self.stdin.flush().await?;
+        self.stdin.write_line(&line).await?;
         Ok(())
     }
 
     #[allow(dead_code)]
     pub async fn send_follow_up(&mut self, message: &str) -> anyhow::Result<()> {
-        self.request_counter += 1;
+        let id = self.next_request_id();
         let cmd = serde_json::json!({
-            "id": format!("req-{}", self.request_counter),
+            "id": id,
             "type": "follow_up",
             "message": message,
         });
         let mut line = serde_json::to_string(&cmd)?;
         line.push('\n');
-        self.stdin.write_all(line.as_bytes()).await?;
-        self.stdin.flush().await?;
+        self.stdin.write_line(&line).await?;
         Ok(())
     }
 
+    /// Aborts the in-flight request (the most recent prompt/steer/
+    /// follow-up), by writing a `{"type":"cancel","id":...}` command and
+    /// dropping whichever partial-turn events are already buffered for it.
+    /// Returns `Ok(false)` without writing anything if there's no request
+    /// to cancel.
+    pub async fn cancel(&mut self) -> anyhow::Result<bool> {
+        let Some(request_id) = self.last_request_id.clone() else {
+            return Ok(false);
+        };
+
+        let cmd = serde_json::json!({
+            "type": "cancel",
+            "id": request_id,
+        });
+        let mut line = serde_json::to_string(&cmd)?;
+        line.push('\n');
+        self.stdin.write_line(&line).await?;
+
+        // Only one request is ever in flight per session, so anything
+        // already buffered belongs to the turn being cancelled — drop it
+        // rather than surfacing stale deltas after the user asked to stop.
+        while self.event_rx.try_recv().is_ok() {}
+
+        self.cancelled_request_id = Some(request_id);
+        Ok(true)
+    }
+
+    /// Receives the next event, silently dropping non-terminal events that
+    /// belong to a request `cancel()` already aborted (in case `pi` keeps
+    /// streaming briefly after the cancel command is sent).
     pub async fn recv_event(&mut self) -> Option<PiEvent> {
-        self.event_rx.recv().await
+        loop {
+            let event = self.event_rx.recv().await?;
+            if let Some(cancelled) = self.cancelled_request_id.clone() {
+                if event.request_id() == Some(cancelled.as_str()) {
+                    if event.is_terminal() {
+                        self.cancelled_request_id = None;
+                        return Some(event);
+                    }
+                    continue;
+                }
+            }
+            return Some(event);
+        }
+    }
+
+    /// Drains one settled batch of workspace file changes, already
+    /// categorized by `spawn_file_watcher`'s debounce loop, without
+    /// blocking. `None` means no batch has settled since the last call.
+    pub fn try_recv_detected_files(&mut self) -> Option<Vec<DetectedFile>> {
+        self.detected_files_rx.try_recv().ok()
     }
 
     pub fn is_alive(&mut self) -> bool {
-        self.child.try_wait().ok().flatten().is_none()
+        self.child.is_alive()
     }
 
     pub async fn kill(&mut self) {
-        let _ = self.child.kill().await;
+        self.child.kill().await;
+    }
+
+    /// Resizes the underlying pseudo-terminal, if this process was spawned
+    /// with `use_pty: true`. A no-op otherwise.
+    pub fn resize(&self, rows: u16, cols: u16) -> anyhow::Result<()> {
+        self.child.resize(rows, cols)
     }
 }
 
+type SpawnedBackend = (
+    RpcChild,
+    RpcWriter,
+    mpsc::UnboundedReceiver<PiEvent>,
+    tokio::task::JoinHandle<()>,
+);
+
+/// Spawns `pi` with plain piped stdio, as `PiRpcProcess::spawn` has always
+/// done. Stderr is now captured (instead of discarded) into `stderr_tail`,
+/// a bounded ring buffer `LiveSessionManager` reads from when the process
+/// dies unexpectedly.
+fn spawn_piped(
+    session_path: &Path,
+    workspace: &Path,
+    thinking_level: ThinkingLevel,
+    pi_agent_dir: &Path,
+    stderr_tail: Arc<std::sync::Mutex<VecDeque<String>>>,
+) -> anyhow::Result<SpawnedBackend> {
+    let mut child = Command::new("pi")
+        .arg("--mode")
+        .arg("rpc")
+        .arg("--session")
+        .arg(session_path)
+        .arg("--thinking")
+        .arg(thinking_level.to_string())
+        .current_dir(workspace)
+        .env("PI_AGENT_DIR", pi_agent_dir)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("Failed to capture Pi stdout"))?;
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("Failed to capture Pi stdin"))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("Failed to capture Pi stderr"))?;
+
+    let (event_tx, event_rx) = mpsc::unbounded_channel();
+
+    let reader_handle = tokio::spawn(async move {
+        let mut reader = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            let event = parse_rpc_event(&line);
+            if event_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            let mut tail = stderr_tail.lock().unwrap();
+            if tail.len() == STDERR_TAIL_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+    });
+
+    Ok((
+        RpcChild::Piped(child),
+        RpcWriter::Piped(BufWriter::new(stdin)),
+        event_rx,
+        reader_handle,
+    ))
+}
+
+/// The default pseudo-terminal size a PTY-backed `pi` process starts with,
+/// before any `PiRpcProcess::resize` call narrows or widens it.
+const DEFAULT_PTY_ROWS: u16 = 24;
+const DEFAULT_PTY_COLS: u16 = 80;
+
+/// Spawns `pi` attached to a pseudo-terminal master instead of plain pipes,
+/// so tools that check `isatty()` (progress bars, color, interactive
+/// prompts) behave the way they would in a real terminal. The JSON-lines
+/// protocol is unaffected: the PTY reader splits on newlines and feeds the
+/// same `parse_rpc_event` the piped backend uses, into the same event
+/// channel, so callers can't tell the two backends apart.
+fn spawn_pty_backed(
+    session_path: &Path,
+    workspace: &Path,
+    thinking_level: ThinkingLevel,
+    pi_agent_dir: &Path,
+) -> anyhow::Result<SpawnedBackend> {
+    let pty_system = native_pty_system();
+    let pair = pty_system.openpty(PtySize {
+        rows: DEFAULT_PTY_ROWS,
+        cols: DEFAULT_PTY_COLS,
+        pixel_width: 0,
+        pixel_height: 0,
+    })?;
+
+    let mut builder = CommandBuilder::new("pi");
+    builder.arg("--mode");
+    builder.arg("rpc");
+    builder.arg("--session");
+    builder.arg(session_path);
+    builder.arg("--thinking");
+    builder.arg(thinking_level.to_string());
+    builder.cwd(workspace);
+    builder.env("PI_AGENT_DIR", pi_agent_dir);
+
+    let child = pair.slave.spawn_command(builder)?;
+    // The slave side belongs to the child now; drop our copy so the master
+    // sees EOF once the child exits.
+    drop(pair.slave);
+
+    let mut pty_reader = pair.master.try_clone_reader()?;
+    let writer = pair.master.take_writer()?;
+
+    let (event_tx, event_rx) = mpsc::unbounded_channel();
+
+    let reader_handle = tokio::task::spawn_blocking(move || {
+        let mut buf = [0u8; 4096];
+        let mut pending = String::new();
+        loop {
+            let n = match pty_reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(_) => break,
+            };
+            pending.push_str(&String::from_utf8_lossy(&buf[..n]));
+            while let Some(pos) = pending.find('\n') {
+                let line = pending.drain(..=pos).collect::<String>();
+                let line = line.trim_end_matches(['\r', '\n']);
+                let event = parse_rpc_event(line);
+                if event_tx.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    Ok((
+        RpcChild::Pty(child, pair.master),
+        RpcWriter::Pty(writer),
+        event_rx,
+        reader_handle,
+    ))
+}
+
 impl Drop for PiRpcProcess {
     fn drop(&mut self) {
         self._reader_handle.abort();
+        self._watcher_handle.abort();
     }
 }
 
+/// Watches `workspace` recursively for changes for the lifetime of a live
+/// session, debouncing raw `notify` events the way `WorkspaceWatcher` does:
+/// buffer incoming change paths for a ~300ms quiet period, coalescing
+/// duplicates into a `HashSet<PathBuf>`, and only then categorize the
+/// settled batch and emit it. This catches nested output directories a
+/// one-shot `read_dir` snapshot never sees, and surfaces generated
+/// artifacts as soon as they appear instead of only at turn boundaries.
+fn spawn_file_watcher(
+    workspace: PathBuf,
+    detected_files_tx: mpsc::UnboundedSender<Vec<DetectedFile>>,
+) -> anyhow::Result<(notify::RecommendedWatcher, tokio::task::JoinHandle<()>)> {
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<notify::Event>();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = event_tx.send(event);
+        }
+    })?;
+    watcher.watch(&workspace, RecursiveMode::Recursive)?;
+
+    let handle = tokio::spawn(async move {
+        let mut changed: HashSet<PathBuf> = HashSet::new();
+
+        while let Some(event) = event_rx.recv().await {
+            changed.extend(event.paths);
+
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_millis(300)) => break,
+                    maybe_event = event_rx.recv() => {
+                        let Some(event) = maybe_event else { return };
+                        changed.extend(event.paths);
+                    }
+                }
+            }
+
+            let batch: Vec<PathBuf> = changed.drain().collect();
+            let detected = crate::file_detector::validate_detected_files(categorize_files(&batch)).await;
+            if !detected.is_empty() && detected_files_tx.send(detected).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok((watcher, handle))
+}
+
 fn parse_rpc_event(line: &str) -> PiEvent {
     let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
-        return PiEvent::Error(format!("Invalid JSON: {line}"));
+        return PiEvent::Error {
+            message: format!("Invalid JSON: {line}"),
+            request_id: None,
+        };
     };
 
+    // `pi` echoes back whichever id `send_prompt`/`send_steer`/
+    // `send_follow_up`/`cancel` stamped onto the command this event is a
+    // response to, under either key depending on frame type.
+    let request_id = value
+        .get("requestId")
+        .or_else(|| value.get("id"))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
     let event_type = value
         .get("type")
         .and_then(|t| t.as_str())
         .unwrap_or("");
 
     match event_type {
-        "agent_start" => PiEvent::AgentStart,
-        "agent_end" => PiEvent::AgentEnd,
+        "agent_start" => PiEvent::AgentStart { request_id },
+        "agent_end" => PiEvent::AgentEnd { request_id },
         "message_update" => {
             if let Some(evt) = value.get("assistantMessageEvent") {
                 let delta_type = evt.get("type").and_then(|t| t.as_str()).unwrap_or("");
@@ -166,8 +573,8 @@ fn parse_rpc_event(line: &str) -> PiEvent {
                     .to_string();
 
                 match delta_type {
-                    "text_delta" => PiEvent::TextDelta(delta),
-                    "thinking_delta" => PiEvent::ThinkingDelta(delta),
+                    "text_delta" => PiEvent::TextDelta { delta, request_id },
+                    "thinking_delta" => PiEvent::ThinkingDelta { delta, request_id },
                     "toolcall_start" => {
                         let name = evt
                             .get("partial")
@@ -175,22 +582,34 @@ fn parse_rpc_event(line: &str) -> PiEvent {
                             .and_then(|n| n.as_str())
                             .unwrap_or("unknown")
                             .to_string();
-                        PiEvent::ToolStart { name }
+                        PiEvent::ToolStart { name, request_id }
                     }
-                    "toolcall_delta" => PiEvent::ToolUpdate(delta),
-                    "done" => PiEvent::AgentEnd,
+                    "toolcall_delta" => PiEvent::ToolUpdate {
+                        output: delta,
+                        request_id,
+                    },
+                    "done" => PiEvent::AgentEnd { request_id },
                     "error" => {
                         let reason = evt
                             .get("reason")
                             .and_then(|r| r.as_str())
                             .unwrap_or("unknown error")
                             .to_string();
-                        PiEvent::Error(reason)
+                        PiEvent::Error {
+                            message: reason,
+                            request_id,
+                        }
                     }
-                    _ => PiEvent::TextDelta(String::new()),
+                    _ => PiEvent::TextDelta {
+                        delta: String::new(),
+                        request_id,
+                    },
                 }
             } else {
-                PiEvent::TextDelta(String::new())
+                PiEvent::TextDelta {
+                    delta: String::new(),
+                    request_id,
+                }
             }
         }
         "tool_execution_start" => {
@@ -200,7 +619,7 @@ fn parse_rpc_event(line: &str) -> PiEvent {
                 .and_then(|n| n.as_str())
                 .unwrap_or("tool")
                 .to_string();
-            PiEvent::ToolStart { name }
+            PiEvent::ToolStart { name, request_id }
         }
         "tool_execution_update" => {
             let output = value
@@ -208,30 +627,47 @@ fn parse_rpc_event(line: &str) -> PiEvent {
                 .and_then(|o| o.as_str())
                 .unwrap_or("")
                 .to_string();
-            PiEvent::ToolUpdate(output)
+            PiEvent::ToolUpdate { output, request_id }
         }
-        "tool_execution_end" => PiEvent::ToolEnd,
+        "tool_execution_end" => PiEvent::ToolEnd { request_id },
         "error" => {
             let msg = value
                 .get("error")
                 .and_then(|e| e.as_str())
                 .unwrap_or("unknown error")
                 .to_string();
-            PiEvent::Error(msg)
+            PiEvent::Error {
+                message: msg,
+                request_id,
+            }
         }
-        _ => PiEvent::TextDelta(String::new()), // ignore unknown events
+        _ => PiEvent::TextDelta {
+            delta: String::new(),
+            request_id,
+        }, // ignore unknown events
     }
 }
 
+/// How many consecutive times `LiveSessionManager` will respawn a session
+/// whose `pi` process exits unexpectedly before giving up and surfacing a
+/// terminal error instead.
+const MAX_RESTART_ATTEMPTS: u32 = 3;
+
 /// Manages persistent live Pi RPC sessions per chat
 pub struct LiveSessionManager {
     sessions: HashMap<i64, PiRpcProcess>,
+    /// Consecutive unexpected-exit restarts attempted for each chat since
+    /// its last successful event, reset once an event is received. Bounds
+    /// `recv_event`'s auto-restart so a session that can never start stays
+    /// dead instead of respawning forever.
+    restart_attempts: HashMap<i64, u32>,
 }
 
 impl LiveSessionManager {
     pub fn new() -> Self {
         Self {
             sessions: HashMap::new(),
+            restart_attempts: HashMap::new(),
         }
     }
 
@@ -241,12 +677,14 @@ impl LiveSessionManager {
         session_path: &Path,
         workspace: &Path,
         thinking_level: ThinkingLevel,
+        use_pty: bool,
     ) -> anyhow::Result<()> {
         // Kill existing session if any
         self.stop_session(chat_id).await;
 
-        let process = PiRpcProcess::spawn(session_path, workspace, thinking_level).await?;
+        let process = PiRpcProcess::spawn(session_path, workspace, thinking_level, use_pty).await?;
         self.sessions.insert(chat_id, process);
+        self.restart_attempts.remove(&chat_id);
         Ok(())
     }
 
@@ -267,9 +705,91 @@ impl LiveSessionManager {
         process.send_steer(message).await
     }
 
+    /// Aborts the current generation for `chat_id` without killing and
+    /// respawning the session, so a "stop" button can interrupt a runaway
+    /// turn and leave the session ready for the next prompt.
+    pub async fn cancel(&mut self, chat_id: i64) -> anyhow::Result<bool> {
+        let process = self
+            .sessions
+            .get_mut(&chat_id)
+            .ok_or_else(|| anyhow::anyhow!("No live session for chat {chat_id}"))?;
+        process.cancel().await
+    }
+
+    /// Receives the next event for `chat_id`, transparently supervising the
+    /// underlying process: if its event channel closes (the reader task
+    /// saw EOF, meaning `pi` exited), this reaps the exit status, captures
+    /// the stderr tail, and attempts a bounded, backoff-limited respawn at
+    /// the same `session_path`/`workspace` before the caller ever sees a
+    /// gap. A successful respawn is reported as `PiEvent::AgentStart` so
+    /// callers can treat it like any other turn boundary; exhausting
+    /// `MAX_RESTART_ATTEMPTS` (or a respawn failure) surfaces a
+    /// `PiEvent::Error` instead and drops the session.
     pub async fn recv_event(&mut self, chat_id: i64) -> Option<PiEvent> {
         let process = self.sessions.get_mut(&chat_id)?;
-        process.recv_event().await
+        match process.recv_event().await {
+            Some(event) => {
+                self.restart_attempts.remove(&chat_id);
+                Some(event)
+            }
+            None => self.restart_session(chat_id).await,
+        }
+    }
+
+    /// Respawns the session for `chat_id` after its process died
+    /// unexpectedly. See `recv_event` for the backoff/give-up behavior.
+    async fn restart_session(&mut self, chat_id: i64) -> Option<PiEvent> {
+        let mut process = self.sessions.remove(&chat_id)?;
+        let exit_status = process.child.exit_status().await;
+        let stderr_tail = process.stderr_tail_text();
+        let diagnostic = if stderr_tail.is_empty() {
+            format!("Pi process exited unexpectedly (status: {exit_status}).")
+        } else {
+            format!(
+                "Pi process exited unexpectedly (status: {exit_status}). Last stderr:\n{stderr_tail}"
+            )
+        };
+
+        let attempts = self.restart_attempts.entry(chat_id).or_insert(0);
+        if *attempts >= MAX_RESTART_ATTEMPTS {
+            self.restart_attempts.remove(&chat_id);
+            return Some(PiEvent::Error {
+                message: format!(
+                    "{diagnostic} Gave up after {MAX_RESTART_ATTEMPTS} restart attempts."
+                ),
+                request_id: None,
+            });
+        }
+        *attempts += 1;
+        let attempt = *attempts;
+
+        // Exponential backoff: 1s, 2s, 4s, ...
+        tokio::time::sleep(Duration::from_secs(1 << (attempt - 1))).await;
+
+        match PiRpcProcess::spawn(
+            &process.session_path,
+            &process.workspace,
+            process.thinking_level,
+            process.use_pty,
+        )
+        .await
+        {
+            Ok(respawned) => {
+                self.sessions.insert(chat_id, respawned);
+                Some(PiEvent::AgentStart { request_id: None })
+            }
+            Err(e) => Some(PiEvent::Error {
+                message: format!(
+                    "{diagnostic} Restart attempt {attempt}/{MAX_RESTART_ATTEMPTS} failed: {e}"
+                ),
+                request_id: None,
+            }),
+        }
+    }
+
+    /// See `PiRpcProcess::try_recv_detected_files`.
+    pub fn try_recv_detected_files(&mut self, chat_id: i64) -> Option<Vec<DetectedFile>> {
+        self.sessions.get_mut(&chat_id)?.try_recv_detected_files()
     }
 
     pub fn is_active(&mut self, chat_id: i64) -> bool {
@@ -283,6 +803,7 @@ impl LiveSessionManager {
         if let Some(mut process) = self.sessions.remove(&chat_id) {
             process.kill().await;
         }
+        self.restart_attempts.remove(&chat_id);
     }
 
     #[allow(dead_code)]
@@ -301,13 +822,13 @@ mod tests {
     #[test]
     fn test_parse_agent_start() {
         let event = parse_rpc_event(r#"{"type":"agent_start"}"#);
-        assert!(matches!(event, PiEvent::AgentStart));
+        assert!(matches!(event, PiEvent::AgentStart { .. }));
     }
 
     #[test]
     fn test_parse_agent_end() {
         let event = parse_rpc_event(r#"{"type":"agent_end","messages":[]}"#);
-        assert!(matches!(event, PiEvent::AgentEnd));
+        assert!(matches!(event, PiEvent::AgentEnd { .. }));
     }
 
     #[test]
@@ -316,7 +837,7 @@ mod tests {
             r#"{"type":"message_update","assistantMessageEvent":{"type":"text_delta","delta":"Hello"}}"#,
         );
         match event {
-            PiEvent::TextDelta(text) => assert_eq!(text, "Hello"),
+            PiEvent::TextDelta { delta, .. } => assert_eq!(delta, "Hello"),
             _ => panic!("Expected TextDelta"),
         }
     }
@@ -325,7 +846,7 @@ mod tests {
     fn test_parse_tool_start() {
         let event = parse_rpc_event(r#"{"type":"tool_execution_start","tool":{"name":"bash"}}"#);
         match event {
-            PiEvent::ToolStart { name } => assert_eq!(name, "bash"),
+            PiEvent::ToolStart { name, .. } => assert_eq!(name, "bash"),
             _ => panic!("Expected ToolStart"),
         }
     }
@@ -334,7 +855,7 @@ mod tests {
     fn test_parse_error() {
         let event = parse_rpc_event(r#"{"type":"error","error":"something failed"}"#);
         match event {
-            PiEvent::Error(msg) => assert_eq!(msg, "something failed"),
+            PiEvent::Error { message, .. } => assert_eq!(message, "something failed"),
             _ => panic!("Expected Error"),
         }
     }
@@ -342,7 +863,15 @@ mod tests {
     #[test]
     fn test_parse_invalid_json() {
         let event = parse_rpc_event("not json");
-        assert!(matches!(event, PiEvent::Error(_)));
+        assert!(matches!(event, PiEvent::Error { .. }));
+    }
+
+    #[test]
+    fn test_parse_echoes_request_id() {
+        let event = parse_rpc_event(
+            r#"{"type":"message_update","requestId":"req-1","assistantMessageEvent":{"type":"text_delta","delta":"Hi"}}"#,
+        );
+        assert_eq!(event.request_id(), Some("req-1"));
     }
 
     #[test]
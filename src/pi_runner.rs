@@ -1,14 +1,15 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use base64::Engine;
-use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
 use tokio::time::{Duration, Instant};
 
-use crate::config::Config;
+use crate::config::{Config, ThinkingLevel};
+use crate::exec_backend::{shell_quote, ExecSession};
 
 #[derive(Debug)]
 pub struct RunResult {
@@ -36,6 +37,9 @@ pub struct ActivityUpdate {
 #[derive(Default)]
 pub struct RunPiOptions {
     pub image_paths: Vec<PathBuf>,
+    /// Overrides `config.thinking_level` for this run, e.g. with the
+    /// per-chat level set via `/think`.
+    pub thinking_level: Option<ThinkingLevel>,
 }
 
 
@@ -63,6 +67,61 @@ impl ChatLocks {
     }
 }
 
+struct WaitingGuard<'a>(&'a AtomicUsize);
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Bounds how many `run_pi_with_streaming` calls can run at once across all
+/// chats, so one host isn't overwhelmed by concurrent agent runs. Unlike
+/// `ChatLocks` (one lock per chat, so a single chat can't overlap itself),
+/// this is a single global permit pool shared by every chat.
+pub struct RunQueue {
+    semaphore: Semaphore,
+    waiting: AtomicUsize,
+}
+
+impl RunQueue {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            semaphore: Semaphore::new(max_concurrent.max(1)),
+            waiting: AtomicUsize::new(0),
+        }
+    }
+
+    /// Acquires a permit, calling `on_queued(position)` every couple of
+    /// seconds while waiting, where `position` is how many other callers
+    /// are also waiting ahead of a permit freeing up. The returned permit
+    /// releases on drop, so every exit path (success, error, early return)
+    /// frees the slot automatically.
+    pub async fn acquire<F>(&self, mut on_queued: F) -> SemaphorePermit<'_>
+    where
+        F: FnMut(usize),
+    {
+        if let Ok(permit) = self.semaphore.try_acquire() {
+            return permit;
+        }
+
+        self.waiting.fetch_add(1, Ordering::SeqCst);
+        let _waiting_guard = WaitingGuard(&self.waiting);
+
+        let mut acquire_fut = Box::pin(self.semaphore.acquire());
+        loop {
+            match tokio::time::timeout(Duration::from_secs(2), &mut acquire_fut).await {
+                Ok(Ok(permit)) => return permit,
+                Ok(Err(_)) => unreachable!("RunQueue's semaphore is never closed"),
+                Err(_) => {
+                    let position = self.waiting.load(Ordering::SeqCst).saturating_sub(1);
+                    on_queued(position);
+                }
+            }
+        }
+    }
+}
+
 fn detect_activity(line: &str) -> Option<(ActivityType, String)> {
     let trimmed = line.trim();
     if trimmed.is_empty() {
@@ -116,6 +175,208 @@ fn get_session_path(config: &Config, chat_id: i64) -> PathBuf {
     config.session_dir.join(format!("telegram-{chat_id}.jsonl"))
 }
 
+/// A structured event parsed from one line of Pi's session JSONL stream,
+/// the same file `extract_images_from_session` reads. Unlike `detect_activity`,
+/// which guesses at intent from free-text stdout, these come straight from
+/// the tool-call/tool-result shape Pi already writes to disk.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "type", content = "data")]
+pub enum SessionEvent {
+    ToolCall { name: String, args: serde_json::Value },
+    ToolResult { name: String, ok: bool },
+    AssistantText { text: String },
+    Thinking,
+    TokenUsage { input: u64, output: u64 },
+}
+
+impl SessionEvent {
+    /// Parses one JSONL line from the session file's entry shape
+    /// (`type == "message"`, `message.role`, `message.content[]`) into a
+    /// `SessionEvent`. Returns `None` for lines that parse as JSON but don't
+    /// match a known shape (e.g. session metadata entries).
+    fn from_line(line: &str) -> Option<Self> {
+        let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+
+        if entry.get("type").and_then(|t| t.as_str()) != Some("message") {
+            return None;
+        }
+        let message = entry.get("message")?;
+        let role = message.get("role").and_then(|r| r.as_str())?;
+
+        match role {
+            "assistant" => {
+                let content = message.get("content")?.as_array()?;
+                for item in content {
+                    match item.get("type").and_then(|t| t.as_str()) {
+                        Some("text") => {
+                            let text = item.get("text").and_then(|t| t.as_str())?.to_string();
+                            return Some(SessionEvent::AssistantText { text });
+                        }
+                        Some("thinking") => return Some(SessionEvent::Thinking),
+                        Some("tool_use") | Some("toolCall") => {
+                            let name = item
+                                .get("name")
+                                .and_then(|n| n.as_str())
+                                .unwrap_or("tool")
+                                .to_string();
+                            let args = item
+                                .get("input")
+                                .or_else(|| item.get("args"))
+                                .cloned()
+                                .unwrap_or(serde_json::Value::Null);
+                            return Some(SessionEvent::ToolCall { name, args });
+                        }
+                        _ => continue,
+                    }
+                }
+                None
+            }
+            "toolResult" => {
+                let name = message
+                    .get("name")
+                    .or_else(|| entry.get("toolName"))
+                    .and_then(|n| n.as_str())
+                    .unwrap_or("tool")
+                    .to_string();
+                let ok = !message
+                    .get("isError")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                Some(SessionEvent::ToolResult { name, ok })
+            }
+            _ => {
+                let usage = entry.get("usage")?;
+                let input = usage.get("input_tokens").and_then(|v| v.as_u64())?;
+                let output = usage.get("output_tokens").and_then(|v| v.as_u64())?;
+                Some(SessionEvent::TokenUsage { input, output })
+            }
+        }
+    }
+}
+
+/// Maps a tool name to a precise `ActivityType`/detail pair using the real
+/// arguments Pi recorded, instead of truncating free-text prose.
+fn activity_from_tool_call(name: &str, args: &serde_json::Value) -> (ActivityType, String) {
+    let arg_str = |key: &str| args.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    match name {
+        "read_file" | "read" => (
+            ActivityType::Reading,
+            arg_str("path").or_else(|| arg_str("file")).unwrap_or_default(),
+        ),
+        "write_file" | "edit_file" | "write" | "edit" => (
+            ActivityType::Writing,
+            arg_str("path").or_else(|| arg_str("file")).unwrap_or_default(),
+        ),
+        "bash" | "run_shell" | "shell" => (
+            ActivityType::Running,
+            arg_str("command").or_else(|| arg_str("cmd")).unwrap_or_default(),
+        ),
+        "grep" | "search" | "glob" => (
+            ActivityType::Searching,
+            arg_str("pattern").or_else(|| arg_str("query")).unwrap_or_else(|| "codebase".to_string()),
+        ),
+        _ => (ActivityType::Working, name.to_string()),
+    }
+}
+
+/// Converts a `SessionEvent` into the `ActivityUpdate` shape the bot already
+/// streams back to Telegram.
+fn session_event_to_activity(event: &SessionEvent) -> Option<(ActivityType, String)> {
+    match event {
+        SessionEvent::ToolCall { name, args } => Some(activity_from_tool_call(name, args)),
+        SessionEvent::Thinking => Some((ActivityType::Thinking, String::new())),
+        _ => None,
+    }
+}
+
+/// Tails a growing session JSONL file, tracking a line offset exactly like
+/// `get_session_line_count`/`after_line`, and yields only newly appended
+/// events on each poll.
+pub struct SessionTailer {
+    last_line: usize,
+}
+
+impl SessionTailer {
+    pub fn new() -> Self {
+        Self { last_line: 0 }
+    }
+
+    /// Reads the lines appended since the last poll. Falls back to the
+    /// regex-based `detect_activity` only for lines that aren't valid JSON.
+    pub async fn poll(
+        &mut self,
+        config: &Config,
+        chat_id: i64,
+    ) -> Vec<(ActivityType, String)> {
+        let session_path = get_session_path(config, chat_id);
+        let Ok(content) = tokio::fs::read_to_string(&session_path).await else {
+            return Vec::new();
+        };
+
+        let lines: Vec<&str> = content.trim().lines().collect();
+        if lines.len() <= self.last_line {
+            return Vec::new();
+        }
+
+        let new_lines = &lines[self.last_line..];
+        self.last_line = lines.len();
+
+        let mut updates = Vec::new();
+        for line in new_lines {
+            if let Some(event) = SessionEvent::from_line(line) {
+                if let Some(update) = session_event_to_activity(&event) {
+                    updates.push(update);
+                }
+            } else if let Some(update) = detect_activity(line) {
+                updates.push(update);
+            }
+        }
+        updates
+    }
+
+    /// Same as `poll`, but reads through an `ExecSession` so the session
+    /// file can live on a remote host.
+    pub async fn poll_via(
+        &mut self,
+        backend: &dyn ExecSession,
+        config: &Config,
+        chat_id: i64,
+    ) -> Vec<(ActivityType, String)> {
+        let session_path = get_session_path(config, chat_id);
+        let Ok(bytes) = backend.read_file(&session_path).await else {
+            return Vec::new();
+        };
+        let content = String::from_utf8_lossy(&bytes);
+
+        let lines: Vec<&str> = content.trim().lines().collect();
+        if lines.len() <= self.last_line {
+            return Vec::new();
+        }
+
+        let new_lines = &lines[self.last_line..];
+        self.last_line = lines.len();
+
+        let mut updates = Vec::new();
+        for line in new_lines {
+            if let Some(event) = SessionEvent::from_line(line) {
+                if let Some(update) = session_event_to_activity(&event) {
+                    updates.push(update);
+                }
+            } else if let Some(update) = detect_activity(line) {
+                updates.push(update);
+            }
+        }
+        updates
+    }
+}
+
+impl Default for SessionTailer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub async fn check_pi_auth() -> bool {
     match Command::new("pi")
         .arg("--version")
@@ -129,7 +390,12 @@ pub async fn check_pi_auth() -> bool {
     }
 }
 
+/// Runs Pi through `backend` — the active `ExecSession` for the chat, local
+/// or the `/host`-selected remote one — so a chat pointed at a remote host
+/// actually runs its Pi agent there instead of on this machine, same as
+/// `/shell` already does via `run_shell_via`.
 pub async fn run_pi_with_streaming<F>(
+    backend: &dyn ExecSession,
     config: &Config,
     chat_id: i64,
     prompt: &str,
@@ -152,12 +418,17 @@ where
 
     let session_path = get_session_path(config, chat_id);
 
+    let thinking_level = options
+        .as_ref()
+        .and_then(|opts| opts.thinking_level)
+        .unwrap_or(config.thinking_level);
+
     let mut args = vec![
         "--session".to_string(),
         session_path.to_string_lossy().to_string(),
         "--print".to_string(),
         "--thinking".to_string(),
-        config.thinking_level.to_string(),
+        thinking_level.to_string(),
     ];
 
     // Add image paths with @ prefix
@@ -172,15 +443,20 @@ where
     let home = dirs::home_dir().unwrap_or_default();
     let pi_agent_dir = home.join(".pi").join("agent");
 
-    let mut child = match Command::new("pi")
-        .args(&args)
-        .current_dir(workspace)
-        .env("PI_AGENT_DIR", &pi_agent_dir)
-        .stdin(std::process::Stdio::null())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-    {
+    // `spawn_command` takes one shell command line (it runs it through
+    // `bash -c` locally, or a single SSH exec channel remotely), so the
+    // args built above are quoted and joined the same way `run_shell_via`'s
+    // callers already quote paths for `ExecSession`.
+    let cmd_line = std::iter::once("pi".to_string())
+        .chain(args.iter().map(|arg| shell_quote(arg)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let env = [(
+        "PI_AGENT_DIR".to_string(),
+        pi_agent_dir.to_string_lossy().to_string(),
+    )];
+
+    let mut child = match backend.spawn_command(&cmd_line, workspace, &env).await {
         Ok(child) => child,
         Err(e) => {
             return RunResult {
@@ -190,85 +466,72 @@ where
         }
     };
 
-    let stdout = child.stdout.take().unwrap();
-    let stderr = child.stderr.take().unwrap();
-
-    let on_activity = Arc::new(on_activity);
-    let on_activity_clone = on_activity.clone();
+    let timeout_ms = config.pi_timeout_ms;
 
-    // Read stdout line-by-line
-    let stdout_handle = tokio::spawn(async move {
-        let mut reader = BufReader::new(stdout).lines();
+    // Pumps stdout/stderr into the final output, same as `run_shell_via`,
+    // while also tailing the session JSONL file Pi is writing (through the
+    // same `backend`, since it may live on a remote host) so structured
+    // tool-call/tool-result entries turn into precise `ActivityUpdate`s.
+    // Falls back to the stdout regex heuristic only for lines that aren't
+    // JSON, and a periodic "working" update so long silent tool calls still
+    // show something.
+    let run = async move {
         let mut output = String::new();
-        let mut last_activity_elapsed: u64 = 0;
-        let start = Instant::now();
-
-        while let Ok(Some(line)) = reader.next_line().await {
-            if !output.is_empty() {
-                output.push('\n');
-            }
-            output.push_str(&line);
-
-            if let Some((activity_type, detail)) = detect_activity(&line) {
-                let elapsed = start.elapsed().as_secs();
-                last_activity_elapsed = elapsed;
-                on_activity_clone(ActivityUpdate {
-                    activity_type,
-                    detail,
-                    elapsed,
-                });
-            }
-
-            let _ = last_activity_elapsed; // suppress unused warning
-        }
-
-        output
-    });
-
-    // Read stderr
-    let stderr_handle = tokio::spawn(async move {
-        let mut reader = BufReader::new(stderr).lines();
         let mut error_output = String::new();
-
-        while let Ok(Some(line)) = reader.next_line().await {
-            if !error_output.is_empty() {
-                error_output.push('\n');
+        let mut stdout_open = true;
+        let mut stderr_open = true;
+
+        let mut tailer = SessionTailer::new();
+        let mut tail_interval = tokio::time::interval(Duration::from_millis(500));
+        let mut periodic_interval = tokio::time::interval(Duration::from_secs(5));
+        periodic_interval.tick().await; // skip first immediate tick
+
+        while stdout_open || stderr_open {
+            tokio::select! {
+                line = child.stdout.recv(), if stdout_open => match line {
+                    Some(line) => {
+                        if !output.is_empty() {
+                            output.push('\n');
+                        }
+                        output.push_str(&line);
+                    }
+                    None => stdout_open = false,
+                },
+                line = child.stderr.recv(), if stderr_open => match line {
+                    Some(line) => {
+                        if !error_output.is_empty() {
+                            error_output.push('\n');
+                        }
+                        error_output.push_str(&line);
+                    }
+                    None => stderr_open = false,
+                },
+                _ = tail_interval.tick() => {
+                    for (activity_type, detail) in tailer.poll_via(backend, config, chat_id).await {
+                        on_activity(ActivityUpdate {
+                            activity_type,
+                            detail,
+                            elapsed: start.elapsed().as_secs(),
+                        });
+                    }
+                }
+                _ = periodic_interval.tick() => {
+                    on_activity(ActivityUpdate {
+                        activity_type: ActivityType::Working,
+                        detail: String::new(),
+                        elapsed: start.elapsed().as_secs(),
+                    });
+                }
             }
-            error_output.push_str(&line);
-        }
-
-        error_output
-    });
-
-    // Periodic "working" updates
-    let on_activity_periodic = on_activity.clone();
-    let timeout_ms = config.pi_timeout_ms;
-    let periodic_handle = tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(5));
-        interval.tick().await; // skip first immediate tick
-        loop {
-            interval.tick().await;
-            let elapsed = start.elapsed().as_secs();
-            on_activity_periodic(ActivityUpdate {
-                activity_type: ActivityType::Working,
-                detail: String::new(),
-                elapsed,
-            });
         }
-    });
-
-    // Wait for process with timeout
-    let result = tokio::time::timeout(Duration::from_millis(timeout_ms), child.wait()).await;
 
-    periodic_handle.abort();
-
-    let (stdout_output, stderr_output) = tokio::join!(stdout_handle, stderr_handle);
-    let stdout_output = stdout_output.unwrap_or_default();
-    let stderr_output = stderr_handle_result(stderr_output);
+        let code = child.wait().await;
+        (output, error_output, code)
+    };
 
-    match result {
-        Ok(Ok(status)) => {
-            if !status.success() && !stderr_output.is_empty() {
+    match tokio::time::timeout(Duration::from_millis(timeout_ms), run).await {
+        Ok((stdout_output, stderr_output, code)) => {
+            if code != Some(0) && !stderr_output.is_empty() {
                 RunResult {
                     output: if stdout_output.is_empty() {
                         "Error occurred".to_string()
@@ -288,25 +551,13 @@ where
                 }
             }
         }
-        Ok(Err(e)) => RunResult {
-            output: stdout_output,
-            error: Some(format!("Pi process error: {e}")),
+        Err(_) => RunResult {
+            output: String::new(),
+            error: Some("Timeout: Pi took too long".to_string()),
         },
-        Err(_) => {
-            // Timeout - kill the process
-            let _ = child.kill().await;
-            RunResult {
-                output: stdout_output,
-                error: Some("Timeout: Pi took too long".to_string()),
-            }
-        }
     }
 }
 
-fn stderr_handle_result(result: Result<String, tokio::task::JoinError>) -> String {
-    result.unwrap_or_default()
-}
-
 pub async fn get_session_line_count(config: &Config, chat_id: i64) -> usize {
     let session_path = get_session_path(config, chat_id);
     match tokio::fs::read_to_string(&session_path).await {
@@ -315,6 +566,20 @@ pub async fn get_session_line_count(config: &Config, chat_id: i64) -> usize {
     }
 }
 
+/// Same as `get_session_line_count`, but reads through an `ExecSession` so
+/// the session file can live on a remote host.
+pub async fn get_session_line_count_via(
+    backend: &dyn ExecSession,
+    config: &Config,
+    chat_id: i64,
+) -> usize {
+    let session_path = get_session_path(config, chat_id);
+    match backend.read_file(&session_path).await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).trim().lines().count(),
+        Err(_) => 0,
+    }
+}
+
 #[derive(Debug)]
 pub struct ExtractedImage {
     pub data: Vec<u8>,
@@ -327,12 +592,29 @@ pub async fn extract_images_from_session(
     after_line: usize,
 ) -> Vec<ExtractedImage> {
     let session_path = get_session_path(config, chat_id);
-    let mut images = Vec::new();
-
     let Ok(content) = tokio::fs::read_to_string(&session_path).await else {
-        return images;
+        return Vec::new();
+    };
+    extract_images_from_content(&content, after_line)
+}
+
+/// Same as `extract_images_from_session`, but reads through an `ExecSession`
+/// so the session file can live on a remote host.
+pub async fn extract_images_from_session_via(
+    backend: &dyn ExecSession,
+    config: &Config,
+    chat_id: i64,
+    after_line: usize,
+) -> Vec<ExtractedImage> {
+    let session_path = get_session_path(config, chat_id);
+    let Ok(bytes) = backend.read_file(&session_path).await else {
+        return Vec::new();
     };
+    extract_images_from_content(&String::from_utf8_lossy(&bytes), after_line)
+}
 
+fn extract_images_from_content(content: &str, after_line: usize) -> Vec<ExtractedImage> {
+    let mut images = Vec::new();
     let lines: Vec<&str> = content.trim().lines().collect();
     let new_lines = &lines[after_line.min(lines.len())..];
 
@@ -466,4 +748,143 @@ mod tests {
     fn test_chat_locks_new() {
         let _locks = ChatLocks::new();
     }
+
+    #[tokio::test]
+    async fn test_run_queue_acquire_under_limit_does_not_queue() {
+        let queue = RunQueue::new(2);
+        let mut queued = false;
+        let _permit = queue.acquire(|_| queued = true).await;
+        assert!(!queued);
+    }
+
+    #[tokio::test]
+    async fn test_run_queue_reports_queue_position_while_waiting() {
+        tokio::time::pause();
+
+        let queue = Arc::new(RunQueue::new(1));
+        let first = queue.acquire(|_| {}).await;
+
+        let queue_clone = queue.clone();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let mut saw_position = None;
+            let _second = queue_clone
+                .acquire(|position| saw_position = Some(position))
+                .await;
+            let _ = tx.send(saw_position);
+        });
+
+        // Let the waiter's 2s poll interval fire at least once before the
+        // first permit is ever released.
+        tokio::time::advance(Duration::from_secs(3)).await;
+        drop(first);
+
+        let saw_position = rx.await.unwrap();
+        assert_eq!(saw_position, Some(0));
+    }
+
+    #[test]
+    fn test_session_event_from_line_tool_call() {
+        let line = r#"{"type":"message","message":{"role":"assistant","content":[{"type":"tool_use","name":"read_file","input":{"path":"src/main.rs"}}]}}"#;
+        let event = SessionEvent::from_line(line).unwrap();
+        match event {
+            SessionEvent::ToolCall { name, args } => {
+                assert_eq!(name, "read_file");
+                assert_eq!(args.get("path").and_then(|v| v.as_str()), Some("src/main.rs"));
+            }
+            other => panic!("expected ToolCall, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_session_event_from_line_tool_result() {
+        let line = r#"{"type":"message","message":{"role":"toolResult","name":"bash","isError":false}}"#;
+        let event = SessionEvent::from_line(line).unwrap();
+        assert!(matches!(event, SessionEvent::ToolResult { ok: true, .. }));
+    }
+
+    #[test]
+    fn test_session_event_from_line_not_json() {
+        assert!(SessionEvent::from_line("Reading package.json").is_none());
+    }
+
+    #[test]
+    fn test_session_event_from_line_unknown_shape() {
+        let line = r#"{"type":"meta","sessionId":"abc"}"#;
+        assert!(SessionEvent::from_line(line).is_none());
+    }
+
+    #[test]
+    fn test_activity_from_tool_call_read() {
+        let args = serde_json::json!({"path": "foo.rs"});
+        let (t, detail) = activity_from_tool_call("read_file", &args);
+        assert_eq!(t, ActivityType::Reading);
+        assert_eq!(detail, "foo.rs");
+    }
+
+    #[test]
+    fn test_activity_from_tool_call_bash_full_command() {
+        let args = serde_json::json!({"command": "npm run a-much-longer-command-than-fifty-characters-would-allow"});
+        let (t, detail) = activity_from_tool_call("bash", &args);
+        assert_eq!(t, ActivityType::Running);
+        assert_eq!(detail, "npm run a-much-longer-command-than-fifty-characters-would-allow");
+    }
+
+    #[test]
+    fn test_activity_from_tool_call_unknown() {
+        let args = serde_json::Value::Null;
+        let (t, detail) = activity_from_tool_call("custom_tool", &args);
+        assert_eq!(t, ActivityType::Working);
+        assert_eq!(detail, "custom_tool");
+    }
+
+    #[tokio::test]
+    async fn test_session_tailer_poll_json_and_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            telegram_token: "token".into(),
+            workspace: dir.path().to_path_buf(),
+            session_dir: dir.path().to_path_buf(),
+            thinking_level: crate::config::ThinkingLevel::Low,
+            allowed_users: vec![],
+            admins: vec![],
+            rate_limit_cooldown_ms: 5000,
+            pi_timeout_ms: 1000,
+            shell_timeout_ms: 1000,
+            session_title_timeout: std::time::Duration::from_millis(1000),
+            exec_target: crate::config::ExecTarget::Local,
+            storage_backend: crate::config::StorageBackend::Memory,
+            max_concurrent_runs: 3,
+            locales_dir: std::path::PathBuf::from("locales"),
+            default_lang: "en".into(),
+            browser_idle_ms: 5 * 60 * 1000,
+            retention_hourly_slots: 24,
+            retention_daily_slots: 7,
+            retention_weekly_slots: 4,
+            retention_monthly_slots: 12,
+            cleanup_interval: std::time::Duration::from_secs(86400),
+            pi_rpc_pty: false,
+        };
+        let session_path = get_session_path(&config, 42);
+
+        let mut tailer = SessionTailer::new();
+        assert!(tailer.poll(&config, 42).await.is_empty());
+
+        tokio::fs::write(
+            &session_path,
+            r#"{"type":"message","message":{"role":"assistant","content":[{"type":"tool_use","name":"bash","input":{"command":"ls -la"}}]}}
+Running some free text
+"#,
+        )
+        .await
+        .unwrap();
+
+        let updates = tailer.poll(&config, 42).await;
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].0, ActivityType::Running);
+        assert_eq!(updates[0].1, "ls -la");
+        assert_eq!(updates[1].0, ActivityType::Running);
+
+        assert!(tailer.poll(&config, 42).await.is_empty());
+    }
 }
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{Duration, Instant};
+
+/// Program names that behave interactively (prompt for input, redraw the
+/// screen, or otherwise misbehave without a real TTY attached).
+const INTERACTIVE_PROGRAMS: &[&str] = &[
+    "bash", "sh", "zsh", "fish", "python", "python3", "irb", "node", "ssh", "top", "htop", "vim",
+    "vi", "nano", "less", "more", "mysql", "psql", "redis-cli", "sqlite3",
+];
+
+/// Returns true when the first word of `cmd` names a known interactive
+/// program, meaning it should run under a PTY rather than one-shot `bash -c`.
+pub fn is_interactive_command(cmd: &str) -> bool {
+    cmd.trim()
+        .split_whitespace()
+        .next()
+        .map(|program| INTERACTIVE_PROGRAMS.contains(&program))
+        .unwrap_or(false)
+}
+
+/// Telegram caps a single message at 4096 characters; a streamed PTY
+/// session only needs to show the most recent screen state anyway.
+const MAX_RENDERED_LENGTH: usize = 4096;
+
+/// Strips ANSI escape sequences (CSI codes, OSC sequences, and common
+/// single-character escapes) out of raw PTY output, so a terminal session
+/// renders as plain, readable text in an edited Telegram message instead of
+/// raw control codes.
+pub fn strip_ansi_codes(text: &str) -> String {
+    static ANSI_RE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+        regex::Regex::new(r"\x1b(\[[0-9;?]*[a-zA-Z]|\][^\x07\x1b]*(\x07|\x1b\\)|[()][AB012]|[=>])")
+            .unwrap()
+    });
+    ANSI_RE.replace_all(text, "").replace('\r', "")
+}
+
+/// Prepares raw PTY output for display: strips ANSI control sequences and
+/// truncates to the tail that fits in one Telegram message, prefixing an
+/// ellipsis when older output was dropped.
+pub fn render_for_telegram(raw: &str) -> String {
+    let stripped = strip_ansi_codes(raw);
+    if stripped.len() <= MAX_RENDERED_LENGTH {
+        return stripped;
+    }
+
+    let mut start = stripped.len() - MAX_RENDERED_LENGTH;
+    while start < stripped.len() && !stripped.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("\u{2026}{}", &stripped[start..])
+}
+
+/// A single PTY-backed shell session: a spawned process attached to a
+/// pseudo-terminal master, with output streamed back over a channel.
+pub struct PtyShell {
+    writer: Box<dyn Write + Send>,
+    master: Box<dyn MasterPty + Send>,
+    child: Box<dyn Child + Send + Sync>,
+    output_rx: mpsc::UnboundedReceiver<String>,
+    started: Instant,
+}
+
+impl PtyShell {
+    pub fn spawn(cmd: &str, cwd: &Path, rows: u16, cols: u16) -> anyhow::Result<Self> {
+        let pty_system = native_pty_system();
+        let pair = pty_system.openpty(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+
+        let mut builder = CommandBuilder::new("bash");
+        builder.arg("-c");
+        builder.arg(cmd);
+        builder.cwd(cwd);
+
+        let child = pair.slave.spawn_command(builder)?;
+        // Drop our handle to the slave side; the child keeps it open.
+        drop(pair.slave);
+
+        let mut reader = pair.master.try_clone_reader()?;
+        let writer = pair.master.take_writer()?;
+
+        let (output_tx, output_rx) = mpsc::unbounded_channel();
+        tokio::task::spawn_blocking(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let chunk = String::from_utf8_lossy(&buf[..n]).into_owned();
+                        if output_tx.send(chunk).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(Self {
+            writer,
+            master: pair.master,
+            child,
+            output_rx,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn write_stdin(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        self.writer.write_all(bytes)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Yields the next chunk of output as it arrives, or `None` once the
+    /// underlying PTY master has closed.
+    pub async fn read(&mut self) -> Option<String> {
+        self.output_rx.recv().await
+    }
+
+    pub fn resize(&self, rows: u16, cols: u16) -> anyhow::Result<()> {
+        self.master.resize(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })?;
+        Ok(())
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn is_alive(&mut self) -> bool {
+        self.child.try_wait().ok().flatten().is_none()
+    }
+
+    /// Kills the whole process group rooted at the PTY child, not just the
+    /// shell leader.
+    pub fn kill(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.child.process_id() {
+            unsafe {
+                libc::kill(-(pid as i32), libc::SIGKILL);
+            }
+        }
+        let _ = self.child.kill();
+    }
+}
+
+/// Per-chat PTY shell sessions, keyed the way `ChatLocks` keys executions.
+pub struct PtyShellManager {
+    shells: Mutex<HashMap<i64, Arc<Mutex<PtyShell>>>>,
+}
+
+impl PtyShellManager {
+    pub fn new() -> Self {
+        Self {
+            shells: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn start(
+        &self,
+        chat_id: i64,
+        cmd: &str,
+        cwd: &Path,
+        rows: u16,
+        cols: u16,
+    ) -> anyhow::Result<Arc<Mutex<PtyShell>>> {
+        self.stop(chat_id).await;
+        let shell = Arc::new(Mutex::new(PtyShell::spawn(cmd, cwd, rows, cols)?));
+        self.shells.lock().await.insert(chat_id, shell.clone());
+        Ok(shell)
+    }
+
+    pub async fn get(&self, chat_id: i64) -> Option<Arc<Mutex<PtyShell>>> {
+        self.shells.lock().await.get(&chat_id).cloned()
+    }
+
+    pub async fn stop(&self, chat_id: i64) {
+        if let Some(shell) = self.shells.lock().await.remove(&chat_id) {
+            shell.lock().await.kill();
+        }
+    }
+}
+
+impl Default for PtyShellManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_interactive_command_bash() {
+        assert!(is_interactive_command("bash"));
+        assert!(is_interactive_command("  vim file.txt"));
+    }
+
+    #[test]
+    fn test_is_interactive_command_false() {
+        assert!(!is_interactive_command("ls -la"));
+        assert!(!is_interactive_command("echo hello"));
+    }
+
+    #[test]
+    fn test_is_interactive_command_empty() {
+        assert!(!is_interactive_command(""));
+        assert!(!is_interactive_command("   "));
+    }
+
+    #[test]
+    fn test_strip_ansi_codes_removes_csi_sequences() {
+        let raw = "\x1b[31mhello\x1b[0m world\r\n";
+        assert_eq!(strip_ansi_codes(raw), "hello world\n");
+    }
+
+    #[test]
+    fn test_strip_ansi_codes_leaves_plain_text_untouched() {
+        assert_eq!(strip_ansi_codes("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_render_for_telegram_truncates_to_tail() {
+        let raw = "x".repeat(5000);
+        let rendered = render_for_telegram(&raw);
+        assert!(rendered.starts_with('\u{2026}'));
+        assert!(rendered.len() <= MAX_RENDERED_LENGTH + '\u{2026}'.len_utf8());
+    }
+
+    #[tokio::test]
+    async fn test_pty_shell_manager_new() {
+        let mgr = PtyShellManager::new();
+        assert!(mgr.get(123).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pty_shell_echo() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut shell = PtyShell::spawn("echo hello-pty", dir.path(), 24, 80).unwrap();
+
+        let mut collected = String::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            match tokio::time::timeout(Duration::from_millis(500), shell.read()).await {
+                Ok(Some(chunk)) => {
+                    collected.push_str(&chunk);
+                    if collected.contains("hello-pty") {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => continue,
+            }
+        }
+        assert!(collected.contains("hello-pty"));
+    }
+}
@@ -1,9 +1,8 @@
-use std::collections::HashMap;
-use tokio::time::Instant;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub struct RateLimiter {
-    entries: HashMap<i64, Instant>,
-}
+use crate::storage::{ChatState, Storage};
+
+pub struct RateLimiter;
 
 pub struct RateLimitResult {
     pub allowed: bool,
@@ -12,26 +11,50 @@ pub struct RateLimitResult {
 
 impl RateLimiter {
     pub fn new() -> Self {
-        Self {
-            entries: HashMap::new(),
-        }
+        Self
     }
 
-    pub fn check(&mut self, chat_id: i64, cooldown_ms: u64) -> RateLimitResult {
-        let now = Instant::now();
+    /// Checks and, on success, records the chat's last-request time via
+    /// `storage` rather than an in-memory `Instant` map, so the cooldown
+    /// survives a bot restart instead of resetting every time the process
+    /// starts.
+    pub async fn check(
+        &mut self,
+        storage: &dyn Storage,
+        chat_id: i64,
+        cooldown_ms: u64,
+    ) -> RateLimitResult {
+        let now_ms = wall_clock_ms();
+        let chat_state = storage.get(chat_id).await.unwrap_or_default();
 
-        let Some(last) = self.entries.get(&chat_id) else {
-            self.entries.insert(chat_id, now);
+        let Some(last_ms) = chat_state.last_request_ms else {
+            let _ = storage
+                .set(
+                    chat_id,
+                    ChatState {
+                        last_request_ms: Some(now_ms),
+                        ..chat_state
+                    },
+                )
+                .await;
             return RateLimitResult {
                 allowed: true,
                 retry_after_ms: None,
             };
         };
 
-        let elapsed_ms = now.duration_since(*last).as_millis() as u64;
+        let elapsed_ms = now_ms.saturating_sub(last_ms);
 
         if elapsed_ms >= cooldown_ms {
-            self.entries.insert(chat_id, now);
+            let _ = storage
+                .set(
+                    chat_id,
+                    ChatState {
+                        last_request_ms: Some(now_ms),
+                        ..chat_state
+                    },
+                )
+                .await;
             RateLimitResult {
                 allowed: true,
                 retry_after_ms: None,
@@ -45,59 +68,102 @@ impl RateLimiter {
     }
 }
 
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn wall_clock_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::InMemStorage;
 
     #[tokio::test]
     async fn test_first_request_allowed() {
-        tokio::time::pause();
+        let storage = InMemStorage::new();
         let mut limiter = RateLimiter::new();
-        let result = limiter.check(123, 5000);
+        let result = limiter.check(&storage, 123, 5000).await;
         assert!(result.allowed);
         assert!(result.retry_after_ms.is_none());
     }
 
     #[tokio::test]
     async fn test_second_request_within_cooldown_denied() {
-        tokio::time::pause();
+        let storage = InMemStorage::new();
         let mut limiter = RateLimiter::new();
-        limiter.check(123, 5000);
-        let result = limiter.check(123, 5000);
+        limiter.check(&storage, 123, 5000).await;
+        let result = limiter.check(&storage, 123, 5000).await;
         assert!(!result.allowed);
         assert!(result.retry_after_ms.is_some());
     }
 
     #[tokio::test]
     async fn test_request_after_cooldown_allowed() {
-        tokio::time::pause();
+        let storage = InMemStorage::new();
+        storage
+            .set(
+                123,
+                ChatState {
+                    last_request_ms: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
         let mut limiter = RateLimiter::new();
-        limiter.check(123, 5000);
-        tokio::time::advance(std::time::Duration::from_millis(5001)).await;
-        let result = limiter.check(123, 5000);
+        let result = limiter.check(&storage, 123, 5000).await;
         assert!(result.allowed);
     }
 
     #[tokio::test]
     async fn test_different_chats_independent() {
-        tokio::time::pause();
+        let storage = InMemStorage::new();
         let mut limiter = RateLimiter::new();
-        limiter.check(123, 5000);
-        let result = limiter.check(456, 5000);
+        limiter.check(&storage, 123, 5000).await;
+        let result = limiter.check(&storage, 456, 5000).await;
         assert!(result.allowed);
     }
 
     #[tokio::test]
-    async fn test_retry_after_decreases() {
-        tokio::time::pause();
+    async fn test_retry_after_reflects_remaining_cooldown() {
+        let storage = InMemStorage::new();
+        let now_ms = wall_clock_ms();
+        storage
+            .set(
+                123,
+                ChatState {
+                    last_request_ms: Some(now_ms - 2000),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
         let mut limiter = RateLimiter::new();
-        limiter.check(123, 5000);
-
-        tokio::time::advance(std::time::Duration::from_millis(2000)).await;
-        let result = limiter.check(123, 5000);
+        let result = limiter.check(&storage, 123, 5000).await;
         assert!(!result.allowed);
         let retry = result.retry_after_ms.unwrap();
         assert!(retry <= 3000);
-        assert!(retry > 2000);
+        assert!(retry > 2900);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_last_request_survives_new_limiter() {
+        // Simulates a restart: a fresh RateLimiter backed by the same
+        // storage still enforces the cooldown recorded by the old one.
+        let storage = InMemStorage::new();
+        let mut first_limiter = RateLimiter::new();
+        first_limiter.check(&storage, 123, 5000).await;
+
+        let mut second_limiter = RateLimiter::new();
+        let result = second_limiter.check(&storage, 123, 5000).await;
+        assert!(!result.allowed);
     }
 }
@@ -1,13 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::SystemTime;
 
+use time::OffsetDateTime;
+use tokio::io::AsyncBufReadExt;
 use tokio::process::Command;
 use tokio::time::Duration;
 
 use crate::config::Config;
 use crate::error::MiniClawError;
 
+/// `telegram-<chatId>-<timestamp>.jsonl`'s timestamp suffix, shared between
+/// the formatter (`format_archive_timestamp`) and the parser
+/// (`parse_archived_at`) so the two stay in sync.
+const ARCHIVE_TIMESTAMP_FORMAT: &[time::format_description::FormatItem<'_>] = time::macros::format_description!(
+    "[year]-[month]-[day]T[hour]-[minute]-[second]-[subsecond digits:3]Z"
+);
+
 pub struct SessionManager {
     active_sessions: HashMap<String, String>, // chatId -> session filename
     active_sessions_file: PathBuf,
@@ -21,6 +31,12 @@ pub struct SessionInfo {
     pub chat_id: String,
     pub path: PathBuf,
     pub modified_at: SystemTime,
+    /// When this session was archived, parsed from the `-YYYY-MM-DDTHH-MM-SS-mmmZ`
+    /// suffix `archive_session`/`switch_session` embed in the filename.
+    /// `None` for the live default `telegram-<chatId>.jsonl` file, which has
+    /// no such suffix. Unlike `modified_at`, this isn't disturbed by the
+    /// `tokio::fs::copy` `switch_session` does when restoring a session.
+    pub archived_at: Option<OffsetDateTime>,
     pub size_bytes: u64,
     pub title: Option<String>,
 }
@@ -98,7 +114,7 @@ impl SessionManager {
         let current_path = config.session_dir.join(&current_filename);
         if current_filename == default_filename
             && tokio::fs::metadata(&current_path).await.is_ok() {
-                let timestamp = chrono_like_timestamp();
+                let timestamp = format_archive_timestamp();
                 let archive_name = format!("telegram-{chat_id}-{timestamp}.jsonl");
                 let archive_path = config.session_dir.join(&archive_name);
                 tokio::fs::rename(&current_path, &archive_path).await?;
@@ -120,61 +136,26 @@ impl SessionManager {
     }
 }
 
-fn chrono_like_timestamp() -> String {
-    // Format similar to ISO but with dashes instead of colons/dots
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap_or_default();
-    let secs = now.as_secs();
-
-    // Simple UTC timestamp formatting
-    let days = secs / 86400;
-    let time_of_day = secs % 86400;
-    let hours = time_of_day / 3600;
-    let minutes = (time_of_day % 3600) / 60;
-    let seconds = time_of_day % 60;
-    let millis = now.subsec_millis();
-
-    // Calculate year/month/day from days since epoch (simplified)
-    let (year, month, day) = days_to_ymd(days);
-
-    format!("{year:04}-{month:02}-{day:02}T{hours:02}-{minutes:02}-{seconds:02}-{millis:03}Z")
-}
-
-fn days_to_ymd(days: u64) -> (u64, u64, u64) {
-    // Simplified calendar calculation
-    let mut y = 1970;
-    let mut remaining = days;
-
-    loop {
-        let days_in_year = if is_leap_year(y) { 366 } else { 365 };
-        if remaining < days_in_year {
-            break;
-        }
-        remaining -= days_in_year;
-        y += 1;
-    }
-
-    let months = if is_leap_year(y) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    let mut m = 0;
-    for (i, &days_in_month) in months.iter().enumerate() {
-        if remaining < days_in_month {
-            m = i as u64 + 1;
-            break;
-        }
-        remaining -= days_in_month;
-    }
-
-    (y, m, remaining + 1)
+/// Formats "now" as `YYYY-MM-DDTHH-MM-SS-mmmZ` (UTC, dashes instead of
+/// colons/dots so the result is filename-safe). Inverse of `parse_archived_at`.
+fn format_archive_timestamp() -> String {
+    OffsetDateTime::now_utc()
+        .format(ARCHIVE_TIMESTAMP_FORMAT)
+        .unwrap_or_default()
 }
 
-fn is_leap_year(y: u64) -> bool {
-    (y.is_multiple_of(4) && !y.is_multiple_of(100)) || y.is_multiple_of(400)
+/// Extracts the `-YYYY-MM-DDTHH-MM-SS-mmmZ` suffix embedded by
+/// `format_archive_timestamp` in `telegram-<chatId>-<timestamp>.jsonl`
+/// filenames and parses it back into an `OffsetDateTime`. Returns `None` for
+/// the live default `telegram-<chatId>.jsonl` file, which has no such suffix.
+fn parse_archived_at(filename: &str) -> Option<OffsetDateTime> {
+    static TIMESTAMP_RE: std::sync::LazyLock<regex::Regex> = std::sync::LazyLock::new(|| {
+        regex::Regex::new(r"(\d{4}-\d{2}-\d{2}T\d{2}-\d{2}-\d{2}-\d{3}Z)\.jsonl$").unwrap()
+    });
+
+    let timestamp = TIMESTAMP_RE.captures(filename)?.get(1)?.as_str();
+    let naive = time::PrimitiveDateTime::parse(timestamp, ARCHIVE_TIMESTAMP_FORMAT).ok()?;
+    Some(naive.assume_utc())
 }
 
 pub async fn list_sessions(config: &Config) -> Vec<SessionInfo> {
@@ -204,21 +185,49 @@ pub async fn list_sessions(config: &Config) -> Vec<SessionInfo> {
             .map(|m| m.as_str().to_string())
             .unwrap_or_else(|| "unknown".to_string());
 
+        let archived_at = parse_archived_at(&filename);
+
         sessions.push(SessionInfo {
             filename,
             chat_id,
             path: entry.path(),
             modified_at: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            archived_at,
             size_bytes: meta.len(),
             title: None,
         });
     }
 
-    // Sort by modified date, newest first
-    sessions.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    // Sort newest first, preferring the timestamp parsed from the filename
+    // (stable across the `tokio::fs::copy` `switch_session` does) and
+    // falling back to filesystem mtime for the live, un-archived session.
+    sessions.sort_by(|a, b| {
+        let key = |s: &SessionInfo| {
+            s.archived_at
+                .unwrap_or_else(|| OffsetDateTime::try_from(s.modified_at).unwrap_or(OffsetDateTime::UNIX_EPOCH))
+        };
+        key(b).cmp(&key(a))
+    });
     sessions
 }
 
+/// Extracts message text from a `content` JSON value shaped either as a
+/// plain string or an array whose first element has a `text` field — the
+/// two shapes a session's JSONL entries use. Shared by `get_first_user_message`,
+/// `search_sessions`, and `crate::transcript`.
+pub(crate) fn extract_message_text(content: &serde_json::Value) -> Option<String> {
+    if let Some(s) = content.as_str() {
+        Some(s.to_string())
+    } else if let Some(arr) = content.as_array() {
+        arr.first()
+            .and_then(|item| item.get("text"))
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_string())
+    } else {
+        None
+    }
+}
+
 async fn get_first_user_message(session_path: &Path) -> Option<String> {
     let content = tokio::fs::read_to_string(session_path).await.ok()?;
 
@@ -232,15 +241,7 @@ async fn get_first_user_message(session_path: &Path) -> Option<String> {
 
         if entry.get("role").and_then(|r| r.as_str()) == Some("user") {
             if let Some(content) = entry.get("content") {
-                let text = if let Some(s) = content.as_str() {
-                    s.to_string()
-                } else if let Some(arr) = content.as_array() {
-                    arr.first()
-                        .and_then(|item| item.get("text"))
-                        .and_then(|t| t.as_str())
-                        .unwrap_or("")
-                        .to_string()
-                } else {
+                let Some(text) = extract_message_text(content) else {
                     continue;
                 };
 
@@ -255,7 +256,116 @@ async fn get_first_user_message(session_path: &Path) -> Option<String> {
     None
 }
 
-pub async fn generate_session_title(session_path: &Path, timeout_ms: u64) -> String {
+/// One regex match found by `search_sessions` within a stored transcript.
+#[derive(Debug, Clone)]
+pub struct SessionMatch {
+    pub filename: String,
+    pub chat_id: String,
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Parses a JSONL entry's optional `timestamp` field (RFC 3339). Shared
+/// with `crate::transcript`.
+pub(crate) fn entry_timestamp(entry: &serde_json::Value) -> Option<OffsetDateTime> {
+    let raw = entry.get("timestamp").and_then(|t| t.as_str())?;
+    OffsetDateTime::parse(raw, &time::format_description::well_known::Rfc3339).ok()
+}
+
+/// Whether `entry` should be considered part of the `[since, until]` window.
+/// Entries without a `timestamp` field are never excluded, since there's
+/// nothing to compare against.
+fn within_window(entry: &serde_json::Value, since: Option<SystemTime>, until: Option<SystemTime>) -> bool {
+    if since.is_none() && until.is_none() {
+        return true;
+    }
+    let Some(entry_time) = entry_timestamp(entry) else {
+        return true;
+    };
+    if let Some(since) = since {
+        if entry_time < OffsetDateTime::try_from(since).unwrap_or(OffsetDateTime::UNIX_EPOCH) {
+            return false;
+        }
+    }
+    if let Some(until) = until {
+        if entry_time > OffsetDateTime::try_from(until).unwrap_or_else(|_| OffsetDateTime::now_utc()) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Greps every stored session transcript for `query`, optionally restricted
+/// to messages timestamped within `[since, until]`. Each `.jsonl` file is
+/// streamed line-by-line rather than loaded whole, so this scales to
+/// archives far larger than `get_first_user_message`'s single-file reads
+/// need to handle. Stops early once `max_results` matches are found.
+pub async fn search_sessions(
+    config: &Config,
+    query: &regex::Regex,
+    since: Option<SystemTime>,
+    until: Option<SystemTime>,
+    max_results: usize,
+) -> Vec<SessionMatch> {
+    let mut matches = Vec::new();
+
+    for session in list_sessions(config).await {
+        if matches.len() >= max_results {
+            break;
+        }
+
+        // A file that hasn't been touched since before the window opened
+        // can't contain anything newer than `since`; skip reading it at all.
+        if let Some(since) = since {
+            if session.modified_at < since {
+                continue;
+            }
+        }
+
+        let Ok(file) = tokio::fs::File::open(&session.path).await else {
+            continue;
+        };
+        let mut lines = tokio::io::BufReader::new(file).lines();
+        let mut line_number = 0usize;
+
+        while let Ok(Some(raw_line)) = lines.next_line().await {
+            line_number += 1;
+            if matches.len() >= max_results {
+                break;
+            }
+
+            let line = raw_line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+                continue;
+            };
+
+            if !within_window(&entry, since, until) {
+                continue;
+            }
+
+            let Some(text) = entry.get("content").and_then(extract_message_text) else {
+                continue;
+            };
+
+            if query.is_match(&text) {
+                matches.push(SessionMatch {
+                    filename: session.filename.clone(),
+                    chat_id: session.chat_id.clone(),
+                    line_number,
+                    line: line.to_string(),
+                });
+            }
+        }
+    }
+
+    matches
+}
+
+pub async fn generate_session_title(session_path: &Path, timeout: Duration) -> String {
     let first_message = match get_first_user_message(session_path).await {
         Some(msg) => msg,
         None => return "Empty session".to_string(),
@@ -267,7 +377,7 @@ pub async fn generate_session_title(session_path: &Path, timeout_ms: u64) -> Str
     );
 
     let result = tokio::time::timeout(
-        Duration::from_millis(timeout_ms),
+        timeout,
         async {
             let output = Command::new("pi")
                 .args(["--print", "--no-session", &prompt])
@@ -315,7 +425,7 @@ pub async fn archive_session(config: &Config, chat_id: i64) -> Option<String> {
         return None;
     }
 
-    let timestamp = chrono_like_timestamp();
+    let timestamp = format_archive_timestamp();
     let archive_name = format!("telegram-{chat_id}-{timestamp}.jsonl");
     let archive_path = config.session_dir.join(&archive_name);
 
@@ -328,7 +438,116 @@ pub async fn delete_session(session_path: &Path) -> Result<(), MiniClawError> {
     Ok(())
 }
 
-pub async fn cleanup_old_sessions(config: &Config, keep_count: usize) -> usize {
+/// Tolerance applied when assigning a session to a time bucket, so an
+/// archive made a few minutes off the exact hour/day/week boundary still
+/// lands in the bucket a user would expect it to.
+const BUCKET_EPSILON_SECS: u64 = 1800;
+
+fn epoch_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// The timestamp retention bucketing should treat as "when this session
+/// happened": `archived_at` when the filename has one (stable across the
+/// `tokio::fs::copy` `switch_session` does, see `SessionInfo::archived_at`),
+/// falling back to filesystem `modified_at` only for the live, not-yet-
+/// archived file. Mirrors the sort key `list_sessions` already uses.
+fn retention_epoch_secs(session: &SessionInfo) -> u64 {
+    match session.archived_at {
+        Some(dt) => dt.unix_timestamp().max(0) as u64,
+        None => epoch_secs(session.modified_at),
+    }
+}
+
+/// Fixed-width bucket index (hour/day/week) for a given timestamp.
+fn fixed_bucket(secs: u64, bucket_secs: u64) -> u64 {
+    (secs + BUCKET_EPSILON_SECS) / bucket_secs
+}
+
+/// Calendar-month bucket index (`year * 12 + month`), since months vary in
+/// length and can't be bucketed by a fixed width.
+fn month_bucket(secs: u64) -> u64 {
+    let adjusted = secs + BUCKET_EPSILON_SECS;
+    let dt = OffsetDateTime::from_unix_timestamp(adjusted as i64).unwrap_or(OffsetDateTime::UNIX_EPOCH);
+    dt.year() as u64 * 12 + (u8::from(dt.month()) as u64 - 1)
+}
+
+/// For one retention tier, keeps the newest session in each of the most
+/// recent `slots` buckets (as computed by `bucket_of`) and discards the
+/// rest. Sessions outside the configured number of slots entirely (too old
+/// to fall in any kept bucket) are dropped too.
+fn keep_newest_per_bucket<'a>(
+    sessions: &'a [SessionInfo],
+    slots: usize,
+    now: u64,
+    bucket_of: impl Fn(u64) -> u64,
+) -> HashSet<&'a Path> {
+    if slots == 0 {
+        return HashSet::new();
+    }
+
+    let current_bucket = bucket_of(now);
+    let mut newest_per_bucket: HashMap<u64, &SessionInfo> = HashMap::new();
+    for session in sessions {
+        let secs = retention_epoch_secs(session);
+        let bucket = bucket_of(secs);
+        if current_bucket.saturating_sub(bucket) >= slots as u64 {
+            continue;
+        }
+        newest_per_bucket
+            .entry(bucket)
+            .and_modify(|kept| {
+                if secs > retention_epoch_secs(kept) {
+                    *kept = session;
+                }
+            })
+            .or_insert(session);
+    }
+
+    newest_per_bucket.values().map(|s| s.path.as_path()).collect()
+}
+
+/// Which of a chat's archived sessions survive the slotted retention
+/// scheme: the newest session in each of the last N hourly/daily/weekly/
+/// monthly buckets (configured via `Config::retention_*_slots`), unioned
+/// across tiers. Everything else is prunable.
+fn sessions_to_keep<'a>(sessions: &'a [SessionInfo], config: &Config) -> HashSet<&'a Path> {
+    let now = epoch_secs(SystemTime::now());
+
+    let mut keep = keep_newest_per_bucket(sessions, config.retention_hourly_slots, now, |secs| {
+        fixed_bucket(secs, 3600)
+    });
+    keep.extend(keep_newest_per_bucket(
+        sessions,
+        config.retention_daily_slots,
+        now,
+        |secs| fixed_bucket(secs, 86_400),
+    ));
+    keep.extend(keep_newest_per_bucket(
+        sessions,
+        config.retention_weekly_slots,
+        now,
+        |secs| fixed_bucket(secs, 7 * 86_400),
+    ));
+    keep.extend(keep_newest_per_bucket(
+        sessions,
+        config.retention_monthly_slots,
+        now,
+        month_bucket,
+    ));
+
+    keep
+}
+
+/// Replaces a flat "keep the N newest" cutoff with a slotted backup scheme:
+/// within each chat, keeps the single newest archive in each of the most
+/// recent hourly/daily/weekly/monthly buckets (see `Config::retention_*_slots`)
+/// and deletes anything not claimed by at least one slot. This keeps recent
+/// detail alongside a sparse long-term history instead of losing everything
+/// past a flat cutoff.
+pub async fn cleanup_old_sessions(config: &Config) -> usize {
     let sessions = list_sessions(config).await;
 
     // Group by chat ID
@@ -343,8 +562,11 @@ pub async fn cleanup_old_sessions(config: &Config, keep_count: usize) -> usize {
     let mut deleted_count = 0;
 
     for (_, chat_sessions) in by_chat_id {
-        // Already sorted newest first
-        for session in chat_sessions.iter().skip(keep_count) {
+        let keep = sessions_to_keep(&chat_sessions, config);
+        for session in &chat_sessions {
+            if keep.contains(session.path.as_path()) {
+                continue;
+            }
             if delete_session(&session.path).await.is_ok() {
                 deleted_count += 1;
             }
@@ -354,6 +576,18 @@ pub async fn cleanup_old_sessions(config: &Config, keep_count: usize) -> usize {
     deleted_count
 }
 
+/// Periodically runs `cleanup_old_sessions` every `config.cleanup_interval`,
+/// the way `browser_pool::spawn_reaper` sweeps idle browser sessions.
+pub fn spawn_session_cleanup(config: Arc<Config>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.cleanup_interval);
+        loop {
+            interval.tick().await;
+            cleanup_old_sessions(&config).await;
+        }
+    });
+}
+
 pub fn format_session_age(time: SystemTime) -> String {
     let diff = SystemTime::now()
         .duration_since(time)
@@ -373,14 +607,8 @@ pub fn format_session_age(time: SystemTime) -> String {
     } else if days < 7 {
         format!("{days}d ago")
     } else {
-        // Simple date format
-        let (year, month, day) = days_to_ymd(
-            time.duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-                / 86400,
-        );
-        format!("{month}/{day}/{year}")
+        let dt = OffsetDateTime::try_from(time).unwrap_or(OffsetDateTime::UNIX_EPOCH);
+        format!("{}/{}/{}", u8::from(dt.month()), dt.day(), dt.year())
     }
 }
 
@@ -453,8 +681,8 @@ mod tests {
     }
 
     #[test]
-    fn test_chrono_like_timestamp_format() {
-        let ts = chrono_like_timestamp();
+    fn test_format_archive_timestamp_format() {
+        let ts = format_archive_timestamp();
         // Should match pattern: YYYY-MM-DDTHH-MM-SS-mmmZ
         assert!(ts.ends_with('Z'));
         assert!(ts.contains('T'));
@@ -462,17 +690,201 @@ mod tests {
     }
 
     #[test]
-    fn test_days_to_ymd() {
-        // 2025-01-01 is day 20089 since epoch
-        let (y, m, d) = days_to_ymd(0);
-        assert_eq!((y, m, d), (1970, 1, 1));
+    fn test_parse_archived_at_roundtrip() {
+        let ts = format_archive_timestamp();
+        let filename = format!("telegram-123-{ts}.jsonl");
+        assert!(parse_archived_at(&filename).is_some());
+    }
+
+    #[test]
+    fn test_parse_archived_at_negative_chat_id() {
+        let ts = format_archive_timestamp();
+        let filename = format!("telegram--456-{ts}.jsonl");
+        assert!(parse_archived_at(&filename).is_some());
+    }
+
+    #[test]
+    fn test_parse_archived_at_none_for_live_session() {
+        assert_eq!(parse_archived_at("telegram-123.jsonl"), None);
+    }
+
+    fn session_at(name: &str, secs_ago: u64) -> SessionInfo {
+        SessionInfo {
+            filename: name.to_string(),
+            chat_id: "1".to_string(),
+            path: PathBuf::from(name),
+            modified_at: SystemTime::now() - std::time::Duration::from_secs(secs_ago),
+            archived_at: None,
+            size_bytes: 0,
+            title: None,
+        }
+    }
+
+    fn test_config(
+        hourly: usize,
+        daily: usize,
+        weekly: usize,
+        monthly: usize,
+    ) -> Config {
+        Config {
+            telegram_token: "token".into(),
+            workspace: PathBuf::from("/tmp"),
+            session_dir: PathBuf::from("/tmp"),
+            thinking_level: crate::config::ThinkingLevel::Low,
+            allowed_users: vec![],
+            admins: vec![],
+            rate_limit_cooldown_ms: 5000,
+            pi_timeout_ms: 1000,
+            shell_timeout_ms: 1000,
+            session_title_timeout: Duration::from_millis(1000),
+            exec_target: crate::config::ExecTarget::Local,
+            storage_backend: crate::config::StorageBackend::Memory,
+            max_concurrent_runs: 3,
+            locales_dir: PathBuf::from("locales"),
+            default_lang: "en".into(),
+            browser_idle_ms: 5 * 60 * 1000,
+            retention_hourly_slots: hourly,
+            retention_daily_slots: daily,
+            retention_weekly_slots: weekly,
+            retention_monthly_slots: monthly,
+            cleanup_interval: Duration::from_secs(86400),
+            pi_rpc_pty: false,
+        }
+    }
+
+    #[test]
+    fn test_keeps_newest_of_two_sessions_in_same_hour() {
+        let sessions = vec![session_at("old", 300), session_at("new", 60)];
+        let config = test_config(1, 0, 0, 0);
+        let keep = sessions_to_keep(&sessions, &config);
+        assert_eq!(keep.len(), 1);
+        assert!(keep.contains(Path::new("new")));
+    }
+
+    #[test]
+    fn test_prunes_sessions_outside_any_slot() {
+        let sessions = vec![session_at("recent", 60), session_at("ancient", 400 * 86_400)];
+        let config = test_config(1, 1, 1, 1);
+        let keep = sessions_to_keep(&sessions, &config);
+        assert!(keep.contains(Path::new("recent")));
+        assert!(!keep.contains(Path::new("ancient")));
+    }
+
+    #[test]
+    fn test_disabled_tier_keeps_nothing_from_that_tier() {
+        let sessions = vec![session_at("only", 60)];
+        let config = test_config(0, 0, 0, 0);
+        let keep = sessions_to_keep(&sessions, &config);
+        assert!(keep.is_empty());
+    }
+
+    #[test]
+    fn test_keep_newest_per_bucket_prefers_archived_at_over_modified_at() {
+        // `modified_at` says "new" is newest, but its `archived_at` (set by a
+        // `switch_session` restore that bumped mtime without changing when it
+        // was actually archived) says it's actually older than "old" — the
+        // bucket pick should follow `archived_at`, not the misleading mtime.
+        let mut new = session_at("new", 60);
+        new.archived_at = Some(OffsetDateTime::now_utc() - time::Duration::seconds(600));
+        let mut old = session_at("old", 300);
+        old.archived_at = Some(OffsetDateTime::now_utc() - time::Duration::seconds(60));
+
+        let sessions = vec![new, old];
+        let config = test_config(1, 0, 0, 0);
+        let keep = sessions_to_keep(&sessions, &config);
+        assert_eq!(keep.len(), 1);
+        assert!(keep.contains(Path::new("old")));
+    }
+
+    #[test]
+    fn test_month_bucket_distinguishes_adjacent_months() {
+        // 2025-01-01 and 2025-02-01, in seconds since epoch.
+        let jan = 1_735_689_600; // 2025-01-01T00:00:00Z
+        let feb = 1_738_368_000; // 2025-02-01T00:00:00Z
+        assert_ne!(month_bucket(jan), month_bucket(feb));
+    }
+
+    #[test]
+    fn test_extract_message_text_string() {
+        let content = serde_json::json!("hello world");
+        assert_eq!(extract_message_text(&content), Some("hello world".to_string()));
+    }
+
+    #[test]
+    fn test_extract_message_text_array() {
+        let content = serde_json::json!([{"type": "text", "text": "hello array"}]);
+        assert_eq!(extract_message_text(&content), Some("hello array".to_string()));
     }
 
     #[test]
-    fn test_is_leap_year() {
-        assert!(is_leap_year(2000));
-        assert!(is_leap_year(2024));
-        assert!(!is_leap_year(1900));
-        assert!(!is_leap_year(2023));
+    fn test_extract_message_text_unsupported_shape() {
+        let content = serde_json::json!(42);
+        assert_eq!(extract_message_text(&content), None);
+    }
+
+    #[test]
+    fn test_within_window_no_timestamp_is_included() {
+        let entry = serde_json::json!({"role": "user", "content": "hi"});
+        assert!(within_window(&entry, Some(SystemTime::now()), None));
+    }
+
+    #[test]
+    fn test_within_window_respects_since_and_until() {
+        let entry = serde_json::json!({"role": "user", "content": "hi", "timestamp": "2025-06-15T00:00:00Z"});
+        let since: SystemTime = OffsetDateTime::parse("2025-06-01T00:00:00Z", &time::format_description::well_known::Rfc3339)
+            .unwrap()
+            .into();
+        let until: SystemTime = OffsetDateTime::parse("2025-06-30T00:00:00Z", &time::format_description::well_known::Rfc3339)
+            .unwrap()
+            .into();
+        assert!(within_window(&entry, Some(since), Some(until)));
+
+        let too_early: SystemTime = OffsetDateTime::parse("2025-07-01T00:00:00Z", &time::format_description::well_known::Rfc3339)
+            .unwrap()
+            .into();
+        assert!(!within_window(&entry, Some(too_early), None));
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions_finds_match_within_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = test_config(1, 1, 1, 1);
+        config.session_dir = dir.path().to_path_buf();
+
+        let lines = [
+            r#"{"role":"user","content":"please search for needle here","timestamp":"2025-06-15T00:00:00Z"}"#,
+            r#"{"role":"user","content":"needle outside the window","timestamp":"2020-01-01T00:00:00Z"}"#,
+            r#"{"role":"assistant","content":"no match here"}"#,
+        ]
+        .join("\n");
+        tokio::fs::write(dir.path().join("telegram-1.jsonl"), lines)
+            .await
+            .unwrap();
+
+        let since: SystemTime = OffsetDateTime::parse("2025-01-01T00:00:00Z", &time::format_description::well_known::Rfc3339)
+            .unwrap()
+            .into();
+        let query = regex::Regex::new("needle").unwrap();
+        let matches = search_sessions(&config, &query, Some(since), None, 10).await;
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].chat_id, "1");
+        assert_eq!(matches[0].line_number, 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions_respects_max_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = test_config(1, 1, 1, 1);
+        config.session_dir = dir.path().to_path_buf();
+
+        let lines = vec![r#"{"role":"user","content":"needle"}"#; 5].join("\n");
+        tokio::fs::write(dir.path().join("telegram-1.jsonl"), lines)
+            .await
+            .unwrap();
+
+        let query = regex::Regex::new("needle").unwrap();
+        let matches = search_sessions(&config, &query, None, None, 2).await;
+        assert_eq!(matches.len(), 2);
     }
 }
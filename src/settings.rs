@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::MiniClawError;
+
+/// Per-chat tuning knobs for behaviors that used to be fixed constants in
+/// the handlers. Defaults match what those constants used to be, so an
+/// untouched chat behaves exactly as before.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ChatSettings {
+    /// Whether live mode edits the status message with a running text
+    /// preview as the response streams in. Turning this off makes live
+    /// mode quieter: the status message just says "Working...".
+    pub stream_preview: bool,
+    /// Minimum time between status-message edits, in milliseconds.
+    pub status_throttle_ms: u64,
+    /// How many characters of the accumulated response to show in the
+    /// live-mode status preview.
+    pub preview_chars: usize,
+    /// Whether to auto-send tool-generated images and detected workspace
+    /// files after a run completes.
+    pub send_tool_images: bool,
+}
+
+impl Default for ChatSettings {
+    fn default() -> Self {
+        Self {
+            stream_preview: true,
+            status_throttle_ms: 2_000,
+            preview_chars: 100,
+            send_tool_images: true,
+        }
+    }
+}
+
+/// Stores per-chat `ChatSettings`, handing out defaults on first access via
+/// the entry API (mirroring `ChatLocks`) and persisting to disk so
+/// preferences survive a restart.
+pub struct ChatSettingsManager {
+    settings: HashMap<i64, ChatSettings>,
+    settings_file: PathBuf,
+    loaded: bool,
+}
+
+impl ChatSettingsManager {
+    pub fn new() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+        Self {
+            settings: HashMap::new(),
+            settings_file: home.join(".mini-claw").join("chat-settings.json"),
+            loaded: false,
+        }
+    }
+
+    async fn load(&mut self) {
+        if self.loaded {
+            return;
+        }
+        if let Ok(data) = tokio::fs::read_to_string(&self.settings_file).await {
+            if let Ok(parsed) = serde_json::from_str(&data) {
+                self.settings = parsed;
+            }
+        }
+        self.loaded = true;
+    }
+
+    async fn save(&self) -> Result<(), MiniClawError> {
+        if let Some(dir) = self.settings_file.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let json = serde_json::to_string_pretty(&self.settings)?;
+        tokio::fs::write(&self.settings_file, json).await?;
+        Ok(())
+    }
+
+    pub async fn get(&mut self, chat_id: i64) -> ChatSettings {
+        self.load().await;
+        self.settings.entry(chat_id).or_default().clone()
+    }
+
+    /// Applies `f` to the chat's settings (creating defaults first if
+    /// there's none yet) and persists the result.
+    pub async fn update<F>(&mut self, chat_id: i64, f: F) -> Result<ChatSettings, MiniClawError>
+    where
+        F: FnOnce(&mut ChatSettings),
+    {
+        self.load().await;
+        let entry = self.settings.entry(chat_id).or_default();
+        f(entry);
+        let updated = entry.clone();
+        self.save().await?;
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_settings_match_old_constants() {
+        let settings = ChatSettings::default();
+        assert!(settings.stream_preview);
+        assert_eq!(settings.status_throttle_ms, 2_000);
+        assert_eq!(settings.preview_chars, 100);
+        assert!(settings.send_tool_images);
+    }
+
+    #[tokio::test]
+    async fn test_get_returns_defaults_on_first_access() {
+        let mut mgr = ChatSettingsManager::new();
+        mgr.settings_file = std::env::temp_dir().join(format!(
+            "mini-claw-test-settings-{}.json",
+            std::process::id()
+        ));
+        mgr.loaded = true; // skip disk I/O entirely for this test
+
+        let settings = mgr.get(42).await;
+        assert_eq!(settings, ChatSettings::default());
+    }
+
+    #[tokio::test]
+    async fn test_update_persists_and_roundtrips() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mgr = ChatSettingsManager::new();
+        mgr.settings_file = dir.path().join("chat-settings.json");
+        mgr.loaded = true;
+
+        mgr.update(42, |s| s.preview_chars = 50).await.unwrap();
+
+        let mut reloaded = ChatSettingsManager::new();
+        reloaded.settings_file = mgr.settings_file.clone();
+        let settings = reloaded.get(42).await;
+        assert_eq!(settings.preview_chars, 50);
+    }
+}
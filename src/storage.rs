@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::error::MiniClawError;
+
+/// Per-chat state that needs to survive a bot restart: which workspace a
+/// chat is pointed at, how far into its session JSONL we've already
+/// delivered activity/images for, and whether it was mid-live-session when
+/// the process stopped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ChatState {
+    pub workspace: Option<PathBuf>,
+    pub last_session_line_count: usize,
+    pub live_mode: bool,
+    /// BCP-47 language tag (e.g. "en", "es") the chat has chosen via
+    /// `/lang`, parsed into a `LanguageIdentifier` by the i18n layer.
+    pub lang: Option<String>,
+    /// Wall-clock time (milliseconds since the Unix epoch) of the chat's
+    /// last allowed request, used by `RateLimiter`. A wall-clock timestamp
+    /// rather than a `tokio::time::Instant` so the cooldown survives a
+    /// restart instead of resetting every time the process starts.
+    pub last_request_ms: Option<u64>,
+    /// Readable text from the last `/read`, `/extract`, or `/snapshot`
+    /// result, staged so the chat's next message to Pi can be prefixed with
+    /// it as context. Cleared as soon as it's consumed.
+    pub pending_web_context: Option<String>,
+    /// `ThinkingLevel` (as its `Display` string: "low"/"medium"/"high") the
+    /// chat has chosen via `/think`, overriding `config.thinking_level`.
+    pub thinking_level: Option<String>,
+}
+
+/// Persists `ChatState` keyed by `chat_id`, mirroring teloxide's dialogue
+/// storage design so the backend used by `AppState` can be swapped (e.g.
+/// in-memory for tests, SQLite in production) without touching callers.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn get(&self, chat_id: i64) -> Result<ChatState, MiniClawError>;
+    async fn set(&self, chat_id: i64, state: ChatState) -> Result<(), MiniClawError>;
+}
+
+/// In-memory backend. Fast, but every chat's state resets on restart —
+/// the default when no persistent backend is configured.
+pub struct InMemStorage {
+    state: Mutex<HashMap<i64, ChatState>>,
+}
+
+impl InMemStorage {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for InMemStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Storage for InMemStorage {
+    async fn get(&self, chat_id: i64) -> Result<ChatState, MiniClawError> {
+        Ok(self
+            .state
+            .lock()
+            .await
+            .get(&chat_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn set(&self, chat_id: i64, state: ChatState) -> Result<(), MiniClawError> {
+        self.state.lock().await.insert(chat_id, state);
+        Ok(())
+    }
+}
+
+/// SQLite-backed storage, so per-chat state survives a bot restart. Each
+/// call opens and closes its own connection inside `spawn_blocking`, since
+/// `rusqlite` is synchronous — the same way `pty_shell.rs` pushes blocking
+/// OS work off the async runtime.
+pub struct SqliteStorage {
+    path: PathBuf,
+}
+
+impl SqliteStorage {
+    pub async fn new(path: PathBuf) -> Result<Self, MiniClawError> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let init_path = path.clone();
+        tokio::task::spawn_blocking(move || -> Result<(), MiniClawError> {
+            let conn = rusqlite::Connection::open(&init_path)
+                .map_err(|e| MiniClawError::Session(format!("failed to open storage db: {e}")))?;
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS chat_state (
+                    chat_id INTEGER PRIMARY KEY,
+                    workspace TEXT,
+                    last_session_line_count INTEGER NOT NULL,
+                    live_mode INTEGER NOT NULL,
+                    lang TEXT,
+                    last_request_ms INTEGER,
+                    pending_web_context TEXT,
+                    thinking_level TEXT
+                )",
+                [],
+            )
+            .map_err(|e| MiniClawError::Session(format!("failed to init storage db: {e}")))?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| MiniClawError::Session(format!("storage init task panicked: {e}")))??;
+
+        Ok(Self { path })
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn get(&self, chat_id: i64) -> Result<ChatState, MiniClawError> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || -> Result<ChatState, MiniClawError> {
+            let conn = rusqlite::Connection::open(&path)
+                .map_err(|e| MiniClawError::Session(format!("failed to open storage db: {e}")))?;
+            let result = conn.query_row(
+                "SELECT workspace, last_session_line_count, live_mode, lang, last_request_ms, pending_web_context, thinking_level FROM chat_state WHERE chat_id = ?1",
+                [chat_id],
+                |row| {
+                    let workspace: Option<String> = row.get(0)?;
+                    let last_session_line_count: i64 = row.get(1)?;
+                    let live_mode: i64 = row.get(2)?;
+                    let lang: Option<String> = row.get(3)?;
+                    let last_request_ms: Option<i64> = row.get(4)?;
+                    let pending_web_context: Option<String> = row.get(5)?;
+                    let thinking_level: Option<String> = row.get(6)?;
+                    Ok(ChatState {
+                        workspace: workspace.map(PathBuf::from),
+                        last_session_line_count: last_session_line_count as usize,
+                        live_mode: live_mode != 0,
+                        lang,
+                        last_request_ms: last_request_ms.map(|v| v as u64),
+                        pending_web_context,
+                        thinking_level,
+                    })
+                },
+            );
+            match result {
+                Ok(state) => Ok(state),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(ChatState::default()),
+                Err(e) => Err(MiniClawError::Session(format!("storage read failed: {e}"))),
+            }
+        })
+        .await
+        .map_err(|e| MiniClawError::Session(format!("storage task panicked: {e}")))?
+    }
+
+    async fn set(&self, chat_id: i64, state: ChatState) -> Result<(), MiniClawError> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || -> Result<(), MiniClawError> {
+            let conn = rusqlite::Connection::open(&path)
+                .map_err(|e| MiniClawError::Session(format!("failed to open storage db: {e}")))?;
+            conn.execute(
+                "INSERT INTO chat_state (chat_id, workspace, last_session_line_count, live_mode, lang, last_request_ms, pending_web_context, thinking_level)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                 ON CONFLICT(chat_id) DO UPDATE SET
+                    workspace = excluded.workspace,
+                    last_session_line_count = excluded.last_session_line_count,
+                    live_mode = excluded.live_mode,
+                    lang = excluded.lang,
+                    last_request_ms = excluded.last_request_ms,
+                    pending_web_context = excluded.pending_web_context,
+                    thinking_level = excluded.thinking_level",
+                rusqlite::params![
+                    chat_id,
+                    state
+                        .workspace
+                        .as_ref()
+                        .map(|p| p.to_string_lossy().to_string()),
+                    state.last_session_line_count as i64,
+                    state.live_mode as i64,
+                    state.lang,
+                    state.last_request_ms.map(|v| v as i64),
+                    state.pending_web_context,
+                    state.thinking_level,
+                ],
+            )
+            .map_err(|e| MiniClawError::Session(format!("storage write failed: {e}")))?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| MiniClawError::Session(format!("storage task panicked: {e}")))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_mem_storage_missing_chat_returns_default() {
+        let storage = InMemStorage::new();
+        let state = storage.get(123).await.unwrap();
+        assert_eq!(state, ChatState::default());
+    }
+
+    #[tokio::test]
+    async fn test_in_mem_storage_roundtrip() {
+        let storage = InMemStorage::new();
+        let state = ChatState {
+            workspace: Some(PathBuf::from("/tmp/ws")),
+            last_session_line_count: 42,
+            live_mode: true,
+            lang: Some("es".into()),
+            last_request_ms: Some(1_700_000_000_000),
+            pending_web_context: None,
+            thinking_level: None,
+        };
+        storage.set(123, state.clone()).await.unwrap();
+        assert_eq!(storage.get(123).await.unwrap(), state);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_storage_missing_chat_returns_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SqliteStorage::new(dir.path().join("state.db")).await.unwrap();
+        let state = storage.get(123).await.unwrap();
+        assert_eq!(state, ChatState::default());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_storage_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SqliteStorage::new(dir.path().join("state.db")).await.unwrap();
+        let state = ChatState {
+            workspace: Some(PathBuf::from("/home/user/project")),
+            last_session_line_count: 17,
+            live_mode: false,
+            lang: None,
+            last_request_ms: Some(1_700_000_000_000),
+            pending_web_context: None,
+            thinking_level: None,
+        };
+        storage.set(77, state.clone()).await.unwrap();
+        assert_eq!(storage.get(77).await.unwrap(), state);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_storage_set_overwrites_existing_row() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SqliteStorage::new(dir.path().join("state.db")).await.unwrap();
+        storage
+            .set(
+                1,
+                ChatState {
+                    workspace: None,
+                    last_session_line_count: 1,
+                    live_mode: true,
+                    lang: None,
+                    last_request_ms: None,
+                    pending_web_context: None,
+                    thinking_level: None,
+                },
+            )
+            .await
+            .unwrap();
+        storage
+            .set(
+                1,
+                ChatState {
+                    workspace: None,
+                    last_session_line_count: 2,
+                    live_mode: false,
+                    lang: None,
+                    last_request_ms: None,
+                    pending_web_context: None,
+                    thinking_level: None,
+                },
+            )
+            .await
+            .unwrap();
+        let state = storage.get(1).await.unwrap();
+        assert_eq!(state.last_session_line_count, 2);
+        assert!(!state.live_mode);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_storage_persists_lang() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = SqliteStorage::new(dir.path().join("state.db")).await.unwrap();
+        storage
+            .set(
+                5,
+                ChatState {
+                    workspace: None,
+                    last_session_line_count: 0,
+                    live_mode: false,
+                    lang: Some("es".into()),
+                    last_request_ms: None,
+                    pending_web_context: None,
+                    thinking_level: None,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(storage.get(5).await.unwrap().lang, Some("es".into()));
+    }
+}
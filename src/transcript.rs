@@ -0,0 +1,315 @@
+use std::path::Path;
+
+use time::OffsetDateTime;
+
+use crate::error::MiniClawError;
+use crate::sessions::{entry_timestamp, extract_message_text};
+
+/// Who said an entry, normalized from whatever string a session's `role`
+/// field holds. `Other` covers roles besides `user`/`assistant` (e.g.
+/// `system`) that show up in some session schemas.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptRole {
+    User,
+    Assistant,
+    Other(String),
+}
+
+impl TranscriptRole {
+    fn from_str(s: &str) -> Self {
+        match s {
+            "user" => TranscriptRole::User,
+            "assistant" => TranscriptRole::Assistant,
+            other => TranscriptRole::Other(other.to_string()),
+        }
+    }
+
+    fn label(&self) -> &str {
+        match self {
+            TranscriptRole::User => "User",
+            TranscriptRole::Assistant => "Assistant",
+            TranscriptRole::Other(s) => s,
+        }
+    }
+}
+
+/// One normalized entry in a parsed `Transcript`, in session order.
+#[derive(Debug, Clone)]
+pub enum TranscriptEntry {
+    Message {
+        role: TranscriptRole,
+        text: String,
+        timestamp: Option<OffsetDateTime>,
+    },
+    ToolCall {
+        name: String,
+        args: serde_json::Value,
+        timestamp: Option<OffsetDateTime>,
+    },
+    ToolResult {
+        name: String,
+        ok: bool,
+        timestamp: Option<OffsetDateTime>,
+    },
+}
+
+/// A session's JSONL file parsed into ordered, typed entries, so titling,
+/// search snippets, and exports can all read one structured model instead
+/// of each poking at `serde_json::Value` themselves.
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    /// Renders the transcript as Markdown suitable for `/export`: one
+    /// paragraph per message, tool calls/results as blockquoted asides.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            match entry {
+                TranscriptEntry::Message { role, text, .. } => {
+                    out.push_str(&format!("**{}:** {text}\n\n", role.label()));
+                }
+                TranscriptEntry::ToolCall { name, args, .. } => {
+                    out.push_str(&format!("> tool call: `{name}` {args}\n\n"));
+                }
+                TranscriptEntry::ToolResult { name, ok, .. } => {
+                    let status = if *ok { "ok" } else { "failed" };
+                    out.push_str(&format!("> tool result: `{name}` {status}\n\n"));
+                }
+            }
+        }
+        out
+    }
+
+    /// Renders the transcript as plain text, for clients that can't render
+    /// Markdown.
+    pub fn to_plain_text(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            match entry {
+                TranscriptEntry::Message { role, text, .. } => {
+                    out.push_str(&format!("{}: {text}\n", role.label()));
+                }
+                TranscriptEntry::ToolCall { name, .. } => {
+                    out.push_str(&format!("[tool call: {name}]\n"));
+                }
+                TranscriptEntry::ToolResult { name, ok, .. } => {
+                    out.push_str(&format!(
+                        "[tool result: {name} {}]\n",
+                        if *ok { "ok" } else { "failed" }
+                    ));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Parses one JSONL line into zero or more `TranscriptEntry` values. Most
+/// lines produce exactly one, but an assistant turn that both writes text
+/// and calls a tool in the same message yields both.
+///
+/// Handles the two entry shapes already in use elsewhere in the crate:
+/// the nested `{"type":"message","message":{"role":...,"content":[...]}}`
+/// shape `crate::pi_runner::SessionEvent` parses for live activity, and the
+/// flatter `{"role":...,"content":...}` shape `get_first_user_message` and
+/// `search_sessions` read.
+fn parse_line(value: &serde_json::Value) -> Vec<TranscriptEntry> {
+    let timestamp = entry_timestamp(value);
+
+    let Some(message) = value.get("message") else {
+        let Some(role) = value.get("role").and_then(|r| r.as_str()) else {
+            return Vec::new();
+        };
+        let Some(text) = value.get("content").and_then(extract_message_text) else {
+            return Vec::new();
+        };
+        return vec![TranscriptEntry::Message {
+            role: TranscriptRole::from_str(role),
+            text,
+            timestamp,
+        }];
+    };
+
+    let role = message.get("role").and_then(|r| r.as_str()).unwrap_or("");
+
+    if role == "toolResult" {
+        let name = message
+            .get("name")
+            .or_else(|| value.get("toolName"))
+            .and_then(|n| n.as_str())
+            .unwrap_or("tool")
+            .to_string();
+        let ok = !message
+            .get("isError")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        return vec![TranscriptEntry::ToolResult { name, ok, timestamp }];
+    }
+
+    let transcript_role = TranscriptRole::from_str(role);
+    let Some(content) = message.get("content") else {
+        return Vec::new();
+    };
+
+    let Some(items) = content.as_array() else {
+        return match extract_message_text(content) {
+            Some(text) => vec![TranscriptEntry::Message {
+                role: transcript_role,
+                text,
+                timestamp,
+            }],
+            None => Vec::new(),
+        };
+    };
+
+    let mut entries = Vec::new();
+    for item in items {
+        match item.get("type").and_then(|t| t.as_str()) {
+            Some("text") => {
+                if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
+                    entries.push(TranscriptEntry::Message {
+                        role: transcript_role.clone(),
+                        text: text.to_string(),
+                        timestamp,
+                    });
+                }
+            }
+            Some("tool_use") | Some("toolCall") => {
+                let name = item
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .unwrap_or("tool")
+                    .to_string();
+                let args = item
+                    .get("input")
+                    .or_else(|| item.get("args"))
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                entries.push(TranscriptEntry::ToolCall {
+                    name,
+                    args,
+                    timestamp,
+                });
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// Parses a session's `.jsonl` file into an ordered `Transcript`. Lines that
+/// fail to parse as JSON, or that parse but match none of the known entry
+/// shapes (e.g. session metadata), are skipped rather than failing the
+/// whole transcript.
+pub async fn parse_transcript(path: &Path) -> Result<Transcript, MiniClawError> {
+    let content = tokio::fs::read_to_string(path).await?;
+    let mut entries = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        entries.extend(parse_line(&value));
+    }
+
+    Ok(Transcript { entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_parse_transcript_flat_shape() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        tokio::fs::write(
+            &path,
+            r#"{"role":"user","content":"hello there"}
+{"role":"assistant","content":[{"type":"text","text":"hi back"}]}"#,
+        )
+        .await
+        .unwrap();
+
+        let transcript = parse_transcript(&path).await.unwrap();
+        assert_eq!(transcript.entries.len(), 2);
+        match &transcript.entries[0] {
+            TranscriptEntry::Message { role, text, .. } => {
+                assert_eq!(*role, TranscriptRole::User);
+                assert_eq!(text, "hello there");
+            }
+            other => panic!("expected Message, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_transcript_nested_shape_with_tool_call() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        tokio::fs::write(
+            &path,
+            r#"{"type":"message","message":{"role":"assistant","content":[{"type":"text","text":"running it"},{"type":"tool_use","name":"bash","input":{"command":"ls"}}]}}
+{"type":"message","message":{"role":"toolResult","name":"bash","isError":false}}"#,
+        )
+        .await
+        .unwrap();
+
+        let transcript = parse_transcript(&path).await.unwrap();
+        assert_eq!(transcript.entries.len(), 3);
+        assert!(matches!(transcript.entries[1], TranscriptEntry::ToolCall { .. }));
+        assert!(matches!(
+            transcript.entries[2],
+            TranscriptEntry::ToolResult { ok: true, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parse_transcript_skips_unrecognized_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        tokio::fs::write(&path, "{\"type\":\"meta\",\"version\":1}\nnot json at all\n").await.unwrap();
+
+        let transcript = parse_transcript(&path).await.unwrap();
+        assert!(transcript.entries.is_empty());
+    }
+
+    #[test]
+    fn test_to_markdown_renders_message_and_tool_call() {
+        let transcript = Transcript {
+            entries: vec![
+                TranscriptEntry::Message {
+                    role: TranscriptRole::User,
+                    text: "hi".to_string(),
+                    timestamp: None,
+                },
+                TranscriptEntry::ToolCall {
+                    name: "bash".to_string(),
+                    args: serde_json::json!({"command": "ls"}),
+                    timestamp: None,
+                },
+            ],
+        };
+        let markdown = transcript.to_markdown();
+        assert!(markdown.contains("**User:** hi"));
+        assert!(markdown.contains("tool call: `bash`"));
+    }
+
+    #[test]
+    fn test_to_plain_text_renders_tool_result() {
+        let transcript = Transcript {
+            entries: vec![TranscriptEntry::ToolResult {
+                name: "bash".to_string(),
+                ok: false,
+                timestamp: None,
+            }],
+        };
+        assert_eq!(transcript.to_plain_text(), "[tool result: bash failed]\n");
+    }
+}
@@ -1,5 +1,10 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_compression::tokio::bufread::GzipDecoder;
+use async_compression::tokio::write::GzipEncoder;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 use crate::error::MiniClawError;
 
@@ -104,6 +109,73 @@ impl WorkspaceManager {
         Ok(resolved)
     }
 
+    /// Tars and gzips the chat's current workspace to a temp file suitable
+    /// for sending back through the bot as a document. `tar::Builder` only
+    /// writes synchronously, so the archive is built off the async runtime
+    /// in [`tokio::task::spawn_blocking`]; the gzip stream itself is written
+    /// out with `async-compression`'s [`GzipEncoder`] (swap in `ZstdEncoder`
+    /// for a `.tar.zst` if a caller ever needs the smaller footprint).
+    pub async fn archive_workspace(&mut self, chat_id: i64) -> Result<PathBuf, MiniClawError> {
+        self.load().await;
+        let source = self.get_workspace(chat_id).await;
+
+        let tar_bytes = tokio::task::spawn_blocking(move || -> std::io::Result<Vec<u8>> {
+            let mut builder = tar::Builder::new(Vec::new());
+            builder.append_dir_all(".", &source)?;
+            builder.into_inner()
+        })
+        .await
+        .map_err(|e| MiniClawError::Workspace(format!("Archive task panicked: {e}")))??;
+
+        let archive_path =
+            std::env::temp_dir().join(format!("mini-claw-workspace-{chat_id}.tar.gz"));
+        let file = tokio::fs::File::create(&archive_path).await?;
+        let mut encoder = GzipEncoder::new(file);
+        encoder.write_all(&tar_bytes).await?;
+        encoder.shutdown().await?;
+
+        Ok(archive_path)
+    }
+
+    /// Extracts an uploaded `.tar.gz` or `.zip` (picked by `archive`'s
+    /// extension) into a new directory under `~/.mini-claw/imports/`, then
+    /// registers it as `chat_id`'s active workspace the same way
+    /// [`set_workspace`](Self::set_workspace) does. Every entry's path is
+    /// normalized and checked against the destination root before being
+    /// written, so a `../`-laden entry can't escape it.
+    pub async fn import_archive(
+        &mut self,
+        chat_id: i64,
+        archive: &Path,
+    ) -> Result<PathBuf, MiniClawError> {
+        self.load().await;
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let dest = home
+            .join(".mini-claw")
+            .join("imports")
+            .join(format!("{chat_id}-{unique}"));
+        tokio::fs::create_dir_all(&dest).await?;
+
+        let is_zip = archive
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+
+        if is_zip {
+            extract_zip(archive, &dest).await?;
+        } else {
+            extract_tar_gz(archive, &dest).await?;
+        }
+
+        self.state.insert(chat_id.to_string(), dest.clone());
+        self.save().await?;
+        Ok(dest)
+    }
+
     pub fn format_path(path: &Path) -> String {
         let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
         let home_str = home.to_string_lossy();
@@ -119,6 +191,99 @@ impl WorkspaceManager {
     }
 }
 
+/// Joins `entry_path` onto `root`, rejecting any component that isn't a
+/// plain path segment (`..`, a Windows prefix, or an absolute root) so a
+/// crafted archive entry can't write outside the destination directory.
+fn sanitize_entry_path(root: &Path, entry_path: &Path) -> Result<PathBuf, MiniClawError> {
+    let mut joined = root.to_path_buf();
+    for component in entry_path.components() {
+        match component {
+            std::path::Component::Normal(part) => joined.push(part),
+            std::path::Component::CurDir => {}
+            _ => {
+                return Err(MiniClawError::Workspace(format!(
+                    "Archive entry escapes target directory: {}",
+                    entry_path.display()
+                )));
+            }
+        }
+    }
+    if !joined.starts_with(root) {
+        return Err(MiniClawError::Workspace(format!(
+            "Archive entry escapes target directory: {}",
+            entry_path.display()
+        )));
+    }
+    Ok(joined)
+}
+
+async fn extract_tar_gz(archive: &Path, dest: &Path) -> Result<(), MiniClawError> {
+    let compressed = tokio::fs::read(archive).await?;
+    let mut decoder = GzipDecoder::new(compressed.as_slice());
+    let mut tar_bytes = Vec::new();
+    decoder
+        .read_to_end(&mut tar_bytes)
+        .await
+        .map_err(|e| MiniClawError::Workspace(format!("Failed to decompress archive: {e}")))?;
+
+    let dest = dest.to_path_buf();
+    tokio::task::spawn_blocking(move || -> Result<(), MiniClawError> {
+        let mut tar_archive = tar::Archive::new(tar_bytes.as_slice());
+        for entry in tar_archive.entries()? {
+            let mut entry = entry?;
+            let entry_path = entry.path()?.into_owned();
+            let target = sanitize_entry_path(&dest, &entry_path)?;
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            entry.unpack(&target)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| MiniClawError::Workspace(format!("Extraction task panicked: {e}")))??;
+
+    Ok(())
+}
+
+async fn extract_zip(archive: &Path, dest: &Path) -> Result<(), MiniClawError> {
+    let archive = archive.to_path_buf();
+    let dest = dest.to_path_buf();
+    tokio::task::spawn_blocking(move || -> Result<(), MiniClawError> {
+        let file = std::fs::File::open(&archive)?;
+        let mut zip = zip::ZipArchive::new(file)
+            .map_err(|e| MiniClawError::Workspace(format!("Invalid zip archive: {e}")))?;
+
+        for i in 0..zip.len() {
+            let mut entry = zip
+                .by_index(i)
+                .map_err(|e| MiniClawError::Workspace(format!("Invalid zip entry: {e}")))?;
+            let Some(entry_path) = entry.enclosed_name().map(|p| p.to_path_buf()) else {
+                return Err(MiniClawError::Workspace(format!(
+                    "Archive entry escapes target directory: {}",
+                    entry.name()
+                )));
+            };
+            let target = sanitize_entry_path(&dest, &entry_path)?;
+
+            if entry.is_dir() {
+                std::fs::create_dir_all(&target)?;
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut out = std::fs::File::create(&target)?;
+            std::io::copy(&mut entry, &mut out)?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| MiniClawError::Workspace(format!("Extraction task panicked: {e}")))??;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Duration;
+
+use crate::config::Config;
+use crate::exec_backend::LocalExecSession;
+use crate::pi_runner::{self, ActivityType, ActivityUpdate, ChatLocks, RunPiOptions};
+
+/// Directory names ignored when coalescing change events, mirroring what a
+/// user would already have in `.gitignore`.
+const IGNORED_DIRS: &[&str] = &[".git", "target", "node_modules"];
+
+fn is_ignored(path: &Path) -> bool {
+    path.components().any(|c| {
+        IGNORED_DIRS
+            .iter()
+            .any(|ignored| c.as_os_str() == *ignored)
+    })
+}
+
+/// One running watch: a `notify` filesystem watcher plus the debounce task
+/// that coalesces its events and reruns Pi, keyed by `chat_id` the same way
+/// `ChatLocks` and `LiveSessionManager` are.
+struct ActiveWatch {
+    stop_tx: mpsc::UnboundedSender<()>,
+}
+
+/// Watches a workspace directory for changes and automatically reruns a
+/// fixed prompt through `run_pi_with_streaming`, one watch per chat_id.
+pub struct WorkspaceWatcher {
+    active: Mutex<HashMap<i64, ActiveWatch>>,
+}
+
+impl WorkspaceWatcher {
+    pub fn new() -> Self {
+        Self {
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts watching `workspace` for `chat_id`, rerunning `prompt` through
+    /// `run_pi_with_streaming` on each settled batch of changes. Replaces any
+    /// existing watch for this chat.
+    pub async fn start<F>(
+        &self,
+        chat_id: i64,
+        config: Arc<Config>,
+        chat_locks: Arc<ChatLocks>,
+        workspace: PathBuf,
+        prompt: String,
+        on_activity: F,
+    ) -> anyhow::Result<()>
+    where
+        F: Fn(ActivityUpdate) + Send + Sync + 'static,
+    {
+        self.stop(chat_id).await;
+
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel::<notify::Event>();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let _ = event_tx.send(event);
+            }
+        })?;
+        watcher.watch(&workspace, RecursiveMode::Recursive)?;
+
+        let (stop_tx, mut stop_rx) = mpsc::unbounded_channel::<()>();
+        let on_activity = Arc::new(on_activity);
+
+        tokio::spawn(async move {
+            // Keep the watcher alive for the lifetime of the task.
+            let _watcher = watcher;
+            let mut changed: HashSet<PathBuf> = HashSet::new();
+
+            loop {
+                tokio::select! {
+                    _ = stop_rx.recv() => break,
+                    maybe_event = event_rx.recv() => {
+                        let Some(event) = maybe_event else { break };
+                        for path in event.paths {
+                            if !is_ignored(&path) {
+                                changed.insert(path);
+                            }
+                        }
+                        if changed.is_empty() {
+                            continue;
+                        }
+                    }
+                }
+
+                // Debounce: wait for a 300ms quiet period, collecting any
+                // further events that arrive in the meantime, exactly like
+                // Deno's --watch debounced resolution loop.
+                loop {
+                    tokio::select! {
+                        _ = stop_rx.recv() => return,
+                        _ = tokio::time::sleep(Duration::from_millis(300)) => break,
+                        maybe_event = event_rx.recv() => {
+                            let Some(event) = maybe_event else { return };
+                            for path in event.paths {
+                                if !is_ignored(&path) {
+                                    changed.insert(path);
+                                }
+                            }
+                        }
+                    }
+                }
+
+                let batch: Vec<PathBuf> = changed.drain().collect();
+                if batch.is_empty() {
+                    continue;
+                }
+
+                let detail = batch
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                on_activity(ActivityUpdate {
+                    activity_type: ActivityType::Working,
+                    detail: format!("changed: {detail}"),
+                    elapsed: 0,
+                });
+
+                // Never overlap a watch-triggered run with a manual one.
+                let _guard = chat_locks.acquire(chat_id).await;
+                let on_activity_run = on_activity.clone();
+                // `notify` can only watch a local directory, so a watch-triggered
+                // run always targets this machine regardless of the chat's active
+                // `/host` — unlike a manually sent message, there's no remote
+                // filesystem here to have diverged from.
+                pi_runner::run_pi_with_streaming(
+                    &LocalExecSession,
+                    &config,
+                    chat_id,
+                    &prompt,
+                    &workspace.to_string_lossy(),
+                    move |update| on_activity_run(update),
+                    None::<RunPiOptions>,
+                )
+                .await;
+            }
+        });
+
+        self.active
+            .lock()
+            .await
+            .insert(chat_id, ActiveWatch { stop_tx });
+        Ok(())
+    }
+
+    pub async fn stop(&self, chat_id: i64) {
+        if let Some(watch) = self.active.lock().await.remove(&chat_id) {
+            let _ = watch.stop_tx.send(());
+        }
+    }
+
+    pub async fn is_watching(&self, chat_id: i64) -> bool {
+        self.active.lock().await.contains_key(&chat_id)
+    }
+}
+
+impl Default for WorkspaceWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_ignored_git_dir() {
+        assert!(is_ignored(Path::new("/workspace/.git/HEAD")));
+    }
+
+    #[test]
+    fn test_is_ignored_target_dir() {
+        assert!(is_ignored(Path::new("/workspace/target/debug/foo")));
+    }
+
+    #[test]
+    fn test_is_ignored_node_modules() {
+        assert!(is_ignored(Path::new("/workspace/node_modules/pkg/index.js")));
+    }
+
+    #[test]
+    fn test_is_ignored_false_for_source_file() {
+        assert!(!is_ignored(Path::new("/workspace/src/main.rs")));
+    }
+
+    #[tokio::test]
+    async fn test_watcher_not_watching_by_default() {
+        let watcher = WorkspaceWatcher::new();
+        assert!(!watcher.is_watching(1).await);
+    }
+
+    #[tokio::test]
+    async fn test_watcher_stop_without_start_is_noop() {
+        let watcher = WorkspaceWatcher::new();
+        watcher.stop(1).await;
+        assert!(!watcher.is_watching(1).await);
+    }
+}